@@ -24,6 +24,7 @@
 //! module.
 
 
+use std::cmp::min;
 use std::fmt;
 use std::os::raw::{c_int, c_uint, c_ulong};
 
@@ -198,6 +199,13 @@ pub struct WindowLayout {
     /// This main reason for choosing this order is that it is easier to
     /// append an element to the end of a vector than it is to prepend one.
     pub windows: Vec<(Window, Geometry)>,
+    /// The visible windows whose border must not have the default width of
+    /// the backend, along with the width their border must have, e.g. 0 for
+    /// a window that must have no border at all. The border is drawn inside
+    /// the geometry of the window.
+    ///
+    /// **Invariant**: every window in `border_widths` must be in `windows`.
+    pub border_widths: Vec<(Window, BorderWidth)>,
 }
 
 impl WindowLayout {
@@ -207,6 +215,7 @@ impl WindowLayout {
         WindowLayout {
             focused_window: None,
             windows: Vec::new(),
+            border_widths: Vec::new(),
         }
     }
 }
@@ -235,11 +244,169 @@ impl PrevOrNext {
     }
 }
 
+/// A direction on the screen: *left*, *right*, *up* or *down*.
+#[derive(Copy, Clone, RustcDecodable, RustcEncodable, Debug, PartialEq, Eq, Hash)]
+pub enum Direction {
+    /// Left
+    Left,
+    /// Right
+    Right,
+    /// Up
+    Up,
+    /// Down
+    Down,
+}
+
+impl Direction {
+    /// Return the opposite of the given direction, i.e. the opposite of
+    /// `Left` is `Right`, the opposite of `Up` is `Down`, and vice versa.
+    pub fn opposite(&self) -> Self {
+        use self::Direction::*;
+        match *self {
+            Left => Right,
+            Right => Left,
+            Up => Down,
+            Down => Up,
+        }
+    }
+}
+
+/// A transformation of the tiles arranged by a tiling layout, which the
+/// user can toggle at run-time, see
+/// [`LayoutSupport`](../wm/trait.LayoutSupport.html).
+#[derive(Copy, Clone, RustcDecodable, RustcEncodable, Debug, PartialEq, Eq, Hash)]
+pub enum LayoutTransform {
+    /// Rotate the tiles by 90 degrees clockwise, e.g. the master column on
+    /// the left becomes a master row on top, with the stack in a row below
+    /// it, from right to left.
+    Rotate,
+    /// Mirror the tiles horizontally, e.g. the master column moves from the
+    /// left to the right side of the screen.
+    MirrorHorizontally,
+    /// Mirror the tiles vertically, e.g. the first tile of a column moves
+    /// from the top to the bottom of the column.
+    MirrorVertically,
+}
+
+impl LayoutTransform {
+    /// Return all the transforms, in the order in which they are applied
+    /// when more than one is toggled on.
+    pub fn all() -> Vec<LayoutTransform> {
+        vec![LayoutTransform::Rotate,
+             LayoutTransform::MirrorHorizontally,
+             LayoutTransform::MirrorVertically]
+    }
+}
+
 /// The size of a gap.
 ///
 /// Note that a gap cannot be negative.
 pub type GapSize = c_uint;
 
+/// The width of the border of a window.
+///
+/// Used in the [`WindowLayout`](struct.WindowLayout.html).
+pub type BorderWidth = c_uint;
+
+/// The space reserved on every side of the screen, in which no tiles are
+/// placed, e.g. for an external status bar. See
+/// [`GapSupport`](../wm/trait.GapSupport.html).
+#[derive(Copy, Clone, RustcDecodable, RustcEncodable, Debug, PartialEq, Eq)]
+pub struct Padding {
+    /// The space reserved at the top of the screen.
+    pub top: GapSize,
+    /// The space reserved at the bottom of the screen.
+    pub bottom: GapSize,
+    /// The space reserved on the left side of the screen.
+    pub left: GapSize,
+    /// The space reserved on the right side of the screen.
+    pub right: GapSize,
+}
+
+impl Padding {
+    /// Return a `Padding` that reserves no space at all.
+    pub fn new() -> Padding {
+        Padding {
+            top: 0,
+            bottom: 0,
+            left: 0,
+            right: 0,
+        }
+    }
+
+    /// Return the given geometry with the padding removed from each of its
+    /// sides. A side with more padding than there is room leaves a geometry
+    /// with a width or height of 0.
+    pub fn shrink(&self, geometry: Geometry) -> Geometry {
+        let left = min(self.left, geometry.width);
+        let top = min(self.top, geometry.height);
+        Geometry {
+            x: geometry.x + left as c_int,
+            y: geometry.y + top as c_int,
+            width: (geometry.width - left).saturating_sub(self.right),
+            height: (geometry.height - top).saturating_sub(self.bottom),
+        }
+    }
+}
+
+/// The fraction of the width of the screen taken by the master tile.
+///
+/// Used by the [`TilingSupport`](../wm/trait.TilingSupport.html) trait.
+pub type MasterRatio = f32;
+
+/// The smallest `MasterRatio` a window manager accepts.
+pub static MIN_MASTER_RATIO: MasterRatio = 0.1;
+
+/// The largest `MasterRatio` a window manager accepts.
+pub static MAX_MASTER_RATIO: MasterRatio = 0.9;
+
+/// The `MasterRatio` a window manager starts with: the master tile takes
+/// half of the screen.
+pub static DEFAULT_MASTER_RATIO: MasterRatio = 0.5;
+
+/// The weight of a tile, determining its share of the column it is stacked
+/// in: a tile with weight 2 is twice as tall as a tile with weight 1 in the
+/// same column. This is what dwm calls the `cfact` of a client.
+///
+/// Used by the [`LayoutSupport`](../wm/trait.LayoutSupport.html) trait.
+pub type TileWeight = f32;
+
+/// The smallest `TileWeight` a window manager accepts.
+pub static MIN_TILE_WEIGHT: TileWeight = 0.25;
+
+/// The largest `TileWeight` a window manager accepts.
+pub static MAX_TILE_WEIGHT: TileWeight = 4.0;
+
+/// The `TileWeight` every tile starts with.
+pub static DEFAULT_TILE_WEIGHT: TileWeight = 1.0;
+
+/// The height of the strip with the tabs that the backend displays above
+/// the tile shown by a layout that has tabs, see
+/// [`Layout`](../wm/trait.Layout.html).
+pub static TAB_STRIP_HEIGHT: c_uint = 20;
+
+/// The parameters of a tiling layout, i.e. the settings that the user can
+/// change at run-time and that a [`Layout`](../wm/trait.Layout.html) must
+/// take into account when arranging the tiles.
+#[derive(Copy, Clone, RustcDecodable, RustcEncodable, Debug, PartialEq)]
+pub struct LayoutParams {
+    /// The fraction of the width of the area taken by the master tile.
+    pub master_ratio: MasterRatio,
+    /// The number of tiles in the master column.
+    pub nmaster: usize,
+}
+
+impl LayoutParams {
+    /// Return the parameters a window manager starts with: the master tile
+    /// takes half of the area and contains a single window.
+    pub fn new() -> LayoutParams {
+        LayoutParams {
+            master_ratio: DEFAULT_MASTER_RATIO,
+            nmaster: 1,
+        }
+    }
+}
+
 /// The type of a workspace index.
 ///
 /// Used by the
@@ -247,8 +414,8 @@ pub type GapSize = c_uint;
 /// indices for workspaces.
 pub type WorkspaceIndex = usize;
 
-/// The highest `WorkspaceIndex`.
+/// The type of a screen index.
 ///
-/// As this is an index (starting from 0), this means there will be
-/// `MAX_WORKSPACE_INDEX + 1` workspaces.
-pub static MAX_WORKSPACE_INDEX: WorkspaceIndex = 3;
+/// Used by the [`MultiScreenSupport`](../wm/trait.MultiScreenSupport.html)
+/// as indices for screens (monitors).
+pub type ScreenIndex = usize;
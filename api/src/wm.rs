@@ -25,7 +25,8 @@ use rustc_serialize::{Decodable, Encodable};
 use std::error;
 use std::fmt::Debug;
 
-use types::{GapSize, Geometry, PrevOrNext, Screen, Window, WindowLayout, WindowWithInfo,
+use types::{Direction, GapSize, Geometry, LayoutParams, LayoutTransform, MasterRatio, Padding,
+            PrevOrNext, Screen, ScreenIndex, TileWeight, Window, WindowLayout, WindowWithInfo,
             WorkspaceIndex};
 
 /// A basic window manager.
@@ -318,6 +319,12 @@ pub trait WindowManager: Encodable + Decodable + Debug + Clone {
 /// we limit ourselves to one simple layout here. In an optional assignment
 /// you will be asked to implement a different layout algorithm.
 ///
+/// The layout algorithm described below is the default one. Other layout
+/// algorithms can be plugged in by implementing the [`Layout`] trait, the
+/// user can then switch between them at run-time using `cycle_layout`.
+///
+/// [`Layout`]: trait.Layout.html
+///
 /// # Layout algorithm
 ///
 /// This window manager follows the following the tiling layout: we start out
@@ -385,6 +392,13 @@ pub trait WindowManager: Encodable + Decodable + Debug + Clone {
 /// window (2) is chosen to be displayed in the master tile. The promoted
 /// window's previous tile disappears.
 ///
+/// By default the master tile takes half of the width of the screen, the
+/// user can make it wider or narrower with `set_master_ratio`.
+///
+/// A window manager can also let the user tune how the tiles are arranged,
+/// e.g. put more than one window in the master column, see
+/// [`LayoutSupport`](trait.LayoutSupport.html).
+///
 /// **Invariant**: at all times there must be as many tiles as there are
 /// visible windows. Note that this will not hold when there are floating
 /// ([`FloatSupport`](trait.FloatSupport.html)) or minimised windows
@@ -400,7 +414,10 @@ pub trait WindowManager: Encodable + Decodable + Debug + Clone {
 pub trait TilingSupport: WindowManager {
     /// Return the window displayed in the master tile.
     ///
-    /// If there are no windows, return `None`.
+    /// If there are no windows, return `None`. When the master column holds
+    /// more than one window, return the topmost one. When `get_nmaster()`
+    /// (see `LayoutSupport`) is 0, there is no master tile, so return `None`
+    /// as well.
     ///
     /// **Invariant**: `get_master_window() == Some(w)`, then `w` must occur
     /// in the vector returned by `get_windows()`.
@@ -419,9 +436,13 @@ pub trait TilingSupport: WindowManager {
     /// If the given window is already in the master tile, no windows have to
     /// be swapped, but the master window should be focused.
     ///
+    /// When the master column holds more than one window, the given window
+    /// is swapped with the topmost one. When `get_nmaster()` is 0, the given
+    /// window is swapped with the first tile, i.e. the tile that would become
+    /// the master tile when `nmaster` is increased again.
     ///
-    /// **Invariant**: if `swap_with_master(w)` succeeds, `get_master_window()
-    /// == Some(w)`.
+    /// **Invariant**: if `swap_with_master(w)` succeeds and `get_nmaster() >
+    /// 0`, `get_master_window() == Some(w)`.
     ///
     /// This function is *allowed* to return an appropriate error when the
     /// window is not managed by the window manager.
@@ -441,6 +462,185 @@ pub trait TilingSupport: WindowManager {
     /// **Invariant**: calling `swap_windows(dir)` and then
     /// `swap_windows(dir.opposite())` will not change the window layout.
     fn swap_windows(&mut self, dir: PrevOrNext);
+
+    /// Return the fraction of the width of the screen taken by the master
+    /// tile. Initially `DEFAULT_MASTER_RATIO`.
+    ///
+    /// **Invariant**: `MIN_MASTER_RATIO <= get_master_ratio() <=
+    /// MAX_MASTER_RATIO`.
+    fn get_master_ratio(&self) -> MasterRatio;
+
+    /// Set the fraction of the width of the screen taken by the master tile.
+    ///
+    /// A ratio outside the range from `MIN_MASTER_RATIO` to
+    /// `MAX_MASTER_RATIO` is clamped to that range, so the master tile and
+    /// the side tiles never disappear.
+    ///
+    /// **Invariant**: after `set_master_ratio(r)` with `r` in the range,
+    /// `get_master_ratio() == r`.
+    fn set_master_ratio(&mut self, ratio: MasterRatio);
+
+    /// Return the tiles to list in the tab strip, in tiling order.
+    ///
+    /// When the current layout only shows some of the tiles (see
+    /// [`Layout`](trait.Layout.html)), the backend displays a strip above
+    /// the shown tile listing the titles of all the tiles. Return an empty
+    /// vector when no strip must be displayed, e.g. when the layout shows all
+    /// the tiles, when there is only one tile, or when a window is
+    /// fullscreen.
+    ///
+    /// **Invariant**: every window in the returned vector occurs in the
+    /// vector returned by `get_windows()`.
+    fn get_tabs(&self) -> Vec<Window>;
+}
+
+/// A tiling window manager with layouts the user can tune.
+///
+/// The tiles are arranged by one of several layout algorithms, see
+/// [`Layout`](trait.Layout.html), and the user can switch between them with
+/// `cycle_layout`.
+///
+/// The master column can also hold more than one window, see `set_nmaster`.
+/// The first `nmaster` tiles then share the master column, each getting the
+/// same height, and the remaining tiles go to the side tiles. When all tiles
+/// fit in the master column, or when `nmaster` is 0, there is only one
+/// column spanning the whole width of the screen.
+///
+/// The tiles in a column do not have to be equally tall: every tile has a
+/// weight, see `set_tile_weight`, and the height of the column is divided
+/// among its tiles proportionally to their weights.
+///
+/// A tiling window manager that doesn't arrange its tiles with a layout,
+/// e.g. one that puts them in a tree built by the user, doesn't implement
+/// this trait.
+pub trait LayoutSupport: TilingSupport {
+    /// Switch to the previous or next layout algorithm.
+    ///
+    /// Only the geometries of the tiles change, the tiles themselves, their
+    /// order and the focused window stay the same. Do nothing when there is
+    /// only one layout to choose from.
+    ///
+    /// The selected layout is part of the state of the window manager, so it
+    /// must survive serialising and deserialising the window manager.
+    ///
+    /// **Invariant**: calling `cycle_layout(dir)` and then
+    /// `cycle_layout(dir.opposite())` will not change the window layout.
+    fn cycle_layout(&mut self, dir: PrevOrNext);
+
+    /// Return the number of windows in the master column. Initially 1.
+    fn get_nmaster(&self) -> usize;
+
+    /// Set the number of windows in the master column.
+    ///
+    /// The number may exceed the number of tiles: all tiles are then
+    /// displayed in the master column. Setting it to 0 removes the master
+    /// column.
+    fn set_nmaster(&mut self, nmaster: usize);
+
+    /// Return the weight of the given window. Initially
+    /// `DEFAULT_TILE_WEIGHT`.
+    ///
+    /// A window that is not tiled, e.g. a floating window, always has the
+    /// default weight.
+    ///
+    /// This function *should* return an appropriate error when the window is
+    /// not managed by the window manager.
+    ///
+    /// **Invariant**: `MIN_TILE_WEIGHT <= get_tile_weight(w) <=
+    /// MAX_TILE_WEIGHT`.
+    fn get_tile_weight(&self, window: Window) -> Result<TileWeight, Self::Error>;
+
+    /// Set the weight of the given tile, which determines its share of the
+    /// column it is stacked in.
+    ///
+    /// A weight outside the range from `MIN_TILE_WEIGHT` to
+    /// `MAX_TILE_WEIGHT` is clamped to that range. Setting the weight of a
+    /// window that is not tiled does nothing. The weight is forgotten when
+    /// the window stops being a tile, e.g. when it is removed, floated or
+    /// minimised.
+    ///
+    /// This function *should* return an appropriate error when the window is
+    /// not managed by the window manager.
+    ///
+    /// **Invariant**: after `set_tile_weight(w, x)` with `w` a tile and `x`
+    /// in the range, `get_tile_weight(w) == Ok(x)`.
+    fn set_tile_weight(&mut self, window: Window, weight: TileWeight) -> Result<(), Self::Error>;
+
+    /// Give all the tiles the `DEFAULT_TILE_WEIGHT` again.
+    fn reset_tile_weights(&mut self);
+
+    /// Return the transforms applied to the tiles of the current layout, in
+    /// the order of `LayoutTransform::all()`. Initially empty.
+    fn get_layout_transforms(&self) -> Vec<LayoutTransform>;
+
+    /// Start applying the given transform to the tiles of the current layout
+    /// when it isn't applied, otherwise stop applying it.
+    ///
+    /// The transforms are applied after the layout has arranged the tiles,
+    /// so they work for every layout, and the transformed tiles must still
+    /// cover the same area. Layouts with tabs (see
+    /// [`Layout`](trait.Layout.html)) are not transformed, the shown tile
+    /// must stay below the tab strip. The transforms are part of the state of the
+    /// window manager, so they must survive serialising and deserialising
+    /// the window manager.
+    ///
+    /// **Invariant**: toggling the same transform twice will not change the
+    /// window layout.
+    fn toggle_layout_transform(&mut self, transform: LayoutTransform);
+}
+
+/// A tiling layout algorithm.
+///
+/// A layout determines where the tiles are placed on the screen. It is given
+/// the tiled windows in their tiling order, the first one being the window in
+/// the master tile, together with the `Geometry` of the area that must be
+/// tiled, which is usually the whole screen. It returns every tiled window
+/// along with the `Geometry` of its tile.
+///
+/// The layout algorithm described in the documentation of
+/// [`TilingSupport`](trait.TilingSupport.html) is one example of a layout.
+///
+/// A layout only computes geometries, it does not know about floating,
+/// minimised or fullscreen windows. These are the responsibility of the
+/// window manager using the layout.
+///
+/// The settings the user can change at run-time, e.g. the master ratio, are
+/// passed to the layout as `LayoutParams`, along with the weight of every
+/// tile. A layout is free to ignore the ones that make no sense for it.
+///
+/// A layout can also show only some of the tiles, e.g. only the focused one.
+/// Such a layout *has tabs*: the tiles that are not shown are hidden, and the
+/// backend displays a strip of `TAB_STRIP_HEIGHT` pixels above the shown
+/// tile, listing all the tiles, see `TilingSupport::get_tabs`.
+///
+/// **Invariant**: every window in the vector returned by `arrange` occurs in
+/// `tiles`, in the same order as in `tiles`. When the layout does not have
+/// tabs, every window in `tiles` occurs exactly once in it.
+pub trait Layout {
+    /// Return a short, human readable name of the layout.
+    fn get_name(&self) -> &'static str;
+
+    /// Return true when the layout only shows some of the tiles, so the
+    /// backend must display the tabs.
+    fn has_tabs(&self) -> bool;
+
+    /// Return the geometry of every shown tile when the given tiles are
+    /// arranged in the given area using the given parameters.
+    ///
+    /// `weights` contains the weight of every tile, in the same order as
+    /// `tiles`, see `TilingSupport::set_tile_weight`.
+    ///
+    /// `focused` is the tile that has the focus, or had it most recently. It
+    /// is `None` when there are no tiles.
+    ///
+    /// When `tiles` is empty, the returned vector must be empty as well.
+    fn arrange(&self,
+               tiles: &[Window],
+               weights: &[TileWeight],
+               focused: Option<Window>,
+               area: Geometry,
+               params: &LayoutParams)
+               -> Vec<(Window, Geometry)>;
 }
 
 /// A window manager that supports floating windows.
@@ -512,6 +712,12 @@ pub trait FloatSupport: WindowManager {
     /// The window layout should reflect the geometry change of the floating
     /// window.
     ///
+    /// The geometry uses the same coordinates as the geometries given to
+    /// `add_window` and returned by `get_window_info`, e.g. relative to the
+    /// screen of the window, see
+    /// [`MultiScreenSupport`](trait.MultiScreenSupport.html). So setting the
+    /// geometry `get_window_info` returns doesn't move the window.
+    ///
     /// This function is *allowed* to return an appropriate error when the
     /// window is not managed by the window manager *or* when the window is
     /// not floating.
@@ -682,22 +888,276 @@ pub trait FullscreenSupport: WindowManager {
 ///
 /// Even when there is only a single tile there should be a gap around it.
 ///
+/// The space between two neighbouring tiles, the *inner gap*, and the space
+/// between a tile and the edge of the screen, the *outer gap*, can also be
+/// set separately. In the example above the outer gap is 5 and the inner gap
+/// is 10. With an outer gap of 5 and an inner gap of 2:
+///
+/// ```
+/// +---------------------+
+/// |+---------++--------+|
+/// ||         ||        ||
+/// ||    1    ||   2    ||
+/// ||         ||        ||
+/// |+---------++--------+|
+/// +---------------------+
+/// ```
+///
+/// Finally, space can be reserved on every side of the screen for external
+/// bars, see `set_padding`. The outer gap is added to the padding. Like the
+/// gaps, the padding does not affect floating windows or the fullscreen
+/// window.
+///
+/// When a single tile is shown, the gaps around it are pointless. With *smart
+/// gaps* on, a single tile gets no gaps at all, only the padding is kept.
+/// Likewise, with *smart borders* on, a single tile is shown without a
+/// border, see `WindowLayout::border_widths`. Both are off initially.
+///
 /// You may ignore scenarios in which the gap size is so large that one of the
 /// windows might become invisible.
 ///
 /// Implementors of this trait must adapt their implementation of the
 /// `get_window_layout()` method accordingly.
 pub trait GapSupport: WindowManager {
-    /// Return the current gap size.
+    /// Return the current gap size, i.e. the outer gap.
     ///
     /// Initially 0.
     fn get_gap(&self) -> GapSize;
 
-    /// Set the gap size.
+    /// Set the gap size: the outer gap becomes `gap` and the inner gap twice
+    /// as much, so every tile is surrounded by `gap` pixels of empty space.
     ///
     /// **Invariant**: after setting `set_gap(g)` with some gap size `g`,
     /// `get_gap() == g`.
     fn set_gap(&mut self, GapSize);
+
+    /// Return the space between two neighbouring tiles.
+    ///
+    /// Initially 0.
+    fn get_inner_gap(&self) -> GapSize;
+
+    /// Set the space between two neighbouring tiles.
+    ///
+    /// **Invariant**: after `set_inner_gap(g)`, `get_inner_gap() == g`.
+    fn set_inner_gap(&mut self, gap: GapSize);
+
+    /// Return the space between the tiles and the edges of the screen. When
+    /// there is padding, the outer gap comes on top of it.
+    ///
+    /// Initially 0.
+    fn get_outer_gap(&self) -> GapSize;
+
+    /// Set the space between the tiles and the edges of the screen.
+    ///
+    /// **Invariant**: after `set_outer_gap(g)`, `get_outer_gap() == g`.
+    fn set_outer_gap(&mut self, gap: GapSize);
+
+    /// Return the space reserved on every side of the screen.
+    ///
+    /// Initially `Padding::new()`, i.e. no space is reserved.
+    fn get_padding(&self) -> Padding;
+
+    /// Set the space reserved on every side of the screen, in which no tiles
+    /// are placed, e.g. for an external status bar.
+    ///
+    /// **Invariant**: after `set_padding(p)`, `get_padding() == p`.
+    fn set_padding(&mut self, padding: Padding);
+
+    /// Return true when a single tile gets no gaps.
+    ///
+    /// Initially false.
+    fn get_smart_gaps(&self) -> bool;
+
+    /// Turn smart gaps on or off.
+    ///
+    /// **Invariant**: after `set_smart_gaps(b)`, `get_smart_gaps() == b`.
+    fn set_smart_gaps(&mut self, smart_gaps: bool);
+
+    /// Return true when a single tile gets no border.
+    ///
+    /// Initially false.
+    fn get_smart_borders(&self) -> bool;
+
+    /// Turn smart borders on or off.
+    ///
+    /// **Invariant**: after `set_smart_borders(b)`, `get_smart_borders() ==
+    /// b`.
+    fn set_smart_borders(&mut self, smart_borders: bool);
+}
+
+
+/// A window manager that can move the focus and the windows around in two
+/// dimensions.
+///
+/// `cycle_focus` and `swap_windows` walk a linear list of windows, which
+/// is not always what the user sees on the screen. The methods of this trait
+/// use the geometries of the windows in `get_window_layout()` instead: they
+/// act on the nearest visible window in the given direction. Floating
+/// windows are visible too, so they take part as well.
+///
+/// For example, in this layout, moving the focus down from window 2 focuses
+/// window 3, moving it to the left from window 3 focuses window 1:
+///
+/// ```plain
+/// +----------+----------+
+/// |          |    2     |
+/// |          |          |
+/// |    1     +----------+
+/// |          |    3     |
+/// |          |          |
+/// +----------+----------+
+/// ```
+pub trait DirectionalSupport: WindowManager {
+    /// Focus the nearest visible window in the given direction of the
+    /// focused window.
+    ///
+    /// Does nothing when no window is focused or when there is no visible
+    /// window in that direction.
+    fn focus_direction(&mut self, direction: Direction);
+
+    /// Swap the focused window with the nearest visible window in the given
+    /// direction. The focused window stays focused.
+    ///
+    /// Two tiles swap their places in the layout, two floating windows swap
+    /// their geometries. A tile and a floating window are not swapped.
+    ///
+    /// Does nothing when no window is focused or when there is no visible
+    /// window in that direction.
+    fn swap_direction(&mut self, direction: Direction);
+}
+
+
+/// A window manager that remembers the order in which the windows were
+/// focused, the *focus history*.
+///
+/// `cycle_focus` goes round-robin through the windows. With the focus history,
+/// the user can instead go back to the previously focused window, like
+/// Alt-Tab does in most desktop environments. The focus history also decides
+/// which window gets the focus when the focused window is removed or
+/// minimised: the most recently focused one that is still visible.
+///
+/// To walk further back in the history, e.g. while the user holds Alt and
+/// presses Tab a number of times, the history can be *frozen*: focusing a
+/// window then doesn't change the history, so the windows can be visited in
+/// their original order. Unfreezing the history commits the focused window
+/// as the most recently focused one.
+pub trait FocusHistorySupport: WindowManager {
+    /// Return the managed windows, the most recently focused one first.
+    ///
+    /// **Invariant**: the focused window, if any, is the first one, unless
+    /// the history is frozen.
+    fn get_focus_history(&self) -> Vec<Window>;
+
+    /// Focus the next, i.e. less recently focused, or the previous window in
+    /// the focus history, starting from the focused window.
+    ///
+    /// When no window is focused, focus the most recently focused one.
+    ///
+    /// Unless the history is frozen, `cycle_focus_history(PrevOrNext::Next)`
+    /// just toggles between the two most recently focused windows.
+    fn cycle_focus_history(&mut self, dir: PrevOrNext) {
+        let history = self.get_focus_history();
+        if history.is_empty() {
+            return;
+        }
+        let len = history.len();
+        let position = self.get_focused_window()
+            .and_then(|focused| history.iter().position(|w| *w == focused));
+        let i = match (position, dir) {
+            (None, _) => 0,
+            (Some(i), PrevOrNext::Next) => (i + 1) % len,
+            (Some(i), PrevOrNext::Prev) => (i + len - 1) % len,
+        };
+        // The window is managed, so this can't fail
+        let _ = self.focus_window(Some(history[i]));
+    }
+
+    /// Return true when the focus history is frozen.
+    ///
+    /// Initially false.
+    fn is_focus_history_frozen(&self) -> bool;
+
+    /// Freeze or unfreeze the focus history. When unfreezing, the focused
+    /// window becomes the most recently focused one.
+    ///
+    /// **Invariant**: after `set_focus_history_frozen(b)`,
+    /// `is_focus_history_frozen() == b`.
+    fn set_focus_history_frozen(&mut self, frozen: bool);
+}
+
+
+/// A window manager that keeps track of the windows that demand the
+/// attention of the user, the *urgent* windows.
+///
+/// An application can mark its window as urgent, e.g. a chat window when a
+/// message arrives, or a terminal when a build finishes. The backend shows
+/// urgent windows differently, and the user can jump to them with
+/// `focus_urgent_window`, even when they are on another workspace.
+///
+/// Focusing a window clears its urgency.
+pub trait UrgencySupport: WindowManager {
+    /// Return the urgent windows, in the order in which they became urgent.
+    fn get_urgent_windows(&self) -> Vec<Window>;
+
+    /// Return true when the given window is urgent.
+    ///
+    /// Return false when the window is not managed.
+    fn is_urgent(&self, window: Window) -> bool {
+        self.get_urgent_windows().contains(&window)
+    }
+
+    /// Mark the given window as urgent, or as no longer urgent.
+    ///
+    /// This function *should* return an appropriate error when the window is
+    /// not managed by the window manager.
+    ///
+    /// **Invariant**: after `set_urgent(w, b)` with a managed window `w`,
+    /// `is_urgent(w) == b`.
+    fn set_urgent(&mut self, window: Window, urgent: bool) -> Result<(), Self::Error>;
+
+    /// Focus the window that became urgent first, which clears its urgency.
+    ///
+    /// Does nothing when there are no urgent windows.
+    fn focus_urgent_window(&mut self) -> Result<(), Self::Error> {
+        match self.get_urgent_windows().first() {
+            Some(window) => self.focus_window(Some(*window)),
+            None => Ok(()),
+        }
+    }
+}
+
+
+/// A window manager with a scratchpad: a floating window that belongs to no
+/// workspace and stays hidden until the user summons it.
+///
+/// Typically the scratchpad is a terminal for a quick command. Toggling the
+/// scratchpad shows it centred and floating on top of the current workspace
+/// and focuses it, toggling it again hides it. A hidden scratchpad is still
+/// managed, so it is included in `get_windows`, but not in the window
+/// layout.
+pub trait ScratchpadSupport: WindowManager {
+    /// Return the scratchpad window, shown or hidden, if there is one.
+    fn get_scratchpad_window(&self) -> Option<Window>;
+
+    /// Return true when the scratchpad is shown on the current workspace.
+    fn is_scratchpad_shown(&self) -> bool;
+
+    /// Make the given window the scratchpad and show it.
+    ///
+    /// The window is taken off its workspace. A previous scratchpad window
+    /// becomes an ordinary floating window on the current workspace.
+    ///
+    /// This function *should* return an appropriate error when the window is
+    /// not managed by the window manager.
+    ///
+    /// **Invariant**: after `set_scratchpad_window(w)` with a managed window
+    /// `w`, `get_scratchpad_window() == Some(w)` and `is_scratchpad_shown()`.
+    fn set_scratchpad_window(&mut self, window: Window) -> Result<(), Self::Error>;
+
+    /// Show the scratchpad when it is hidden, hide it when it is shown.
+    ///
+    /// Does nothing when there is no scratchpad window.
+    fn toggle_scratchpad(&mut self);
 }
 
 
@@ -706,19 +1166,23 @@ pub trait GapSupport: WindowManager {
 /// For a small introduction to workspaces, see the first section of the
 /// assignment.
 ///
-/// The idea is that there are a number of different workspaces
-/// ([`MAX_WORKSPACE_INDEX`] + 1), each represented by a different copy of a
-/// single type of window manager. Initially the first (index 0) workspace is
-/// active. The user interacts with this workspace's window manager, e.g.,
-/// adding/removing windows, focusing a window, etc. When the user then
-/// switches to another workspace, all the windows will be hidden. The user
-/// can then interact with this workspace as if it is a new one. When the user
-/// switches back to the first workspace, the windows previously opened in
-/// this workspace are shown again. When the user switches back to the other
-/// workspace, the windows previously opened in this workspace are shown
-/// again.
+/// The idea is that there are a number of different workspaces, each
+/// represented by a different copy of a single type of window manager.
+/// Initially there is only one workspace (index 0), and it is active. The
+/// user interacts with this workspace's window manager, e.g., adding/removing
+/// windows, focusing a window, etc. When the user then switches to another
+/// workspace, all the windows will be hidden. The user can then interact with
+/// this workspace as if it is a new one. When the user switches back to the
+/// first workspace, the windows previously opened in this workspace are shown
+/// again. When the user switches back to the other workspace, the windows
+/// previously opened in this workspace are shown again.
 ///
-/// [`MAX_WORKSPACE_INDEX`]: ../types/static.MAX_WORKSPACE_INDEX.html
+/// Workspaces are created on demand and can be deleted when they are no
+/// longer needed, so there is no fixed number of them. Every workspace has a
+/// name that is shown to the user, e.g. `"web"` or `"thesis"`, and that is
+/// unique among the workspaces. The indices of the workspaces are `0 <= index
+/// < get_workspace_count()`; deleting a workspace shifts the indices of the
+/// workspaces after it down by one.
 ///
 /// This is useful when multitasking: one workspace is for surfing the web,
 /// another for working on a CPL assignment, a third one for working on your
@@ -737,31 +1201,321 @@ pub trait MultiWorkspaceSupport<WM: WindowManager>: WindowManager {
     ///
     /// When creating a new workspace this will return 0.
     ///
-    /// **Invariant**: `0 <= get_current_workspace_index() <=
-    /// MAX_WORKSPACE_INDEX`.
+    /// **Invariant**: `0 <= get_current_workspace_index() <
+    /// get_workspace_count()`.
     fn get_current_workspace_index(&self) -> WorkspaceIndex;
 
+    /// Return the number of workspaces.
+    ///
+    /// **Invariant**: there is always at least one workspace.
+    fn get_workspace_count(&self) -> usize;
+
     /// Get an immutable borrow of the workspace at the given index.
     ///
-    /// This function *should* return an appropriate error when `0 <= index <=
-    /// MAX_WORKSPACE_INDEX` is not true.
+    /// This function *should* return an appropriate error when `0 <= index <
+    /// get_workspace_count()` is not true.
     fn get_workspace(&self, index: WorkspaceIndex) -> Result<&WM, Self::Error>;
 
     /// Get a mutable borrow of the workspace at the given index.
     ///
-    /// This function *should* return an appropriate error when `0 <= index <=
-    /// MAX_WORKSPACE_INDEX` is not true.
+    /// This function *should* return an appropriate error when `0 <= index <
+    /// get_workspace_count()` is not true.
     fn get_workspace_mut(&mut self, index: WorkspaceIndex) -> Result<&mut WM, Self::Error>;
 
     /// Switch to the workspace at the given index.
     ///
-    /// If `index == get_current_workspace_index()`, do nothing.
+    /// If `index == get_current_workspace_index()`, do nothing. Otherwise the
+    /// workspace that was current becomes the previous one, see
+    /// `get_previous_workspace_index`.
     ///
     /// **Invariant**: the window layout after switching to another workspace
     /// and then switching back to the original workspace should be the same
     /// as before.
     ///
-    /// This function *should* return an appropriate error when `0 <= index <=
-    /// MAX_WORKSPACE_INDEX` is not true.
+    /// This function *should* return an appropriate error when `0 <= index <
+    /// get_workspace_count()` is not true.
     fn switch_workspace(&mut self, index: WorkspaceIndex) -> Result<(), Self::Error>;
+
+    /// Return the index of the workspace that was current before the last
+    /// switch to another workspace, or `None` when there was no switch yet, or
+    /// when that workspace has been deleted since.
+    ///
+    /// When creating a new window manager this will return `None`.
+    ///
+    /// **Invariant**: `get_previous_workspace_index() !=
+    /// Some(get_current_workspace_index())`.
+    fn get_previous_workspace_index(&self) -> Option<WorkspaceIndex>;
+
+    /// Switch back to the previous workspace, see
+    /// `get_previous_workspace_index`. Doing this twice returns to the
+    /// current workspace, so the user can go back and forth between two
+    /// workspaces.
+    ///
+    /// Does nothing when there is no previous workspace.
+    ///
+    /// The default implementation uses `get_previous_workspace_index` and
+    /// `switch_workspace`.
+    fn switch_to_previous_workspace(&mut self) -> Result<(), Self::Error> {
+        match self.get_previous_workspace_index() {
+            Some(index) => self.switch_workspace(index),
+            None => Ok(()),
+        }
+    }
+
+    /// Switch to the next or previous workspace, in the order of their
+    /// indices. After the last workspace comes the first one, and vice versa.
+    ///
+    /// When `skip_empty` is `true`, workspaces without windows are skipped.
+    /// Does nothing when there is no other (non-empty) workspace.
+    ///
+    /// The default implementation uses `get_workspace` and
+    /// `switch_workspace`.
+    fn cycle_workspace(&mut self, dir: PrevOrNext, skip_empty: bool) -> Result<(), Self::Error> {
+        let count = self.get_workspace_count();
+        let current = self.get_current_workspace_index();
+        for step in 1..count {
+            let index = match dir {
+                PrevOrNext::Next => (current + step) % count,
+                PrevOrNext::Prev => (current + count - step) % count,
+            };
+            if !skip_empty || !try!(self.get_workspace(index)).get_windows().is_empty() {
+                return self.switch_workspace(index);
+            }
+        }
+        Ok(())
+    }
+
+    /// Add a window to the workspace at the given index, without switching to
+    /// it.
+    ///
+    /// This is how a window is opened on another workspace than the current
+    /// one, e.g. a chat client on its own workspace.
+    ///
+    /// This function *should* return an appropriate error when `0 <= index <
+    /// get_workspace_count()` is not true, or when the window is already
+    /// managed by the window manager.
+    ///
+    /// **Invariant**: after `add_window_to_workspace(index, info)`,
+    /// `is_managed(info.window)` and `get_current_workspace_index()` is
+    /// unchanged.
+    fn add_window_to_workspace(&mut self,
+                               index: WorkspaceIndex,
+                               window_with_info: WindowWithInfo)
+                               -> Result<(), Self::Error>;
+
+    /// Move the window to the workspace at the given index.
+    ///
+    /// The window keeps its state: whether it floats or is tiled, its
+    /// floating geometry, the weight of its tile, and whether it is
+    /// fullscreen or minimised. When `follow` is `true`, the workspace becomes
+    /// the current one and the window is focused, unless it is minimised.
+    /// Otherwise the current workspace stays the current one. Moving a window
+    /// to the workspace that contains it doesn't change the window.
+    ///
+    /// This function *should* return an appropriate error when the window is
+    /// not managed by the window manager, or when `0 <= index <
+    /// get_workspace_count()` is not true.
+    ///
+    /// **Invariant**: after `move_window_to_workspace(window, index,
+    /// follow)`, `is_managed(window)` and the workspace that contains the
+    /// window is the workspace that was at the given index.
+    fn move_window_to_workspace(&mut self,
+                                window: Window,
+                                index: WorkspaceIndex,
+                                follow: bool)
+                                -> Result<(), Self::Error>;
+
+    /// Return the index of the workspace that contains the given window, or
+    /// `None` when no workspace contains it, e.g. when the window isn't
+    /// managed.
+    ///
+    /// The default implementation asks every workspace whether it manages the
+    /// window.
+    fn get_window_workspace(&self, window: Window) -> Option<WorkspaceIndex> {
+        (0..self.get_workspace_count()).find(|&index| {
+            self.get_workspace(index).ok().map_or(false, |workspace| workspace.is_managed(window))
+        })
+    }
+
+    /// Return the name of the workspace at the given index.
+    ///
+    /// This function *should* return an appropriate error when `0 <= index <
+    /// get_workspace_count()` is not true.
+    fn get_workspace_name(&self, index: WorkspaceIndex) -> Result<String, Self::Error>;
+
+    /// Return the index of the workspace with the given name, or `None` when
+    /// there is no such workspace.
+    ///
+    /// The default implementation compares the name with the names of all
+    /// workspaces.
+    fn find_workspace(&self, name: &str) -> Option<WorkspaceIndex> {
+        (0..self.get_workspace_count())
+            .find(|&index| self.get_workspace_name(index).ok().map_or(false, |n| n == name))
+    }
+
+    /// Create a new, empty workspace with the given name after the existing
+    /// ones, without switching to it, and return its index.
+    ///
+    /// A workspace name must not be empty, nor contain a NUL character, as
+    /// NUL separates the names when the backend publishes them.
+    ///
+    /// This function *should* return an appropriate error when the name is
+    /// not valid or when a workspace with this name already exists.
+    ///
+    /// **Invariant**: after `create_workspace(name)` returns `Ok(index)`,
+    /// `find_workspace(name) == Some(index)` and `index ==
+    /// get_workspace_count() - 1`.
+    fn create_workspace(&mut self, name: &str) -> Result<WorkspaceIndex, Self::Error>;
+
+    /// Return the index of the workspace with the given name, create it
+    /// first when there is no such workspace.
+    ///
+    /// This lets the user bind keys to workspaces by name, the workspace is
+    /// only created when it is used for the first time.
+    ///
+    /// The default implementation uses `find_workspace` and
+    /// `create_workspace`.
+    fn get_or_create_workspace(&mut self, name: &str) -> Result<WorkspaceIndex, Self::Error> {
+        match self.find_workspace(name) {
+            Some(index) => Ok(index),
+            None => self.create_workspace(name),
+        }
+    }
+
+    /// Give the workspace at the given index another name.
+    ///
+    /// Renaming a workspace to its own name does nothing.
+    ///
+    /// This function *should* return an appropriate error when `0 <= index <
+    /// get_workspace_count()` is not true, when the name is not valid (see
+    /// `create_workspace`), or when another workspace already has this name.
+    fn rename_workspace(&mut self, index: WorkspaceIndex, name: &str) -> Result<(), Self::Error>;
+
+    /// Delete the workspace at the given index.
+    ///
+    /// Only an empty workspace that isn't the current one can be deleted, so
+    /// no windows are lost and there is always a current workspace. The
+    /// indices of the workspaces after the deleted one, including the current
+    /// one, shift down by one.
+    ///
+    /// This function *should* return an appropriate error when `0 <= index <
+    /// get_workspace_count()` is not true, when the workspace still contains
+    /// windows, or when it is the current workspace.
+    fn delete_workspace(&mut self, index: WorkspaceIndex) -> Result<(), Self::Error>;
+
+    /// Return whether workspaces are deleted automatically as soon as they
+    /// are empty.
+    ///
+    /// When creating a new window manager this will return `false`.
+    fn get_delete_empty_workspaces(&self) -> bool;
+
+    /// Set whether workspaces are deleted automatically as soon as they are
+    /// empty.
+    ///
+    /// A workspace is deleted at the end of the operation that leaves it
+    /// empty and hidden: removing or moving its last window, or switching
+    /// away from it while it is empty. A workspace shown on a screen, like
+    /// the current one, is never deleted. A new workspace is only deleted
+    /// once it was used, so `create_workspace` followed by e.g.
+    /// `move_window_to_workspace` works, and the indices given to an
+    /// operation stay valid while it runs. Enabling this deletes the empty
+    /// workspaces that aren't shown.
+    fn set_delete_empty_workspaces(&mut self, delete_empty: bool);
+}
+
+
+/// A window manager that shows its workspaces on multiple screens (monitors).
+///
+/// This follows the model of xmonad: every screen shows one workspace, and a
+/// workspace is shown on at most one screen. The current workspace is shown
+/// on the *current screen*, the workspaces that aren't shown on a screen are
+/// hidden. Switching to a workspace that is shown on another screen makes
+/// that screen the current one. Switching to a hidden workspace shows it on
+/// the current screen instead of the workspace that was shown there.
+///
+/// The screens have a position as well as a size, in the coordinates of the
+/// root window, e.g. a second monitor to the right of a first one of 1280x1024
+/// has `x == 1280`. The window layout contains the windows of all the shown
+/// workspaces, each moved to the position of its screen. Within a workspace,
+/// the geometries of floating windows are relative to the screen it is shown
+/// on, so a window keeps its place on the screen when it is moved to another
+/// one. This holds for `add_window`, `get_window_info` and
+/// `set_window_geometry` alike, only the window layout uses the coordinates
+/// of the root window.
+///
+/// `resize_screen(screen)` replaces the screens with a single screen of that
+/// size.
+pub trait MultiScreenSupport<WM: WindowManager>: MultiWorkspaceSupport<WM> {
+    /// Return the geometries of the screens.
+    ///
+    /// **Invariant**: there is always at least one screen.
+    fn get_screens(&self) -> Vec<Geometry>;
+
+    /// Replace the screens, e.g. when a monitor was plugged in.
+    ///
+    /// The first screens keep showing their workspace. Every new screen
+    /// shows a hidden workspace, a new one is created when there is none.
+    /// The workspaces of the screens that are gone become hidden, their
+    /// windows stay on them. When the current screen is gone, the first
+    /// screen becomes the current one. Floating windows that stick out of
+    /// their screen, e.g. because it became smaller or was rotated, are moved
+    /// back onto it.
+    ///
+    /// This function *should* return an appropriate error when `screens` is
+    /// empty.
+    fn set_screens(&mut self, screens: Vec<Geometry>) -> Result<(), Self::Error>;
+
+    /// Return the index of the current screen, the screen that shows the
+    /// current workspace.
+    ///
+    /// **Invariant**: `0 <= get_current_screen_index() <
+    /// get_screens().len()`.
+    fn get_current_screen_index(&self) -> ScreenIndex;
+
+    /// Return the index of the workspace shown on the given screen.
+    ///
+    /// This function *should* return an appropriate error when `0 <= screen <
+    /// get_screens().len()` is not true.
+    fn get_screen_workspace(&self, screen: ScreenIndex) -> Result<WorkspaceIndex, Self::Error>;
+
+    /// Return the index of the screen that shows the workspace at the given
+    /// index, or `None` when the workspace is hidden.
+    ///
+    /// The default implementation uses `get_screen_workspace` for every
+    /// screen.
+    fn get_workspace_screen(&self, index: WorkspaceIndex) -> Option<ScreenIndex> {
+        (0..self.get_screens().len())
+            .find(|&screen| self.get_screen_workspace(screen).ok() == Some(index))
+    }
+
+    /// Make the given screen the current one, its workspace becomes the
+    /// current workspace.
+    ///
+    /// This function *should* return an appropriate error when `0 <= screen <
+    /// get_screens().len()` is not true.
+    ///
+    /// The default implementation switches to the workspace of the screen.
+    fn focus_screen(&mut self, screen: ScreenIndex) -> Result<(), Self::Error> {
+        let index = try!(self.get_screen_workspace(screen));
+        self.switch_workspace(index)
+    }
+
+    /// Move the window to the workspace shown on the given screen, see
+    /// `move_window_to_workspace`. When `follow` is `true`, that screen
+    /// becomes the current one.
+    ///
+    /// This function *should* return an appropriate error when the window is
+    /// not managed by the window manager, or when `0 <= screen <
+    /// get_screens().len()` is not true.
+    ///
+    /// The default implementation uses `get_screen_workspace` and
+    /// `move_window_to_workspace`.
+    fn move_window_to_screen(&mut self,
+                             window: Window,
+                             screen: ScreenIndex,
+                             follow: bool)
+                             -> Result<(), Self::Error> {
+        let index = try!(self.get_screen_workspace(screen));
+        self.move_window_to_workspace(window, index, follow)
+    }
 }
@@ -153,6 +153,7 @@ impl WindowManager for FullscreenWM {
                     // ... and should fill the screen. The other windows are
                     // simply hidden.
                     windows: vec![(*w, fullscreen_geometry)],
+                    border_widths: Vec::new(),
                 }
             }
             // Otherwise, return an empty WindowLayout
@@ -19,7 +19,9 @@
 //!
 
 use cplwm_api::types::*;
-use cplwm_api::wm::{TilingSupport, WindowManager};
+use cplwm_api::wm::{DirectionalSupport, FocusHistorySupport, Layout, LayoutSupport, TilingSupport,
+                    UrgencySupport, WindowManager};
+use layout::{self, TilingLayout};
 use wm_error::WMError;
 
 use std::collections::{HashMap, VecDeque};
@@ -41,6 +43,113 @@ pub struct TilingWM {
     pub screen: Screen,
     /// Boolean variable to indicate if there is a focused window
     pub is_focus: bool,
+    /// The current layout. Only this one is stored, the layouts to cycle through are
+    /// `TilingLayout::all()`, so a restart picks up the layouts of the new version.
+    pub layout: TilingLayout,
+    /// The parameters passed to the current layout, e.g. the master ratio
+    pub layout_params: LayoutParams,
+    /// The weights set for the tiles, the other tiles have the
+    /// `DEFAULT_TILE_WEIGHT`
+    pub weights: HashMap<Window, TileWeight>,
+    /// The transforms applied to the tiles arranged by the current layout
+    pub transforms: Vec<LayoutTransform>,
+    /// All the windows in the order in which they were focused, the most recently focused one
+    /// is on the back
+    pub focus_history: Vec<Window>,
+    /// Whether focusing a window leaves the `focus_history` unchanged
+    pub focus_history_frozen: bool,
+    /// The urgent windows, in the order in which they became urgent
+    pub urgent: Vec<Window>,
+}
+
+impl TilingWM {
+    /// Return the layout currently used to tile the windows.
+    pub fn get_layout(&self) -> TilingLayout {
+        self.layout
+    }
+
+    /// Arrange the tiles in the given area with the current layout, and transform them by the
+    /// `transforms`, unless the layout has tabs.
+    pub fn arrange_tiles(&self, area: Geometry) -> Vec<(Window, Geometry)> {
+        let tiles: Vec<Window> = self.tiles.iter().map(|t| *t).collect();
+        let weights: Vec<TileWeight> = tiles.iter()
+            .map(|t| *self.weights.get(t).unwrap_or(&DEFAULT_TILE_WEIGHT))
+            .collect();
+        let windows = self.get_layout().arrange(&tiles,
+                                                &weights,
+                                                self.get_last_focused_tile(),
+                                                area,
+                                                &self.layout_params);
+        if self.get_layout().has_tabs() {
+            windows
+        } else {
+            layout::transform(windows, &self.transforms, area)
+        }
+    }
+
+    /// Return the tile that has the focus, or had it most recently.
+    pub fn get_last_focused_tile(&self) -> Option<Window> {
+        self.windows.iter().rev().find(|w| self.tiles.contains(w)).map(|w| *w)
+    }
+
+    /// Move the focused window to the back of the `focus_history`, unless it is frozen. The
+    /// focused window is no longer urgent.
+    pub fn record_focus(&mut self) {
+        if let Some(window) = self.get_focused_window() {
+            self.urgent.retain(|w| *w != window);
+        }
+        if self.focus_history_frozen {
+            return;
+        }
+        if let Some(window) = self.get_focused_window() {
+            self.focus_history.retain(|w| *w != window);
+            self.focus_history.push(window);
+        }
+    }
+
+    /// Move the most recently focused window to the back of `windows`, so it has the focus when
+    /// there is any.
+    fn restore_most_recent_focus(&mut self) {
+        if let Some(window) = self.focus_history.last().map(|w| *w) {
+            if let Some(i) = self.windows.iter().position(|w| *w == window) {
+                let w = self.windows.remove(i).unwrap();
+                self.windows.push_back(w);
+            }
+        }
+    }
+
+    /// Swap the places of the two given tiles. Do nothing when one of them is not a tile.
+    pub fn swap_tiles(&mut self, a: Window, b: Window) {
+        let i = self.tiles.iter().position(|t| *t == a);
+        let j = self.tiles.iter().position(|t| *t == b);
+        if let (Some(i), Some(j)) = (i, j) {
+            self.tiles.swap(i, j);
+        }
+    }
+
+    /// Return the `WindowWithInfo` stored for the given window, i.e. the one it was added with,
+    /// as changed by the wrapping window managers since.
+    pub fn get_stored_info(&self, window: Window) -> Option<WindowWithInfo> {
+        self.windows_info.get(&window).map(|info| *info)
+    }
+
+    /// Return the `WindowWithInfo` stored for the given window, so a wrapping window manager
+    /// can change it, e.g. when the window starts floating.
+    pub fn get_stored_info_mut(&mut self, window: Window) -> Option<&mut WindowWithInfo> {
+        self.windows_info.get_mut(&window)
+    }
+
+    /// Add the given managed window to the back of the tiles, e.g. when it stops floating.
+    pub fn add_tile(&mut self, window: Window) {
+        self.tiles.push_back(window);
+    }
+
+    /// Remove the given window from the tiles, together with its weight, e.g. when it starts
+    /// floating. Do nothing when it is not a tile.
+    pub fn remove_tile(&mut self, window: Window) {
+        self.tiles.retain(|t| *t != window);
+        self.weights.remove(&window);
+    }
 }
 
 // Now we start implementing our window manager
@@ -57,6 +166,13 @@ impl WindowManager for TilingWM {
             tiles: VecDeque::new(),
             screen: screen,
             is_focus: false,
+            layout: TilingLayout::all()[0],
+            layout_params: LayoutParams::new(),
+            weights: HashMap::new(),
+            transforms: Vec::new(),
+            focus_history: Vec::new(),
+            focus_history_frozen: false,
+            urgent: Vec::new(),
         }
     }
 
@@ -82,7 +198,8 @@ impl WindowManager for TilingWM {
     }
 
     /// To add a window, just push it onto the end the `windows` `VecDeque`.
-    /// Add the window also in the tiles `VecDeque`, and the WindowWithInfo in the `HashMap`.
+    /// Add the window also in the tiles `VecDeque`, the WindowWithInfo in the `HashMap`, and as
+    /// the most recently focused window in the `focus_history`.
     ///
     /// The function returns an error if the window is already managed by the window manager.
     fn add_window(&mut self, window_with_info: WindowWithInfo) -> Result<(), Self::Error> {
@@ -94,6 +211,7 @@ impl WindowManager for TilingWM {
             if window_with_info.float_or_tile == FloatOrTile::Tile {
                 self.tiles.push_back(window_with_info.window);
             }
+            self.focus_history.push(window_with_info.window);
             self.is_focus = true;
             Ok(())
         } else {
@@ -102,19 +220,32 @@ impl WindowManager for TilingWM {
     }
 
     /// To remove a window, remove it from the `windows` `VecDeque`.
-    /// Remove also the window from `tiles`, and it's associated `WindowWithInfo`.
+    /// Remove also the window from `tiles`, the `focus_history` and `urgent`, and it's
+    /// associated `WindowWithInfo` and weight.
+    ///
+    /// When the removed window had the focus, the most recently focused window gets it.
     fn remove_window(&mut self, window: Window) -> Result<(), Self::Error> {
         match self.windows.iter().position(|w| *w == window) {
             None => Err(WMError::UnknownWindow(window)),
             Some(i) => {
+                self.weights.remove(&window);
+                self.focus_history.retain(|w| *w != window);
+                self.urgent.retain(|w| *w != window);
+                let was_last = i == self.windows.len() - 1;
                 // workaround for FloatingWM
                 if self.windows_info.get(&window).unwrap().float_or_tile == FloatOrTile::Float {
                     self.windows.remove(i);
                     self.windows_info.remove(&window);
+                    if was_last {
+                        self.restore_most_recent_focus();
+                    }
                     return Ok(());
                 }
                 // remove from windows
                 self.windows.remove(i);
+                if was_last {
+                    self.restore_most_recent_focus();
+                }
                 // remove WindowWithInfo
                 self.windows_info.remove(&window);
 
@@ -134,54 +265,21 @@ impl WindowManager for TilingWM {
     }
 
     /// Return the `WindowLayout` of the WindowManager.
-    /// Calculate the geometry of each window, subdivining the space of the whole screen.
+    /// The geometry of each tile is calculated by the current layout, which subdivides the
+    /// space of the whole screen, and then transformed by the `transforms`, unless the layout has
+    /// tabs.
     fn get_window_layout(&self) -> WindowLayout {
-        let fullscreen_geometry = self.screen.to_geometry();
         match self.windows.back() {
-
             // If there is at least one window.
             Some(w) => {
-                let len = self.tiles.len();
                 let mut focused = Some(*w);
                 if !self.is_focus {
                     focused = None;
                 }
-                match len {
-                    0 => {
-                        WindowLayout {
-                            focused_window: focused,
-                            windows: Vec::new(),
-                        }
-                    } 
-                    1 => {
-                        WindowLayout {
-                            focused_window: focused,
-                            windows: vec![(*(self.tiles.back().unwrap()), fullscreen_geometry)],
-                        }
-                    } 
-                    _ => {
-                        let mut windows: Vec<(Window, Geometry)> = Vec::new();
-                        let mut geometry = Geometry {
-                            x: 0,
-                            y: 0,
-                            width: fullscreen_geometry.width / 2,
-                            height: fullscreen_geometry.height,
-                        };
-                        windows.push((self.tiles[0], geometry));
-                        let height = fullscreen_geometry.height / ((self.tiles.len() - 1) as u32);
-                        geometry.x = geometry.width as i32;
-                        geometry.height = height;
-                        // workaround to not write also the case for the 2nd window
-                        geometry.y -= height as i32;
-                        for i in 1..self.tiles.len() {
-                            geometry.y += height as i32;
-                            windows.push((self.tiles[i], geometry));
-                        }
-                        WindowLayout {
-                            focused_window: focused,
-                            windows: windows,
-                        }
-                    }
+                WindowLayout {
+                    focused_window: focused,
+                    windows: self.arrange_tiles(self.screen.to_geometry()),
+                    border_widths: Vec::new(),
                 }
             }
             // Otherwise, return an empty WindowLayout
@@ -191,7 +289,8 @@ impl WindowManager for TilingWM {
 
     /// Focus the given window, or when passed None, focus nothing.
     ///
-    /// Move the new focused window in the last position of the windows `VecDeque`.
+    /// Move the new focused window in the last position of the windows `VecDeque`, and record it
+    /// in the `focus_history`. The window is no longer urgent.
     fn focus_window(&mut self, window: Option<Window>) -> Result<(), Self::Error> {
         match window {
             None => {
@@ -205,6 +304,7 @@ impl WindowManager for TilingWM {
                         self.is_focus = true;
                         let w = self.windows.remove(i);
                         self.windows.push_back(w.unwrap());
+                        self.record_focus();
                         Ok(())
                     }
                 }
@@ -214,8 +314,22 @@ impl WindowManager for TilingWM {
 
     /// Focus the previous or next window.
     ///
-    /// Behaves as the `cycle_focus` of the `FullscreenWM`.
+    /// Behaves as the `cycle_focus` of the `FullscreenWM`, unless the current layout has tabs and
+    /// a tile is focused: then focus the previous or next tab, i.e. tile.
     fn cycle_focus(&mut self, dir: PrevOrNext) {
+        if self.get_layout().has_tabs() && self.tiles.len() > 1 {
+            if let Some(window) = self.get_focused_window() {
+                if let Some(i) = self.tiles.iter().position(|t| *t == window) {
+                    let len = self.tiles.len();
+                    let next = match dir {
+                        PrevOrNext::Prev => self.tiles[(i + len - 1) % len],
+                        PrevOrNext::Next => self.tiles[(i + 1) % len],
+                    };
+                    self.focus_window(Some(next)).unwrap();
+                    return;
+                }
+            }
+        }
         let len = self.windows.len();
         match len {
             0 => return,
@@ -240,18 +354,22 @@ impl WindowManager for TilingWM {
             }
         }
         self.is_focus = true;
+        self.record_focus();
     }
 
     /// Get the info (WindowWithInfo) belonging to the given window.
     ///
-    /// Retrive it from the `WindowLayout`.
+    /// Retrive it from the `WindowLayout`. When the window is not in it, e.g. because it is
+    /// hidden by a layout with tabs, return the `WindowWithInfo` stored in `windows_info`.
     fn get_window_info(&self, window: Window) -> Result<WindowWithInfo, Self::Error> {
         match self.windows.iter().position(|w| *w == window) {
             None => Err(WMError::UnknownWindow(window)),
             Some(_) => {
                 let layout = self.get_window_layout().windows;
-                let i = layout.iter().position(|w| (*w).0 == window).unwrap();
-                Ok(WindowWithInfo::new_tiled(window, layout[i].1))
+                match layout.iter().position(|w| (*w).0 == window) {
+                    Some(i) => Ok(WindowWithInfo::new_tiled(window, layout[i].1)),
+                    None => Ok(*self.windows_info.get(&window).unwrap()),
+                }
             }
         }
     }
@@ -272,7 +390,12 @@ impl WindowManager for TilingWM {
 impl TilingSupport for TilingWM {
     /// Return the window displayed in the master tile.
     /// The master window is the one in the last position of the tiles `VecDeque`.
+    /// If `nmaster` is 0 there is no master tile, otherwise it is the topmost tile of the master
+    /// column.
     fn get_master_window(&self) -> Option<Window> {
+        if self.layout_params.nmaster == 0 {
+            return None;
+        }
         match self.tiles.front() {
             None => None, 
             Some(w) => Some(*w),
@@ -350,6 +473,152 @@ impl TilingSupport for TilingWM {
             } 
         }
     }
+
+    /// Return the master ratio stored in the `layout_params`.
+    fn get_master_ratio(&self) -> MasterRatio {
+        self.layout_params.master_ratio
+    }
+
+    /// Set the master ratio, clamping it between `MIN_MASTER_RATIO` and `MAX_MASTER_RATIO`.
+    fn set_master_ratio(&mut self, ratio: MasterRatio) {
+        self.layout_params.master_ratio = ratio.max(MIN_MASTER_RATIO).min(MAX_MASTER_RATIO);
+    }
+
+    /// Return all the tiles when the current layout has tabs and there is more than one tile.
+    fn get_tabs(&self) -> Vec<Window> {
+        if self.get_layout().has_tabs() && self.tiles.len() > 1 {
+            self.tiles.iter().map(|t| *t).collect()
+        } else {
+            Vec::new()
+        }
+    }
+}
+
+impl LayoutSupport for TilingWM {
+    /// Switch to the previous or next layout of `TilingLayout::all()`, considering it as
+    /// circular.
+    fn cycle_layout(&mut self, dir: PrevOrNext) {
+        let layouts = TilingLayout::all();
+        let len = layouts.len();
+        let i = layouts.iter().position(|l| *l == self.layout).unwrap_or(0);
+        self.layout = match dir {
+            PrevOrNext::Prev => layouts[(i + len - 1) % len],
+            PrevOrNext::Next => layouts[(i + 1) % len],
+        };
+    }
+
+    /// Return the number of windows in the master column stored in the `layout_params`.
+    fn get_nmaster(&self) -> usize {
+        self.layout_params.nmaster
+    }
+
+    /// Set the number of windows in the master column.
+    fn set_nmaster(&mut self, nmaster: usize) {
+        self.layout_params.nmaster = nmaster;
+    }
+
+    /// Return the weight stored in `weights`, or the `DEFAULT_TILE_WEIGHT` when there is none.
+    fn get_tile_weight(&self, window: Window) -> Result<TileWeight, Self::Error> {
+        if !self.is_managed(window) {
+            return Err(WMError::UnknownWindow(window));
+        }
+        Ok(*self.weights.get(&window).unwrap_or(&DEFAULT_TILE_WEIGHT))
+    }
+
+    /// Store the weight of the tile, clamping it between `MIN_TILE_WEIGHT` and
+    /// `MAX_TILE_WEIGHT`. Windows that are not tiles are ignored.
+    fn set_tile_weight(&mut self, window: Window, weight: TileWeight) -> Result<(), Self::Error> {
+        if !self.is_managed(window) {
+            return Err(WMError::UnknownWindow(window));
+        }
+        if self.tiles.contains(&window) {
+            self.weights.insert(window, weight.max(MIN_TILE_WEIGHT).min(MAX_TILE_WEIGHT));
+        }
+        Ok(())
+    }
+
+    /// Forget all the weights.
+    fn reset_tile_weights(&mut self) {
+        self.weights.clear();
+    }
+
+    /// Return the toggled `transforms`, in the order in which they are applied.
+    fn get_layout_transforms(&self) -> Vec<LayoutTransform> {
+        LayoutTransform::all().into_iter().filter(|t| self.transforms.contains(t)).collect()
+    }
+
+    /// Add the transform to `transforms`, or remove it when it is already there.
+    fn toggle_layout_transform(&mut self, transform: LayoutTransform) {
+        match self.transforms.iter().position(|t| *t == transform) {
+            Some(i) => {
+                self.transforms.remove(i);
+            }
+            None => self.transforms.push(transform),
+        }
+    }
+}
+
+impl FocusHistorySupport for TilingWM {
+    /// Return the `focus_history`, reversed.
+    fn get_focus_history(&self) -> Vec<Window> {
+        self.focus_history.iter().rev().map(|w| *w).collect()
+    }
+
+    /// Return `focus_history_frozen`.
+    fn is_focus_history_frozen(&self) -> bool {
+        self.focus_history_frozen
+    }
+
+    /// Set `focus_history_frozen`, when unfreezing record the focused window.
+    fn set_focus_history_frozen(&mut self, frozen: bool) {
+        self.focus_history_frozen = frozen;
+        self.record_focus();
+    }
+}
+
+impl UrgencySupport for TilingWM {
+    /// Return `urgent`.
+    fn get_urgent_windows(&self) -> Vec<Window> {
+        self.urgent.clone()
+    }
+
+    /// Add the window to the back of `urgent`, or remove it.
+    ///
+    /// The focused window can become urgent too, e.g. the focused window of a workspace the user
+    /// doesn't see. Focusing it again clears its urgency, see `record_focus`.
+    fn set_urgent(&mut self, window: Window, urgent: bool) -> Result<(), Self::Error> {
+        if !self.is_managed(window) {
+            return Err(WMError::UnknownWindow(window));
+        }
+        self.urgent.retain(|w| *w != window);
+        if urgent {
+            self.urgent.push(window);
+        }
+        Ok(())
+    }
+}
+
+impl DirectionalSupport for TilingWM {
+    /// Focus the nearest tile in the given direction, according to the `WindowLayout`.
+    fn focus_direction(&mut self, direction: Direction) {
+        if let Some(window) = self.get_focused_window() {
+            let windows = self.get_window_layout().windows;
+            if let Some(target) = layout::window_in_direction(&windows, window, direction) {
+                self.focus_window(Some(target)).unwrap();
+            }
+        }
+    }
+
+    /// Swap the focused tile with the nearest tile in the given direction, according to the
+    /// `WindowLayout`.
+    fn swap_direction(&mut self, direction: Direction) {
+        if let Some(window) = self.get_focused_window() {
+            let windows = self.get_window_layout().windows;
+            if let Some(target) = layout::window_in_direction(&windows, window, direction) {
+                self.swap_tiles(window, target);
+            }
+        }
+    }
 }
 
 // Here we define a submodule, called `tests`, that will contain the unit
@@ -361,8 +630,11 @@ impl TilingSupport for TilingWM {
 mod tests {
 
     use super::TilingWM;
-    use cplwm_api::wm::{TilingSupport, WindowManager};
+    use layout::TilingLayout;
+    use cplwm_api::wm::{DirectionalSupport, FocusHistorySupport, LayoutSupport, TilingSupport,
+                        UrgencySupport, WindowManager};
     use cplwm_api::types::*;
+    use rustc_serialize::json::{decode, encode};
 
     // We define a static variable for the screen we will use in the tests.
     // You can just as well define it as a local variable in your tests.
@@ -636,6 +908,494 @@ mod tests {
         assert_eq!(master6, Some(1));
     }
 
+    #[test]
+    fn test_cycling_layouts() {
+        // Let's make a new `TilingWM` with `SCREEN` as screen.
+        let mut wm = TilingWM::new(SCREEN);
+
+        // Let's add three windows
+        wm.add_window(WindowWithInfo::new_tiled(1, SOME_GEOM)).unwrap();
+        wm.add_window(WindowWithInfo::new_tiled(2, SOME_GEOM)).unwrap();
+        wm.add_window(WindowWithInfo::new_tiled(3, SOME_GEOM)).unwrap();
+        let wl1 = wm.get_window_layout();
+
+        // Cycling to the next and back to the previous layout should not change anything
+        wm.cycle_layout(PrevOrNext::Next);
+        wm.cycle_layout(PrevOrNext::Prev);
+        assert_eq!(wl1, wm.get_window_layout());
+        // and the other way around
+        wm.cycle_layout(PrevOrNext::Prev);
+        wm.cycle_layout(PrevOrNext::Next);
+        assert_eq!(wl1, wm.get_window_layout());
+
+        // Cycling through all the layouts brings us back to the first one
+        for _ in 0..TilingLayout::all().len() {
+            wm.cycle_layout(PrevOrNext::Next);
+        }
+        assert_eq!(TilingLayout::all()[0], wm.get_layout());
+        assert_eq!(wl1, wm.get_window_layout());
+
+        // The layout must survive serialising and deserialising the window manager
+        wm.cycle_layout(PrevOrNext::Prev);
+        let restored: TilingWM = decode(&encode(&wm).unwrap()).unwrap();
+        assert_eq!(*TilingLayout::all().last().unwrap(), restored.get_layout());
+        assert_eq!(wm.get_window_layout(), restored.get_window_layout());
+    }
+
+    #[test]
+    fn test_master_ratio() {
+        // Let's make a new `TilingWM` with `SCREEN` as screen.
+        let mut wm = TilingWM::new(SCREEN);
+        // Initially the master tile takes half of the screen
+        assert_eq!(DEFAULT_MASTER_RATIO, wm.get_master_ratio());
+
+        // A single window stays fullscreen whatever the ratio
+        wm.add_window(WindowWithInfo::new_tiled(1, SOME_GEOM)).unwrap();
+        wm.set_master_ratio(0.75);
+        assert_eq!(vec![(1, SCREEN_GEOM)], wm.get_window_layout().windows);
+        // With two more windows, the master tile takes three quarters of the screen
+        wm.add_window(WindowWithInfo::new_tiled(2, SOME_GEOM)).unwrap();
+        wm.add_window(WindowWithInfo::new_tiled(3, SOME_GEOM)).unwrap();
+        //  _ _ _ _ _ _ _ _
+        // |         |  2  |
+        // |    1    |_ _ _|
+        // |         |  3  |
+        // |_ _ _ _ _|_ _ _|
+        let three_tiles_layout: Vec<(Window, Geometry)> = vec![(1,
+                                                                Geometry {
+                                                                   x: 0,
+                                                                   y: 0,
+                                                                   width: 600,
+                                                                   height: 600,
+                                                               }),
+                                                               (2,
+                                                                Geometry {
+                                                                   x: 600,
+                                                                   y: 0,
+                                                                   width: 200,
+                                                                   height: 300,
+                                                               }),
+                                                               (3,
+                                                                Geometry {
+                                                                   x: 600,
+                                                                   y: 300,
+                                                                   width: 200,
+                                                                   height: 300,
+                                                               })];
+        assert_eq!(0.75, wm.get_master_ratio());
+        assert_eq!(three_tiles_layout, wm.get_window_layout().windows);
+
+        // The ratio is clamped, so the tiles never disappear
+        wm.set_master_ratio(1.5);
+        assert_eq!(MAX_MASTER_RATIO, wm.get_master_ratio());
+        wm.set_master_ratio(-1.0);
+        assert_eq!(MIN_MASTER_RATIO, wm.get_master_ratio());
+        let wl = wm.get_window_layout();
+        assert_eq!(80, wl.windows[0].1.width);
+        assert_eq!(720, wl.windows[1].1.width);
+
+        // Back to half of the screen
+        wm.set_master_ratio(0.5);
+        assert_eq!(400, wm.get_window_layout().windows[0].1.width);
+    }
+
+    #[test]
+    fn test_multiple_master_windows() {
+        // Let's make a new `TilingWM` with `SCREEN` as screen.
+        let mut wm = TilingWM::new(SCREEN);
+        // Initially there is one window in the master column
+        assert_eq!(1, wm.get_nmaster());
+
+        // Let's add four windows
+        for w in 1..5 {
+            wm.add_window(WindowWithInfo::new_tiled(w, SOME_GEOM)).unwrap();
+        }
+        // Let's put two windows in the master column
+        wm.set_nmaster(2);
+        //  _ _ _ _ _ _
+        // |  1  |  3  |
+        // |_ _ _|_ _ _|
+        // |  2  |  4  |
+        // |_ _ _|_ _ _|
+        let quarter = |x, y| {
+            Geometry {
+                x: x,
+                y: y,
+                width: 400,
+                height: 300,
+            }
+        };
+        let four_tiles_layout: Vec<(Window, Geometry)> = vec![(1, quarter(0, 0)),
+                                                              (2, quarter(0, 300)),
+                                                              (3, quarter(400, 0)),
+                                                              (4, quarter(400, 300))];
+        assert_eq!(four_tiles_layout, wm.get_window_layout().windows);
+        // The master window is the topmost one of the master column
+        assert_eq!(Some(1), wm.get_master_window());
+        // Swapping with the master swaps with the topmost one
+        wm.swap_with_master(4).unwrap();
+        assert_eq!(Some(4), wm.get_master_window());
+        assert_eq!(Some(4), wm.get_focused_window());
+        wm.swap_with_master(1).unwrap();
+
+        // With more master windows than tiles, there is only one column
+        wm.set_nmaster(5);
+        let row = |y| {
+            Geometry {
+                x: 0,
+                y: y,
+                width: 800,
+                height: 150,
+            }
+        };
+        let one_column_layout: Vec<(Window, Geometry)> =
+            vec![(1, row(0)), (2, row(150)), (3, row(300)), (4, row(450))];
+        assert_eq!(one_column_layout, wm.get_window_layout().windows);
+
+        // Without master column there is only one column too
+        wm.set_nmaster(0);
+        assert_eq!(one_column_layout, wm.get_window_layout().windows);
+        // but no master window
+        assert_eq!(None, wm.get_master_window());
+        // Swapping with the master moves the window to the first tile
+        wm.swap_with_master(3).unwrap();
+        assert_eq!(Some(3), wm.get_focused_window());
+        assert_eq!(None, wm.get_master_window());
+        assert_eq!(3, wm.get_window_layout().windows[0].0);
+        // which becomes the master tile again when increasing nmaster
+        wm.set_nmaster(1);
+        assert_eq!(Some(3), wm.get_master_window());
+    }
+
+    #[test]
+    fn test_swapping_along_the_spiral() {
+        // Let's make a new `TilingWM` with `SCREEN` as screen, using the spiral layout.
+        let mut wm = TilingWM::new(SCREEN);
+        while wm.get_layout() != TilingLayout::Spiral {
+            wm.cycle_layout(PrevOrNext::Next);
+        }
+
+        // Let's add three windows
+        wm.add_window(WindowWithInfo::new_tiled(1, SOME_GEOM)).unwrap();
+        wm.add_window(WindowWithInfo::new_tiled(2, SOME_GEOM)).unwrap();
+        wm.add_window(WindowWithInfo::new_tiled(3, SOME_GEOM)).unwrap();
+        let geometries: Vec<Geometry> =
+            wm.get_window_layout().windows.iter().map(|&(_, g)| g).collect();
+        // The master window is at the start of the spiral
+        assert!(geometries[0].width * geometries[0].height >
+                geometries[2].width * geometries[2].height);
+
+        // Swapping the focused window, the 3rd, with the previous one moves it up the spiral
+        wm.swap_windows(PrevOrNext::Prev);
+        assert_eq!(vec![(1, geometries[0]), (3, geometries[1]), (2, geometries[2])],
+                   wm.get_window_layout().windows);
+        // Swapping the last one with the master gives it the largest slot
+        wm.swap_with_master(2).unwrap();
+        assert_eq!(vec![(2, geometries[0]), (3, geometries[1]), (1, geometries[2])],
+                   wm.get_window_layout().windows);
+        assert_eq!(Some(2), wm.get_master_window());
+        // which is as wide as the master ratio says
+        wm.set_master_ratio(0.75);
+        assert_eq!(600, wm.get_window_layout().windows[0].1.width);
+    }
+
+    #[test]
+    fn test_master_window_keeps_the_largest_spiral_slot() {
+        // Let's make a new `TilingWM` with `SCREEN` as screen, using the spiral layout.
+        let mut wm = TilingWM::new(SCREEN);
+        while wm.get_layout() != TilingLayout::Spiral {
+            wm.cycle_layout(PrevOrNext::Next);
+        }
+
+        let area = |wm: &TilingWM, window: Window| {
+            let layout = wm.get_window_layout();
+            let &(_, g) = layout.windows.iter().find(|&&(w, _)| w == window).unwrap();
+            g.width * g.height
+        };
+        for window in 1..6 {
+            wm.add_window(WindowWithInfo::new_tiled(window, SOME_GEOM)).unwrap();
+            // The master window keeps the largest slot, the new one goes at the end of the
+            // spiral
+            for other in 2..(window + 1) {
+                assert!(area(&wm, 1) >= area(&wm, other));
+            }
+            assert_eq!(Some(&window), wm.tiles.back());
+            assert_eq!(Some(1), wm.get_master_window());
+        }
+
+        // Cycling the layout does not change the order of the tiles
+        wm.cycle_layout(PrevOrNext::Next);
+        assert_eq!((1..6).collect::<Vec<Window>>(),
+                   wm.tiles.iter().map(|t| *t).collect::<Vec<Window>>());
+    }
+
+    #[test]
+    fn test_monocle_tabs() {
+        // Let's make a new `TilingWM` with `SCREEN` as screen, using the monocle layout.
+        let mut wm = TilingWM::new(SCREEN);
+        while wm.get_layout() != TilingLayout::Monocle {
+            wm.cycle_layout(PrevOrNext::Next);
+        }
+
+        // With a single window there are no tabs
+        wm.add_window(WindowWithInfo::new_tiled(1, SOME_GEOM)).unwrap();
+        assert_eq!(Vec::<Window>::new(), wm.get_tabs());
+        assert_eq!(vec![(1, SCREEN_GEOM)], wm.get_window_layout().windows);
+
+        // Let's add two more windows
+        wm.add_window(WindowWithInfo::new_tiled(2, SOME_GEOM)).unwrap();
+        wm.add_window(WindowWithInfo::new_tiled(3, SOME_GEOM)).unwrap();
+        assert_eq!(vec![1, 2, 3], wm.get_tabs());
+        // Only the focused window is shown, below the tab strip
+        let below_strip = Geometry {
+            x: 0,
+            y: TAB_STRIP_HEIGHT as i32,
+            width: 800,
+            height: 600 - TAB_STRIP_HEIGHT,
+        };
+        assert_eq!(vec![(3, below_strip)], wm.get_window_layout().windows);
+
+        // Cycling the focus flips through the tabs in tiling order
+        wm.focus_window(Some(1)).unwrap();
+        wm.cycle_focus(PrevOrNext::Next);
+        assert_eq!(vec![(2, below_strip)], wm.get_window_layout().windows);
+        wm.cycle_focus(PrevOrNext::Prev);
+        wm.cycle_focus(PrevOrNext::Prev);
+        assert_eq!(Some(3), wm.get_focused_window());
+        // The tabs don't change
+        assert_eq!(vec![1, 2, 3], wm.get_tabs());
+
+        // Without focus the last focused tile is still shown
+        wm.focus_window(None).unwrap();
+        assert_eq!(vec![(3, below_strip)], wm.get_window_layout().windows);
+    }
+
+    #[test]
+    fn test_tile_weights() {
+        // Let's make a new `TilingWM` with `SCREEN` as screen.
+        let mut wm = TilingWM::new(SCREEN);
+        assert!(wm.get_tile_weight(1).is_err());
+        assert!(wm.set_tile_weight(1, 2.0).is_err());
+
+        // Let's add four windows
+        for w in 1..5 {
+            wm.add_window(WindowWithInfo::new_tiled(w, SOME_GEOM)).unwrap();
+        }
+        // Initially every tile has the default weight
+        assert_eq!(DEFAULT_TILE_WEIGHT, wm.get_tile_weight(3).unwrap());
+
+        // Window 3 becomes twice as tall as the other tiles in the stack
+        //  _ _ _ _ _ _ _ _
+        // |       |   2   |
+        // |       |_ _ _ _|
+        // |   1   |       |
+        // |       |   3   |
+        // |       |_ _ _ _|
+        // |_ _ _ _|_ _4_ _|
+        wm.set_tile_weight(3, 2.0).unwrap();
+        assert_eq!(2.0, wm.get_tile_weight(3).unwrap());
+        let heights: Vec<u32> =
+            wm.get_window_layout().windows.iter().map(|&(_, g)| g.height).collect();
+        assert_eq!(vec![600, 150, 300, 150], heights);
+        assert_eq!(450, wm.get_window_layout().windows[3].1.y);
+
+        // The weights survive a restart
+        let restored: TilingWM = decode(&encode(&wm).unwrap()).unwrap();
+        assert_eq!(wm.get_window_layout(), restored.get_window_layout());
+
+        // The weight is clamped
+        wm.set_tile_weight(2, 100.0).unwrap();
+        assert_eq!(MAX_TILE_WEIGHT, wm.get_tile_weight(2).unwrap());
+        wm.set_tile_weight(2, 0.0).unwrap();
+        assert_eq!(MIN_TILE_WEIGHT, wm.get_tile_weight(2).unwrap());
+
+        // Resetting equalises the tiles again
+        wm.reset_tile_weights();
+        assert_eq!(DEFAULT_TILE_WEIGHT, wm.get_tile_weight(3).unwrap());
+        assert_eq!(200, wm.get_window_layout().windows[2].1.height);
+
+        // The weight of a removed tile is forgotten
+        wm.set_tile_weight(4, 3.0).unwrap();
+        wm.remove_window(4).unwrap();
+        assert!(wm.weights.is_empty());
+    }
+
+    #[test]
+    fn test_layout_transforms() {
+        // Let's make a new `TilingWM` with `SCREEN` as screen.
+        let mut wm = TilingWM::new(SCREEN);
+        assert!(wm.get_layout_transforms().is_empty());
+        for w in 1..4 {
+            wm.add_window(WindowWithInfo::new_tiled(w, SOME_GEOM)).unwrap();
+        }
+        let master_stack = wm.get_window_layout();
+
+        // Rotate clockwise: the master tile is on top, the stack below it from
+        // right to left
+        wm.toggle_layout_transform(LayoutTransform::Rotate);
+        assert_eq!(vec![LayoutTransform::Rotate], wm.get_layout_transforms());
+        let wl = wm.get_window_layout();
+        assert_eq!(Geometry {
+                       x: 0,
+                       y: 0,
+                       width: 800,
+                       height: 300,
+                   },
+                   wl.windows[0].1);
+        assert_eq!(Geometry {
+                       x: 0,
+                       y: 300,
+                       width: 400,
+                       height: 300,
+                   },
+                   wl.windows[2].1);
+
+        // The transforms survive a restart
+        wm.toggle_layout_transform(LayoutTransform::MirrorHorizontally);
+        let restored: TilingWM = decode(&encode(&wm).unwrap()).unwrap();
+        assert_eq!(wm.get_layout_transforms(), restored.get_layout_transforms());
+        assert_eq!(wm.get_window_layout(), restored.get_window_layout());
+
+        // Toggling them again brings back the original layout
+        wm.toggle_layout_transform(LayoutTransform::Rotate);
+        wm.toggle_layout_transform(LayoutTransform::MirrorHorizontally);
+        assert_eq!(master_stack, wm.get_window_layout());
+
+        // A layout with tabs is not transformed
+        while wm.get_layout() != TilingLayout::Monocle {
+            wm.cycle_layout(PrevOrNext::Next);
+        }
+        let monocle = wm.get_window_layout();
+        wm.toggle_layout_transform(LayoutTransform::MirrorVertically);
+        assert_eq!(monocle, wm.get_window_layout());
+    }
+
+    #[test]
+    fn test_focus_history() {
+        let mut wm = TilingWM::new(SCREEN);
+        // Nothing happens without windows
+        wm.cycle_focus_history(PrevOrNext::Next);
+        assert!(wm.get_focus_history().is_empty());
+        for w in 1..4 {
+            wm.add_window(WindowWithInfo::new_tiled(w, SOME_GEOM)).unwrap();
+        }
+        assert_eq!(vec![3, 2, 1], wm.get_focus_history());
+        wm.focus_window(Some(1)).unwrap();
+        assert_eq!(vec![1, 3, 2], wm.get_focus_history());
+
+        // Cycling through the history toggles between the two most recent windows
+        wm.cycle_focus_history(PrevOrNext::Next);
+        assert_eq!(Some(3), wm.get_focused_window());
+        wm.cycle_focus_history(PrevOrNext::Next);
+        assert_eq!(Some(1), wm.get_focused_window());
+        assert_eq!(vec![1, 3, 2], wm.get_focus_history());
+
+        // ... unless the history is frozen
+        wm.set_focus_history_frozen(true);
+        assert!(wm.is_focus_history_frozen());
+        wm.cycle_focus_history(PrevOrNext::Next);
+        wm.cycle_focus_history(PrevOrNext::Next);
+        assert_eq!(Some(2), wm.get_focused_window());
+        assert_eq!(vec![1, 3, 2], wm.get_focus_history());
+        wm.cycle_focus_history(PrevOrNext::Prev);
+        assert_eq!(Some(3), wm.get_focused_window());
+        // Unfreezing commits the focused window
+        wm.set_focus_history_frozen(false);
+        assert_eq!(vec![3, 1, 2], wm.get_focus_history());
+
+        // Cycling round-robin is recorded as well
+        wm.cycle_focus(PrevOrNext::Next);
+        let focused = wm.get_focused_window().unwrap();
+        assert_eq!(focused, wm.get_focus_history()[0]);
+
+        // Removing the focused window focuses the most recently focused one
+        wm.focus_window(Some(2)).unwrap();
+        wm.focus_window(Some(3)).unwrap();
+        wm.remove_window(3).unwrap();
+        assert_eq!(Some(2), wm.get_focused_window());
+        assert_eq!(vec![2, 1], wm.get_focus_history());
+        // Removing another window leaves the focus alone
+        wm.remove_window(1).unwrap();
+        assert_eq!(Some(2), wm.get_focused_window());
+    }
+
+    #[test]
+    fn test_urgency() {
+        let mut wm = TilingWM::new(SCREEN);
+        assert!(wm.set_urgent(1, true).is_err());
+        // Nothing happens without urgent windows
+        wm.focus_urgent_window().unwrap();
+        assert_eq!(None, wm.get_focused_window());
+        for w in 1..5 {
+            wm.add_window(WindowWithInfo::new_tiled(w, SOME_GEOM)).unwrap();
+        }
+        wm.set_urgent(2, true).unwrap();
+        wm.set_urgent(1, true).unwrap();
+        wm.set_urgent(3, true).unwrap();
+        assert_eq!(vec![2, 1, 3], wm.get_urgent_windows());
+        // The focused window can become urgent as well
+        wm.set_urgent(4, true).unwrap();
+        assert!(wm.is_urgent(4));
+        wm.set_urgent(4, false).unwrap();
+        wm.set_urgent(3, false).unwrap();
+        assert_eq!(vec![2, 1], wm.get_urgent_windows());
+
+        // Focusing an urgent window clears its urgency
+        wm.focus_urgent_window().unwrap();
+        assert_eq!(Some(2), wm.get_focused_window());
+        assert_eq!(vec![1], wm.get_urgent_windows());
+        wm.cycle_focus(PrevOrNext::Next);
+        assert_eq!(Some(1), wm.get_focused_window());
+        assert!(wm.get_urgent_windows().is_empty());
+
+        // Removing a window removes it from the urgent windows
+        wm.set_urgent(3, true).unwrap();
+        wm.remove_window(3).unwrap();
+        assert!(wm.get_urgent_windows().is_empty());
+    }
+
+    #[test]
+    fn test_directional_focus_and_swap() {
+        let mut wm = TilingWM::new(SCREEN);
+        // Nothing happens without windows
+        wm.focus_direction(Direction::Left);
+        wm.swap_direction(Direction::Left);
+        for w in 1..4 {
+            wm.add_window(WindowWithInfo::new_tiled(w, SOME_GEOM)).unwrap();
+        }
+        //  _ _ _ _ _ _
+        // |     |  2  |
+        // |  1  |_ _ _|
+        // |     |  3  |
+        // |_ _ _|_ _ f|
+        wm.focus_direction(Direction::Up);
+        assert_eq!(Some(2), wm.get_focused_window());
+        wm.focus_direction(Direction::Left);
+        assert_eq!(Some(1), wm.get_focused_window());
+        // Nothing on the left of the master tile
+        wm.focus_direction(Direction::Left);
+        assert_eq!(Some(1), wm.get_focused_window());
+        // Both tiles on the right are equally near, the first one wins
+        wm.focus_direction(Direction::Right);
+        assert_eq!(Some(2), wm.get_focused_window());
+
+        // Swap the focused tile with the one below it, it keeps the focus
+        wm.swap_direction(Direction::Down);
+        assert_eq!(vec![1, 3, 2], wm.tiles.iter().map(|w| *w).collect::<Vec<_>>());
+        assert_eq!(Some(2), wm.get_focused_window());
+        // ... and with the master tile
+        wm.swap_direction(Direction::Left);
+        assert_eq!(Some(2), wm.get_master_window());
+        assert_eq!(Some(2), wm.get_focused_window());
+
+        // Nothing happens without focus
+        wm.focus_window(None).unwrap();
+        wm.focus_direction(Direction::Right);
+        wm.swap_direction(Direction::Right);
+        assert_eq!(None, wm.get_focused_window());
+        assert_eq!(Some(2), wm.get_master_window());
+    }
+
     // To run these tests, run the command `cargo test` in the `solution`
     // directory.
 }
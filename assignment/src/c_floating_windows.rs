@@ -26,8 +26,10 @@
 //!
 
 use cplwm_api::types::*;
-use cplwm_api::wm::{FloatSupport, TilingSupport, WindowManager};
-use b_tiling_wm::TilingWM;
+use cplwm_api::wm::{DirectionalSupport, FloatSupport, FocusHistorySupport, GapSupport,
+                    LayoutSupport, TilingSupport, UrgencySupport, WindowManager};
+use f_gaps::GapsWM;
+use layout;
 
 use wm_error::WMError;
 
@@ -39,14 +41,31 @@ pub type WMName = FloatingWM;
 /// The FloatingWM struct
 #[derive(RustcDecodable, RustcEncodable, Debug, Clone)]
 pub struct FloatingWM {
-    /// A wrapped Tiling Window Manager
-    pub tiling_wm: TilingWM,
+    /// A wrapped Gaps Window Manager, which wraps the Tiling Window Manager
+    pub gaps_wm: GapsWM,
 
     /// An HashMap of floating windows <Window, Geometry>
     pub floats: HashMap<Window, Geometry>,
 }
 
 
+impl FloatingWM {
+    /// Return the `WindowWithInfo` stored for the given window, see `GapsWM::get_stored_info`.
+    pub fn get_stored_info(&self, window: Window) -> Option<WindowWithInfo> {
+        self.gaps_wm.get_stored_info(window)
+    }
+
+    /// Add the given managed window to the back of the tiles.
+    pub fn add_tile(&mut self, window: Window) {
+        self.gaps_wm.add_tile(window)
+    }
+
+    /// Remove the given window from the tiles, together with its weight.
+    pub fn remove_tile(&mut self, window: Window) {
+        self.gaps_wm.remove_tile(window)
+    }
+}
+
 impl WindowManager for FloatingWM {
     /// We use 'WMError` as our `Error` type.
     type Error = WMError;
@@ -56,19 +75,19 @@ impl WindowManager for FloatingWM {
     fn new(screen: Screen) -> FloatingWM {
         FloatingWM {
             // initialize the wrapped WM
-            tiling_wm: TilingWM::new(screen),
+            gaps_wm: GapsWM::new(screen),
             floats: HashMap::new(),
         }
     }
 
     /// The function wraps the old `get_windows`
     fn get_windows(&self) -> Vec<Window> {
-        self.tiling_wm.get_windows()
+        self.gaps_wm.get_windows()
     }
 
     /// The function wraps the old `get_focused_window`
     fn get_focused_window(&self) -> Option<Window> {
-        self.tiling_wm.get_focused_window()
+        self.gaps_wm.get_focused_window()
     }
 
     /// To add a window first call the old the `add_window` function from the
@@ -78,7 +97,7 @@ impl WindowManager for FloatingWM {
     /// then add the window and its geometry to the floats `HashMap`.
     fn add_window(&mut self, window_with_info: WindowWithInfo) -> Result<(), Self::Error> {
         // get the return value of the add_window funciton
-        try!(self.tiling_wm.add_window(window_with_info));
+        try!(self.gaps_wm.add_window(window_with_info));
         // if the window is float, add the window and its gemometry to the floats vec
         if window_with_info.float_or_tile == FloatOrTile::Float {
             self.floats.insert(window_with_info.window, window_with_info.geometry);
@@ -90,14 +109,14 @@ impl WindowManager for FloatingWM {
     ///
     /// If there is no error and the window is float, we remove it from the `floats` vec
     fn remove_window(&mut self, window: Window) -> Result<(), Self::Error> {
-        try!(self.tiling_wm.remove_window(window));
+        try!(self.gaps_wm.remove_window(window));
         if self.floats.contains_key(&window) {
             // if there are no more windows, then there is no focus
-            if self.tiling_wm.windows.is_empty() {
+            if self.gaps_wm.get_windows().is_empty() {
                 // remove also the window for the floats Vec
                 let w = 0 as u64;
                 self.floats.remove(&w);
-                self.tiling_wm.is_focus = false;
+                self.gaps_wm.focus_window(None).unwrap();
             } else {
                 // if the window is tiled remove it from the floats
                 self.floats.remove(&window);
@@ -109,14 +128,13 @@ impl WindowManager for FloatingWM {
     }
 
 
-    /// The function concatenates the tiled windows returned by the TilingWM
+    /// The function concatenates the tiled windows returned by the GapsWM
     /// with the floating windows layout respecting the order of the focus.
     fn get_window_layout(&self) -> WindowLayout {
-        let mut layout = self.tiling_wm.get_window_layout();
+        let mut layout = self.gaps_wm.get_window_layout();
         // for each window in the windows `VecDeque` if the window is floating, concatenate it to
         // the windows layout (to maintain the order of the focus)
-        for i in 0..self.tiling_wm.windows.len() {
-            let window = self.tiling_wm.windows[i];
+        for window in self.gaps_wm.get_windows() {
             // if the window is floating
             if self.is_floating(window) {
                 let geom = self.floats.get(&window);
@@ -131,12 +149,12 @@ impl WindowManager for FloatingWM {
 
     /// Focus the given window, or when passed None, focus nothing.
     fn focus_window(&mut self, window: Option<Window>) -> Result<(), Self::Error> {
-        self.tiling_wm.focus_window(window)
+        self.gaps_wm.focus_window(window)
     }
 
     /// Focus the previous or next window.
     fn cycle_focus(&mut self, dir: PrevOrNext) {
-        self.tiling_wm.cycle_focus(dir);
+        self.gaps_wm.cycle_focus(dir);
     }
 
     /// Get the info (WindowWithInfo) belonging to the given window.
@@ -144,12 +162,12 @@ impl WindowManager for FloatingWM {
     /// If the window is tiled call the wrapped function.
     /// Otherwise return the WindowWithInfo from the `HashMap`.
     fn get_window_info(&self, window: Window) -> Result<WindowWithInfo, Self::Error> {
-        match self.tiling_wm.windows_info.get(&window) {
+        match self.gaps_wm.get_stored_info(window) {
             None => Err(WMError::UnknownWindow(window)),
             Some(window_with_info) => {
                 match window_with_info.float_or_tile {
-                    FloatOrTile::Float => self.tiling_wm.get_window_info(window), 
-                    FloatOrTile::Tile => Ok(*window_with_info),
+                    FloatOrTile::Float => self.gaps_wm.get_window_info(window), 
+                    FloatOrTile::Tile => Ok(window_with_info),
                 }
             }
         }
@@ -157,12 +175,12 @@ impl WindowManager for FloatingWM {
 
     /// Return the screen managed by the window manager.
     fn get_screen(&self) -> Screen {
-        self.tiling_wm.get_screen()
+        self.gaps_wm.get_screen()
     }
 
     /// Resize the screen according to the given Screen.
     fn resize_screen(&mut self, screen: Screen) {
-        self.tiling_wm.resize_screen(screen);
+        self.gaps_wm.resize_screen(screen);
     }
 }
 
@@ -171,23 +189,206 @@ impl WindowManager for FloatingWM {
 impl TilingSupport for FloatingWM {
     /// Return the window displayed in the master tile.
     fn get_master_window(&self) -> Option<Window> {
-        self.tiling_wm.get_master_window()
+        self.gaps_wm.get_master_window()
     }
 
     /// Swap the given window with the window in the master tile.
     fn swap_with_master(&mut self, window: Window) -> Result<(), Self::Error> {
-        self.tiling_wm.swap_with_master(window)
+        self.gaps_wm.swap_with_master(window)
     }
 
     /// Swap the focused window with the one in the next or previous tile.
     ///
     /// If the focused window is Tiled call the wrapped function otherwise do nothing.
     fn swap_windows(&mut self, dir: PrevOrNext) {
-        if self.tiling_wm.is_focus {
-            let focused = self.get_focused_window().unwrap();
+        if let Some(focused) = self.get_focused_window() {
             match self.get_window_info(focused).unwrap().float_or_tile {
                 FloatOrTile::Float => (), 
-                FloatOrTile::Tile => self.tiling_wm.swap_windows(dir),
+                FloatOrTile::Tile => self.gaps_wm.swap_windows(dir),
+            }
+        }
+    }
+
+    /// Return the master ratio of the wrapped WM.
+    fn get_master_ratio(&self) -> MasterRatio {
+        self.gaps_wm.get_master_ratio()
+    }
+
+    /// Set the master ratio of the wrapped WM.
+    fn set_master_ratio(&mut self, ratio: MasterRatio) {
+        self.gaps_wm.set_master_ratio(ratio)
+    }
+
+    /// Return the tabs of the wrapped WM.
+    fn get_tabs(&self) -> Vec<Window> {
+        self.gaps_wm.get_tabs()
+    }
+}
+
+impl LayoutSupport for FloatingWM {
+    /// Switch to the previous or next layout.
+    /// Floating windows are not affected, so just call the wrapped function.
+    fn cycle_layout(&mut self, dir: PrevOrNext) {
+        self.gaps_wm.cycle_layout(dir)
+    }
+
+    /// Return the number of windows in the master column of the wrapped WM.
+    fn get_nmaster(&self) -> usize {
+        self.gaps_wm.get_nmaster()
+    }
+
+    /// Set the number of windows in the master column of the wrapped WM.
+    fn set_nmaster(&mut self, nmaster: usize) {
+        self.gaps_wm.set_nmaster(nmaster)
+    }
+
+    /// Return the weight of the window in the wrapped WM.
+    fn get_tile_weight(&self, window: Window) -> Result<TileWeight, Self::Error> {
+        self.gaps_wm.get_tile_weight(window)
+    }
+
+    /// Set the weight of the tile in the wrapped WM.
+    fn set_tile_weight(&mut self, window: Window, weight: TileWeight) -> Result<(), Self::Error> {
+        self.gaps_wm.set_tile_weight(window, weight)
+    }
+
+    /// Reset the weights of the tiles in the wrapped WM.
+    fn reset_tile_weights(&mut self) {
+        self.gaps_wm.reset_tile_weights()
+    }
+
+    /// Return the layout transforms of the wrapped WM.
+    fn get_layout_transforms(&self) -> Vec<LayoutTransform> {
+        self.gaps_wm.get_layout_transforms()
+    }
+
+    /// Toggle the layout transform in the wrapped WM.
+    fn toggle_layout_transform(&mut self, transform: LayoutTransform) {
+        self.gaps_wm.toggle_layout_transform(transform)
+    }
+}
+
+impl GapSupport for FloatingWM {
+    /// Return the gap size of the wrapped WM.
+    fn get_gap(&self) -> GapSize {
+        self.gaps_wm.get_gap()
+    }
+
+    /// Set the gap size of the wrapped WM.
+    fn set_gap(&mut self, gap: GapSize) {
+        self.gaps_wm.set_gap(gap)
+    }
+
+    /// Return the inner gap of the wrapped WM.
+    fn get_inner_gap(&self) -> GapSize {
+        self.gaps_wm.get_inner_gap()
+    }
+
+    /// Set the inner gap of the wrapped WM.
+    fn set_inner_gap(&mut self, gap: GapSize) {
+        self.gaps_wm.set_inner_gap(gap)
+    }
+
+    /// Return the outer gap of the wrapped WM.
+    fn get_outer_gap(&self) -> GapSize {
+        self.gaps_wm.get_outer_gap()
+    }
+
+    /// Set the outer gap of the wrapped WM.
+    fn set_outer_gap(&mut self, gap: GapSize) {
+        self.gaps_wm.set_outer_gap(gap)
+    }
+
+    /// Return the padding of the wrapped WM.
+    fn get_padding(&self) -> Padding {
+        self.gaps_wm.get_padding()
+    }
+
+    /// Set the padding of the wrapped WM.
+    fn set_padding(&mut self, padding: Padding) {
+        self.gaps_wm.set_padding(padding)
+    }
+
+    /// Return whether the wrapped WM uses smart gaps.
+    fn get_smart_gaps(&self) -> bool {
+        self.gaps_wm.get_smart_gaps()
+    }
+
+    /// Set whether the wrapped WM uses smart gaps.
+    fn set_smart_gaps(&mut self, smart_gaps: bool) {
+        self.gaps_wm.set_smart_gaps(smart_gaps)
+    }
+
+    /// Return whether the wrapped WM uses smart borders.
+    fn get_smart_borders(&self) -> bool {
+        self.gaps_wm.get_smart_borders()
+    }
+
+    /// Set whether the wrapped WM uses smart borders.
+    fn set_smart_borders(&mut self, smart_borders: bool) {
+        self.gaps_wm.set_smart_borders(smart_borders)
+    }
+}
+
+impl FocusHistorySupport for FloatingWM {
+    /// Call the wrapped function, the history includes the floating windows.
+    fn get_focus_history(&self) -> Vec<Window> {
+        self.gaps_wm.get_focus_history()
+    }
+
+    /// Call the wrapped function.
+    fn is_focus_history_frozen(&self) -> bool {
+        self.gaps_wm.is_focus_history_frozen()
+    }
+
+    /// Call the wrapped function.
+    fn set_focus_history_frozen(&mut self, frozen: bool) {
+        self.gaps_wm.set_focus_history_frozen(frozen)
+    }
+}
+
+impl UrgencySupport for FloatingWM {
+    /// Call the wrapped function, floating windows can be urgent too.
+    fn get_urgent_windows(&self) -> Vec<Window> {
+        self.gaps_wm.get_urgent_windows()
+    }
+
+    /// Call the wrapped function.
+    fn set_urgent(&mut self, window: Window, urgent: bool) -> Result<(), Self::Error> {
+        self.gaps_wm.set_urgent(window, urgent)
+    }
+}
+
+impl DirectionalSupport for FloatingWM {
+    /// Focus the nearest tile or floating window in the given direction, according to the
+    /// `WindowLayout`, which includes the floating windows.
+    fn focus_direction(&mut self, direction: Direction) {
+        if let Some(window) = self.get_focused_window() {
+            let windows = self.get_window_layout().windows;
+            if let Some(target) = layout::window_in_direction(&windows, window, direction) {
+                self.focus_window(Some(target)).unwrap();
+            }
+        }
+    }
+
+    /// Swap the focused window with the nearest window in the given direction.
+    ///
+    /// Two tiles swap their places, two floating windows swap their geometries. A tile and a
+    /// floating window are not swapped.
+    fn swap_direction(&mut self, direction: Direction) {
+        if let Some(window) = self.get_focused_window() {
+            let windows = self.get_window_layout().windows;
+            if let Some(target) = layout::window_in_direction(&windows, window, direction) {
+                let floating = self.floats.get(&window).cloned();
+                let target_floating = self.floats.get(&target).cloned();
+                match (floating, target_floating) {
+                    (Some(geometry), Some(target_geometry)) => {
+                        self.set_window_geometry(window, target_geometry).unwrap();
+                        self.set_window_geometry(target, geometry).unwrap();
+                    }
+                    (None, None) => self.gaps_wm.swap_tiles(window, target),
+                    _ => (),
+                }
             }
         }
     }
@@ -209,36 +410,31 @@ impl FloatSupport for FloatingWM {
     /// If the windows is tiled, remove it from the tiles, add it to the floats retrieving its
     /// original geometry from the windows_info `HashMap`.
     fn toggle_floating(&mut self, window: Window) -> Result<(), Self::Error> {
-        match self.tiling_wm.windows_info.get_mut(&window) {
+        let window_with_info = match self.gaps_wm.get_stored_info(window) {
             // if the window is not managed return an error
-            None => Err(WMError::UnknownWindow(window)), 
-            Some(window_with_info) => {
-                // check whether the windows is float or tile
-                match window_with_info.float_or_tile {
-                    FloatOrTile::Float => {
-                        // remove the window from the floats
-                        self.floats.remove(&window);
-                        // insert the window in the tile VecDeque
-                        self.tiling_wm.tiles.push_back(window);
-                        // update the window info of the window
-                        window_with_info.float_or_tile = FloatOrTile::Tile;
-                        Ok(())
-                    } 
-                    FloatOrTile::Tile => {
-                        let j = self.tiling_wm.tiles.iter().position(|t| *t == window).unwrap();
-                        // remove the window from the tiles
-                        self.tiling_wm.tiles.remove(j);
-                        // retrieve the old geometry
-                        let geom = window_with_info.geometry;
-                        // push window + geometry to floats
-                        self.floats.insert(window, geom);
-                        // update the window info of the window
-                        window_with_info.float_or_tile = FloatOrTile::Float;
-                        Ok(())
-                    } 
-                }
-            }
-        }
+            None => return Err(WMError::UnknownWindow(window)),
+            Some(window_with_info) => window_with_info,
+        };
+        // check whether the windows is float or tile
+        let float_or_tile = match window_with_info.float_or_tile {
+            FloatOrTile::Float => {
+                // remove the window from the floats
+                self.floats.remove(&window);
+                // insert the window in the tile VecDeque
+                self.gaps_wm.add_tile(window);
+                FloatOrTile::Tile
+            } 
+            FloatOrTile::Tile => {
+                // remove the window from the tiles, together with its weight
+                self.gaps_wm.remove_tile(window);
+                // push window + its old geometry to floats
+                self.floats.insert(window, window_with_info.geometry);
+                FloatOrTile::Float
+            } 
+        };
+        // update the window info of the window
+        self.gaps_wm.get_stored_info_mut(window).unwrap().float_or_tile = float_or_tile;
+        Ok(())
     }
 
     /// Resize/move the given floating window according to the given geometry.
@@ -246,7 +442,7 @@ impl FloatSupport for FloatingWM {
                            window: Window,
                            new_geometry: Geometry)
                            -> Result<(), Self::Error> {
-        match self.tiling_wm.windows_info.get_mut(&window) {
+        match self.gaps_wm.get_stored_info_mut(window) {
             None => Err(WMError::UnknownWindow(window)), 
             Some(window_with_info) => {
                 if self.floats.contains_key(&window) {
@@ -265,7 +461,8 @@ impl FloatSupport for FloatingWM {
 mod tests {
 
     use super::FloatingWM;
-    use cplwm_api::wm::{FloatSupport, TilingSupport, WindowManager};
+    use cplwm_api::wm::{DirectionalSupport, FloatSupport, LayoutSupport, TilingSupport,
+                        WindowManager};
     use cplwm_api::types::*;
 
     // We define a static variable for the screen we will use in the tests.
@@ -573,6 +770,59 @@ mod tests {
         assert_eq!(two_windows_layout, wl1.windows);
 
     }
+
+    #[test]
+    fn test_floating_forgets_tile_weight() {
+        let mut wm = FloatingWM::new(SCREEN);
+        wm.add_window(WindowWithInfo::new_tiled(1, SOME_GEOM)).unwrap();
+        wm.add_window(WindowWithInfo::new_tiled(2, SOME_GEOM)).unwrap();
+        wm.set_tile_weight(2, 2.0).unwrap();
+        // a floating window has no weight
+        wm.toggle_floating(2).unwrap();
+        assert_eq!(DEFAULT_TILE_WEIGHT, wm.get_tile_weight(2).unwrap());
+        wm.set_tile_weight(2, 2.0).unwrap();
+        assert_eq!(DEFAULT_TILE_WEIGHT, wm.get_tile_weight(2).unwrap());
+        // when it sinks again it gets the default weight
+        wm.toggle_floating(2).unwrap();
+        assert_eq!(DEFAULT_TILE_WEIGHT, wm.get_tile_weight(2).unwrap());
+    }
+
+    #[test]
+    fn test_directional_focus_and_swap_floating_windows() {
+        let mut wm = FloatingWM::new(SCREEN);
+        let left = Geometry {
+            x: 10,
+            y: 10,
+            width: 100,
+            height: 100,
+        };
+        let right = Geometry {
+            x: 500,
+            y: 10,
+            width: 100,
+            height: 100,
+        };
+        wm.add_window(WindowWithInfo::new_float(1, left)).unwrap();
+        wm.add_window(WindowWithInfo::new_float(2, right)).unwrap();
+        // Floating windows can be focused
+        wm.focus_direction(Direction::Left);
+        assert_eq!(Some(1), wm.get_focused_window());
+        // ... and swapped: they swap their geometries
+        wm.swap_direction(Direction::Right);
+        assert_eq!(Some(1), wm.get_focused_window());
+        assert_eq!(right, wm.get_window_info(1).unwrap().geometry);
+        assert_eq!(left, wm.get_window_info(2).unwrap().geometry);
+
+        // A tile filling the screen lies between them, it is not swapped with a floating window
+        wm.add_window(WindowWithInfo::new_tiled(3, SOME_GEOM)).unwrap();
+        wm.focus_window(Some(2)).unwrap();
+        wm.focus_direction(Direction::Right);
+        assert_eq!(Some(3), wm.get_focused_window());
+        wm.focus_window(Some(2)).unwrap();
+        wm.swap_direction(Direction::Right);
+        assert_eq!(left, wm.get_window_info(2).unwrap().geometry);
+        assert_eq!(Some(3), wm.get_master_window());
+    }
     // To run these tests, run the command `cargo test` in the `solution`
     // directory.
 }
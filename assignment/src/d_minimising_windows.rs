@@ -23,7 +23,8 @@
 //!
 
 use cplwm_api::types::*;
-use cplwm_api::wm::{FloatSupport, MinimiseSupport, TilingSupport, WindowManager};
+use cplwm_api::wm::{DirectionalSupport, FloatSupport, FocusHistorySupport, GapSupport,
+                    LayoutSupport, MinimiseSupport, TilingSupport, UrgencySupport, WindowManager};
 use c_floating_windows::FloatingWM;
 use wm_error::WMError;
 
@@ -37,6 +38,10 @@ pub struct MinimiseWM {
     pub floating_wm: FloatingWM,
     /// A Vec containing all the minimised windows
     pub minimised: Vec<Window>,
+    /// The minimised window that was shown because it got the focus while the focus history
+    /// was frozen. It is minimised again when an other window gets the focus before the history
+    /// is unfrozen.
+    pub peeked: Option<Window>,
 }
 
 impl WindowManager for MinimiseWM {
@@ -48,6 +53,7 @@ impl WindowManager for MinimiseWM {
         MinimiseWM {
             floating_wm: FloatingWM::new(screen),
             minimised: Vec::new(),
+            peeked: None,
         }
     }
 
@@ -68,11 +74,23 @@ impl WindowManager for MinimiseWM {
 
     /// Remove the given window from the window manager.
     /// If it's minimied, unminimised it and call the wrapped function.
+    ///
+    /// If the wrapped function passes the focus to a minimised window, focus the most recently
+    /// focused window that is visible instead.
     fn remove_window(&mut self, window: Window) -> Result<(), Self::Error> {
+        if self.peeked == Some(window) {
+            self.peeked = None;
+        }
         if self.is_managed(window) && self.is_minimised(window) {
             self.toggle_minimised(window).unwrap();
         }
-        self.floating_wm.remove_window(window)
+        try!(self.floating_wm.remove_window(window));
+        if let Some(focused) = self.get_focused_window() {
+            if self.is_minimised(focused) {
+                self.focus_most_recent_visible();
+            }
+        }
+        Ok(())
     }
 
     /// Call the wrapped function.
@@ -84,15 +102,34 @@ impl WindowManager for MinimiseWM {
     ///
     /// If the windwos is `Some`, managed and minimised, unminimise it.
     /// Then call the wrapped function.
+    ///
+    /// While the focus history is frozen, e.g. while the user goes through it with Alt-Tab, the
+    /// minimised windows that get the focus are only unminimised until an other window gets it.
     fn focus_window(&mut self, window: Option<Window>) -> Result<(), Self::Error> {
+        let peeked = self.peeked.take();
         // if the window is not none, is managed and minimised
         if window.is_some() && self.is_managed(window.unwrap()) &&
            self.is_minimised(window.unwrap()) {
             // unminimised it
             self.toggle_minimised(window.unwrap()).unwrap();
+            if self.is_focus_history_frozen() {
+                self.peeked = window;
+            }
         }
         // and call the wrapped function
-        self.floating_wm.focus_window(window)
+        if let Err(error) = self.floating_wm.focus_window(window) {
+            self.peeked = peeked;
+            return Err(error);
+        }
+        // minimise the window we peeked at before again
+        if let Some(peeked) = peeked {
+            if window == Some(peeked) {
+                self.peeked = Some(peeked);
+            } else if !self.is_minimised(peeked) {
+                self.toggle_minimised(peeked).unwrap();
+            }
+        }
+        Ok(())
     }
 
     /// Focus the previous or next window.
@@ -124,7 +161,7 @@ impl WindowManager for MinimiseWM {
             // } else if !self.is_minimised(window) {
             // self.floating_wm.get_window_info(window)
         } else {
-            Ok(*(self.floating_wm.tiling_wm.windows_info.get(&window).unwrap()))
+            Ok(self.floating_wm.get_stored_info(window).unwrap())
         }
     }
 
@@ -166,6 +203,170 @@ impl TilingSupport for MinimiseWM {
     fn swap_windows(&mut self, dir: PrevOrNext) {
         self.floating_wm.swap_windows(dir);
     }
+
+    /// Return the master ratio.
+    /// Call the wrapped function.
+    fn get_master_ratio(&self) -> MasterRatio {
+        self.floating_wm.get_master_ratio()
+    }
+
+    /// Set the master ratio.
+    /// Call the wrapped function.
+    fn set_master_ratio(&mut self, ratio: MasterRatio) {
+        self.floating_wm.set_master_ratio(ratio)
+    }
+
+    /// Return the tabs of the wrapped WM.
+    fn get_tabs(&self) -> Vec<Window> {
+        self.floating_wm.get_tabs()
+    }
+}
+
+impl LayoutSupport for MinimiseWM {
+    /// Switch to the previous or next layout.
+    /// Call the wrapped function.
+    fn cycle_layout(&mut self, dir: PrevOrNext) {
+        self.floating_wm.cycle_layout(dir)
+    }
+
+    /// Return the number of windows in the master column of the wrapped WM.
+    fn get_nmaster(&self) -> usize {
+        self.floating_wm.get_nmaster()
+    }
+
+    /// Set the number of windows in the master column of the wrapped WM.
+    fn set_nmaster(&mut self, nmaster: usize) {
+        self.floating_wm.set_nmaster(nmaster)
+    }
+
+    /// Return the weight of the window in the wrapped WM.
+    fn get_tile_weight(&self, window: Window) -> Result<TileWeight, Self::Error> {
+        self.floating_wm.get_tile_weight(window)
+    }
+
+    /// Set the weight of the tile in the wrapped WM.
+    fn set_tile_weight(&mut self, window: Window, weight: TileWeight) -> Result<(), Self::Error> {
+        self.floating_wm.set_tile_weight(window, weight)
+    }
+
+    /// Reset the weights of the tiles in the wrapped WM.
+    fn reset_tile_weights(&mut self) {
+        self.floating_wm.reset_tile_weights()
+    }
+
+    /// Return the layout transforms of the wrapped WM.
+    fn get_layout_transforms(&self) -> Vec<LayoutTransform> {
+        self.floating_wm.get_layout_transforms()
+    }
+
+    /// Toggle the layout transform in the wrapped WM.
+    fn toggle_layout_transform(&mut self, transform: LayoutTransform) {
+        self.floating_wm.toggle_layout_transform(transform)
+    }
+}
+
+impl GapSupport for MinimiseWM {
+    /// Return the gap size of the wrapped WM.
+    fn get_gap(&self) -> GapSize {
+        self.floating_wm.get_gap()
+    }
+
+    /// Set the gap size of the wrapped WM.
+    fn set_gap(&mut self, gap: GapSize) {
+        self.floating_wm.set_gap(gap)
+    }
+
+    /// Return the inner gap of the wrapped WM.
+    fn get_inner_gap(&self) -> GapSize {
+        self.floating_wm.get_inner_gap()
+    }
+
+    /// Set the inner gap of the wrapped WM.
+    fn set_inner_gap(&mut self, gap: GapSize) {
+        self.floating_wm.set_inner_gap(gap)
+    }
+
+    /// Return the outer gap of the wrapped WM.
+    fn get_outer_gap(&self) -> GapSize {
+        self.floating_wm.get_outer_gap()
+    }
+
+    /// Set the outer gap of the wrapped WM.
+    fn set_outer_gap(&mut self, gap: GapSize) {
+        self.floating_wm.set_outer_gap(gap)
+    }
+
+    /// Return the padding of the wrapped WM.
+    fn get_padding(&self) -> Padding {
+        self.floating_wm.get_padding()
+    }
+
+    /// Set the padding of the wrapped WM.
+    fn set_padding(&mut self, padding: Padding) {
+        self.floating_wm.set_padding(padding)
+    }
+
+    /// Return whether the wrapped WM uses smart gaps.
+    fn get_smart_gaps(&self) -> bool {
+        self.floating_wm.get_smart_gaps()
+    }
+
+    /// Set whether the wrapped WM uses smart gaps.
+    fn set_smart_gaps(&mut self, smart_gaps: bool) {
+        self.floating_wm.set_smart_gaps(smart_gaps)
+    }
+
+    /// Return whether the wrapped WM uses smart borders.
+    fn get_smart_borders(&self) -> bool {
+        self.floating_wm.get_smart_borders()
+    }
+
+    /// Set whether the wrapped WM uses smart borders.
+    fn set_smart_borders(&mut self, smart_borders: bool) {
+        self.floating_wm.set_smart_borders(smart_borders)
+    }
+}
+
+impl FocusHistorySupport for MinimiseWM {
+    /// Call the wrapped function, the history includes the minimised windows.
+    fn get_focus_history(&self) -> Vec<Window> {
+        self.floating_wm.get_focus_history()
+    }
+
+    /// Call the wrapped function.
+    fn is_focus_history_frozen(&self) -> bool {
+        self.floating_wm.is_focus_history_frozen()
+    }
+
+    /// Call the wrapped function. The window we peeked at, if any, stays unminimised.
+    fn set_focus_history_frozen(&mut self, frozen: bool) {
+        self.peeked = None;
+        self.floating_wm.set_focus_history_frozen(frozen)
+    }
+}
+
+impl UrgencySupport for MinimiseWM {
+    /// Call the wrapped function, minimised windows can be urgent too.
+    fn get_urgent_windows(&self) -> Vec<Window> {
+        self.floating_wm.get_urgent_windows()
+    }
+
+    /// Call the wrapped function.
+    fn set_urgent(&mut self, window: Window, urgent: bool) -> Result<(), Self::Error> {
+        self.floating_wm.set_urgent(window, urgent)
+    }
+}
+
+impl DirectionalSupport for MinimiseWM {
+    /// Call the wrapped function, the minimised windows are not visible.
+    fn focus_direction(&mut self, direction: Direction) {
+        self.floating_wm.focus_direction(direction)
+    }
+
+    /// Call the wrapped function, the minimised windows are not visible.
+    fn swap_direction(&mut self, direction: Direction) {
+        self.floating_wm.swap_direction(direction)
+    }
 }
 
 impl FloatSupport for MinimiseWM {
@@ -234,10 +435,7 @@ impl MinimiseSupport for MinimiseWM {
                         .insert(window_with_info.window, window_with_info.geometry);
                 } 
                 FloatOrTile::Tile => {
-                    self.floating_wm
-                        .tiling_wm
-                        .tiles
-                        .push_back(window_with_info.window);
+                    self.floating_wm.add_tile(window_with_info.window);
                 }
             }
             let i = self.minimised.iter().position(|w| *w == window).unwrap();
@@ -253,16 +451,8 @@ impl MinimiseSupport for MinimiseWM {
                         .remove(&window);
                 } 
                 FloatOrTile::Tile => {
-                    let i = self.floating_wm
-                        .tiling_wm
-                        .tiles
-                        .iter()
-                        .position(|w| *w == window)
-                        .unwrap();
-                    self.floating_wm
-                        .tiling_wm
-                        .tiles
-                        .remove(i);
+                    // a minimised window is no longer a tile, forget its weight
+                    self.floating_wm.remove_tile(window);
                 }
             }
             let focus = self.get_focused_window();
@@ -270,37 +460,30 @@ impl MinimiseSupport for MinimiseWM {
             if focus.is_none() || focus != Some(window) {
                 return Ok(());
             }
-            // number of unminimised window
-            let len = self.get_windows().len() - self.minimised.len();
             // if the focus was on the minimised window let's change the focus
-            // let's focus the first previous window that is not minimised
-            match len {
-                0 => {
-                    self.focus_window(None).unwrap();
-                } 
-                _ => {
-                    let windows = self.get_windows();
-                    // scan the windows in reverse order
-                    for i in (0..(windows.len() - 1)).rev() {
-                        // the first window that is not minimised
-                        if self.minimised.iter().position(|w| *w == windows[i]).is_none() {
-                            // receives the focus
-                            self.focus_window(Some(windows[i])).unwrap();
-                            break;
-                        }
-                    }
-                }
-            }
+            self.focus_most_recent_visible();
         }
         Ok(())
     }
 }
 
+impl MinimiseWM {
+    /// Focus the most recently focused window that is not minimised, or nothing when all the
+    /// windows are minimised.
+    pub fn focus_most_recent_visible(&mut self) {
+        let visible = self.get_focus_history()
+            .into_iter()
+            .find(|w| !self.is_minimised(*w));
+        self.floating_wm.focus_window(visible).unwrap();
+    }
+}
+
 #[cfg(test)]
 mod tests {
 
     use super::WMName;
-    use cplwm_api::wm::{FloatSupport, MinimiseSupport, TilingSupport, WindowManager};
+    use cplwm_api::wm::{FloatSupport, FocusHistorySupport, LayoutSupport, MinimiseSupport,
+                        TilingSupport, UrgencySupport, WindowManager};
     use cplwm_api::types::*;
 
     // We define a static variable for the screen we will use in the tests.
@@ -719,6 +902,99 @@ mod tests {
         assert_eq!(wl1, wm.get_window_layout());
     }
 
+    #[test]
+    fn minimising_forgets_tile_weight() {
+        let mut wm = WMName::new(SCREEN);
+
+        wm.add_window(WindowWithInfo::new_tiled(1, SOME_GEOM)).unwrap();
+        wm.add_window(WindowWithInfo::new_tiled(2, SOME_GEOM)).unwrap();
+        wm.add_window(WindowWithInfo::new_tiled(3, SOME_GEOM)).unwrap();
+        let wl1 = wm.get_window_layout();
+        wm.set_tile_weight(3, 3.0).unwrap();
+        // let's minimise and unminimise the 3rd window
+        wm.toggle_minimised(3).unwrap();
+        assert_eq!(DEFAULT_TILE_WEIGHT, wm.get_tile_weight(3).unwrap());
+        wm.toggle_minimised(3).unwrap();
+        // its tile is as tall as the other one again
+        assert_eq!(DEFAULT_TILE_WEIGHT, wm.get_tile_weight(3).unwrap());
+        assert_eq!(wl1, wm.get_window_layout());
+    }
+
+    #[test]
+    fn minimising_focuses_the_most_recent_window() {
+        let mut wm = WMName::new(SCREEN);
+
+        wm.add_window(WindowWithInfo::new_tiled(1, SOME_GEOM)).unwrap();
+        wm.add_window(WindowWithInfo::new_tiled(2, SOME_GEOM)).unwrap();
+        wm.add_window(WindowWithInfo::new_tiled(3, SOME_GEOM)).unwrap();
+        // focus the 2nd window by cycling backwards
+        wm.cycle_focus(PrevOrNext::Prev);
+        assert_eq!(wm.get_focused_window(), Some(2));
+        assert_eq!(wm.get_focus_history(), vec![2, 3, 1]);
+        // minimising it focuses the 3rd window, the most recently focused one
+        wm.toggle_minimised(2).unwrap();
+        assert_eq!(wm.get_focused_window(), Some(3));
+        // the minimised window stays in the history
+        assert_eq!(wm.get_focus_history(), vec![3, 2, 1]);
+        // removing the focused window skips the minimised one
+        wm.remove_window(3).unwrap();
+        assert_eq!(wm.get_focused_window(), Some(1));
+        // minimising the last visible window leaves no focus
+        wm.toggle_minimised(1).unwrap();
+        assert_eq!(wm.get_focused_window(), None);
+    }
+
+    #[test]
+    fn focusing_an_urgent_minimised_window() {
+        let mut wm = WMName::new(SCREEN);
+
+        wm.add_window(WindowWithInfo::new_tiled(1, SOME_GEOM)).unwrap();
+        wm.add_window(WindowWithInfo::new_float(2, SOME_GEOM)).unwrap();
+        wm.toggle_minimised(1).unwrap();
+        // a minimised window can become urgent
+        wm.set_urgent(1, true).unwrap();
+        assert_eq!(wm.get_urgent_windows(), vec![1]);
+        // focusing it unminimises it and clears its urgency
+        wm.focus_urgent_window().unwrap();
+        assert_eq!(wm.get_focused_window(), Some(1));
+        assert!(!wm.is_minimised(1));
+        assert!(wm.get_urgent_windows().is_empty());
+    }
+
+    #[test]
+    fn cycling_the_frozen_history_through_minimised_windows() {
+        let mut wm = WMName::new(SCREEN);
+
+        wm.add_window(WindowWithInfo::new_tiled(1, SOME_GEOM)).unwrap();
+        wm.add_window(WindowWithInfo::new_float(2, SOME_GEOM)).unwrap();
+        wm.add_window(WindowWithInfo::new_tiled(3, SOME_GEOM)).unwrap();
+        wm.toggle_minimised(1).unwrap();
+        wm.toggle_minimised(2).unwrap();
+        assert_eq!(wm.get_focus_history(), vec![3, 2, 1]);
+        // going through the history like Alt-Tab does shows the minimised windows one by one
+        wm.set_focus_history_frozen(true);
+        wm.cycle_focus_history(PrevOrNext::Next);
+        assert_eq!(wm.get_focused_window(), Some(2));
+        assert_eq!(wm.get_minimised_windows(), vec![1]);
+        wm.cycle_focus_history(PrevOrNext::Next);
+        assert_eq!(wm.get_focused_window(), Some(1));
+        assert_eq!(wm.get_minimised_windows(), vec![2]);
+        // only the window chosen when the history is unfrozen stays unminimised
+        wm.set_focus_history_frozen(false);
+        assert_eq!(wm.get_focused_window(), Some(1));
+        assert_eq!(wm.get_minimised_windows(), vec![2]);
+        assert_eq!(wm.get_focus_history(), vec![1, 3, 2]);
+        // going back to a visible window minimises the one we peeked at
+        wm.set_focus_history_frozen(true);
+        wm.cycle_focus_history(PrevOrNext::Next);
+        wm.cycle_focus_history(PrevOrNext::Next);
+        assert_eq!(wm.get_focused_window(), Some(2));
+        wm.cycle_focus_history(PrevOrNext::Next);
+        assert_eq!(wm.get_focused_window(), Some(1));
+        wm.set_focus_history_frozen(false);
+        assert_eq!(wm.get_minimised_windows(), vec![2]);
+    }
+
     // To run these tests, run the command `cargo test` in the `solution`
     // directory.
 }
@@ -25,7 +25,9 @@
 
 
 use cplwm_api::types::*;
-use cplwm_api::wm::{FloatSupport, FullscreenSupport, MinimiseSupport, TilingSupport, WindowManager};
+use cplwm_api::wm::{DirectionalSupport, FloatSupport, FocusHistorySupport, FullscreenSupport,
+                    GapSupport, LayoutSupport, MinimiseSupport, TilingSupport, UrgencySupport,
+                    WindowManager};
 use d_minimising_windows::MinimiseWM;
 use wm_error::WMError;
 
@@ -118,7 +120,7 @@ impl WindowManager for FullWM {
     }
 
     /// If there is a fullscreen window return the layout containing only that window with the
-    /// geometry of the screen. Otherwise call the wrapped function.
+    /// geometry of the screen and without a border. Otherwise call the wrapped function.
     fn get_window_layout(&self) -> WindowLayout {
         let fullscreen = self.fullscreen_window;
         if fullscreen.is_none() {
@@ -129,6 +131,7 @@ impl WindowManager for FullWM {
         WindowLayout {
             focused_window: fullscreen,
             windows: vec![(fullscreen.unwrap(), self.get_screen().to_geometry())],
+            border_widths: vec![(fullscreen.unwrap(), 0)],
         }
     }
 
@@ -243,6 +246,179 @@ impl TilingSupport for FullWM {
         }
         self.minimise_wm.swap_windows(dir)
     }
+
+    /// Return the master ratio.
+    /// Call the wrapped function.
+    fn get_master_ratio(&self) -> MasterRatio {
+        self.minimise_wm.get_master_ratio()
+    }
+
+    /// Set the master ratio.
+    /// As for `cycle_layout`, a fullscreen window stays fullscreen.
+    fn set_master_ratio(&mut self, ratio: MasterRatio) {
+        self.minimise_wm.set_master_ratio(ratio)
+    }
+
+    /// Return the tabs of the wrapped WM, or no tabs when a window is fullscreen.
+    fn get_tabs(&self) -> Vec<Window> {
+        if self.fullscreen_window.is_some() {
+            Vec::new()
+        } else {
+            self.minimise_wm.get_tabs()
+        }
+    }
+}
+
+impl LayoutSupport for FullWM {
+    /// Switch to the previous or next layout.
+    /// A fullscreen window stays fullscreen, the new layout is applied in 'background'.
+    fn cycle_layout(&mut self, dir: PrevOrNext) {
+        self.minimise_wm.cycle_layout(dir)
+    }
+
+    /// Return the number of windows in the master column of the wrapped WM.
+    fn get_nmaster(&self) -> usize {
+        self.minimise_wm.get_nmaster()
+    }
+
+    /// Set the number of windows in the master column of the wrapped WM.
+    fn set_nmaster(&mut self, nmaster: usize) {
+        self.minimise_wm.set_nmaster(nmaster)
+    }
+
+    /// Return the weight of the window in the wrapped WM.
+    fn get_tile_weight(&self, window: Window) -> Result<TileWeight, Self::Error> {
+        self.minimise_wm.get_tile_weight(window)
+    }
+
+    /// Set the weight of the tile in the wrapped WM.
+    fn set_tile_weight(&mut self, window: Window, weight: TileWeight) -> Result<(), Self::Error> {
+        self.minimise_wm.set_tile_weight(window, weight)
+    }
+
+    /// Reset the weights of the tiles in the wrapped WM.
+    fn reset_tile_weights(&mut self) {
+        self.minimise_wm.reset_tile_weights()
+    }
+
+    /// Return the layout transforms of the wrapped WM.
+    fn get_layout_transforms(&self) -> Vec<LayoutTransform> {
+        self.minimise_wm.get_layout_transforms()
+    }
+
+    /// Toggle the layout transform in the wrapped WM.
+    fn toggle_layout_transform(&mut self, transform: LayoutTransform) {
+        self.minimise_wm.toggle_layout_transform(transform)
+    }
+}
+
+impl GapSupport for FullWM {
+    /// Return the gap size of the wrapped WM.
+    fn get_gap(&self) -> GapSize {
+        self.minimise_wm.get_gap()
+    }
+
+    /// Set the gap size of the wrapped WM.
+    fn set_gap(&mut self, gap: GapSize) {
+        self.minimise_wm.set_gap(gap)
+    }
+
+    /// Return the inner gap of the wrapped WM.
+    fn get_inner_gap(&self) -> GapSize {
+        self.minimise_wm.get_inner_gap()
+    }
+
+    /// Set the inner gap of the wrapped WM.
+    fn set_inner_gap(&mut self, gap: GapSize) {
+        self.minimise_wm.set_inner_gap(gap)
+    }
+
+    /// Return the outer gap of the wrapped WM.
+    fn get_outer_gap(&self) -> GapSize {
+        self.minimise_wm.get_outer_gap()
+    }
+
+    /// Set the outer gap of the wrapped WM.
+    fn set_outer_gap(&mut self, gap: GapSize) {
+        self.minimise_wm.set_outer_gap(gap)
+    }
+
+    /// Return the padding of the wrapped WM.
+    fn get_padding(&self) -> Padding {
+        self.minimise_wm.get_padding()
+    }
+
+    /// Set the padding of the wrapped WM.
+    fn set_padding(&mut self, padding: Padding) {
+        self.minimise_wm.set_padding(padding)
+    }
+
+    /// Return whether the wrapped WM uses smart gaps.
+    fn get_smart_gaps(&self) -> bool {
+        self.minimise_wm.get_smart_gaps()
+    }
+
+    /// Set whether the wrapped WM uses smart gaps.
+    fn set_smart_gaps(&mut self, smart_gaps: bool) {
+        self.minimise_wm.set_smart_gaps(smart_gaps)
+    }
+
+    /// Return whether the wrapped WM uses smart borders.
+    fn get_smart_borders(&self) -> bool {
+        self.minimise_wm.get_smart_borders()
+    }
+
+    /// Set whether the wrapped WM uses smart borders.
+    fn set_smart_borders(&mut self, smart_borders: bool) {
+        self.minimise_wm.set_smart_borders(smart_borders)
+    }
+}
+
+impl FocusHistorySupport for FullWM {
+    /// Call the wrapped function.
+    fn get_focus_history(&self) -> Vec<Window> {
+        self.minimise_wm.get_focus_history()
+    }
+
+    /// Call the wrapped function.
+    fn is_focus_history_frozen(&self) -> bool {
+        self.minimise_wm.is_focus_history_frozen()
+    }
+
+    /// Call the wrapped function.
+    fn set_focus_history_frozen(&mut self, frozen: bool) {
+        self.minimise_wm.set_focus_history_frozen(frozen)
+    }
+}
+
+impl UrgencySupport for FullWM {
+    /// Call the wrapped function.
+    fn get_urgent_windows(&self) -> Vec<Window> {
+        self.minimise_wm.get_urgent_windows()
+    }
+
+    /// Call the wrapped function.
+    fn set_urgent(&mut self, window: Window, urgent: bool) -> Result<(), Self::Error> {
+        self.minimise_wm.set_urgent(window, urgent)
+    }
+}
+
+impl DirectionalSupport for FullWM {
+    /// If there is a fullscreen window do nothing, it is the only visible window. Otherwise call
+    /// the wrapped function.
+    fn focus_direction(&mut self, direction: Direction) {
+        if self.fullscreen_window.is_none() {
+            self.minimise_wm.focus_direction(direction)
+        }
+    }
+
+    /// If there is a fullscreen window do nothing, it is the only visible window. Otherwise call
+    /// the wrapped function.
+    fn swap_direction(&mut self, direction: Direction) {
+        if self.fullscreen_window.is_none() {
+            self.minimise_wm.swap_direction(direction)
+        }
+    }
 }
 
 impl FloatSupport for FullWM {
@@ -377,8 +553,8 @@ impl FullscreenSupport for FullWM {
 mod tests {
 
     use super::WMName;
-    use cplwm_api::wm::{FloatSupport, FullscreenSupport, MinimiseSupport, TilingSupport,
-                        WindowManager};
+    use cplwm_api::wm::{DirectionalSupport, FloatSupport, FullscreenSupport, MinimiseSupport,
+                        TilingSupport, WindowManager};
     use cplwm_api::types::*;
 
     // We define a static variable for the screen we will use in the tests.
@@ -806,10 +982,14 @@ mod tests {
         let wl1 = wm.get_window_layout();
         // the geometry of the window should be the SCREEN_GEOM
         assert_eq!(wl1.windows, vec![(1, SCREEN_GEOM)]);
+        // ... without a border
+        assert_eq!(wl1.border_widths, vec![(1, 0)]);
         // toggle the window
         wm.toggle_fullscreen(1).unwrap();
         // now the window should be tiled, and the master window
         assert_eq!(wm.get_master_window().unwrap(), 1);
+        // ... with the default border
+        assert!(wm.get_window_layout().border_widths.is_empty());
         // make the window fullscreen again
         wm.toggle_fullscreen(1).unwrap();
         // add another fullscreen window
@@ -932,6 +1112,26 @@ mod tests {
         // it should return fullscreen
         assert_eq!(wm.get_fullscreen_window(), Some(2));
     }
+
+    #[test]
+    fn directional_fullscreen_windows() {
+        let mut wm = WMName::new(SCREEN);
+
+        wm.add_window(WindowWithInfo::new_tiled(1, SOME_GEOM)).unwrap();
+        wm.add_window(WindowWithInfo::new_fullscreen(2, SOME_GEOM)).unwrap();
+        // the fullscreen window is the only visible one, so nothing happens
+        wm.focus_direction(Direction::Left);
+        wm.swap_direction(Direction::Left);
+        assert_eq!(wm.get_focused_window(), Some(2));
+        assert_eq!(wm.get_fullscreen_window(), Some(2));
+        assert_eq!(wm.get_master_window(), Some(1));
+        // without the fullscreen window, the 1st is on the left of the 2nd
+        wm.toggle_fullscreen(2).unwrap();
+        wm.swap_direction(Direction::Left);
+        assert_eq!(wm.get_master_window(), Some(2));
+        wm.focus_direction(Direction::Right);
+        assert_eq!(wm.get_focused_window(), Some(1));
+    }
     // To run these tests, run the command `cargo test` in the `solution`
     // directory.
 }
@@ -21,19 +21,84 @@
 
 
 use cplwm_api::types::*;
-use cplwm_api::wm::{GapSupport, TilingSupport, WindowManager};
+use cplwm_api::wm::{DirectionalSupport, FocusHistorySupport, GapSupport, Layout, LayoutSupport,
+                    TilingSupport, UrgencySupport, WindowManager};
 use b_tiling_wm::TilingWM;
+use layout;
 use wm_error::WMError;
 /// The name of the Window Manger
 pub type WMName = GapsWM;
 
 /// Window Manager that supports gaps
+///
+/// The `FloatingWM` wraps this window manager, so the window managers wrapping that one support
+/// gaps as well.
 #[derive(RustcDecodable, RustcEncodable, Debug, Clone)]
 pub struct GapsWM {
-    /// A wrapper of the Fullscreen Windows Window Manager
+    /// A wrapper of the Tiling Window Manager
     pub tiling_wm: TilingWM,
-    /// The value of the gap, initially 0
-    pub gap: GapSize,
+    /// The space between two neighbouring tiles, initially 0
+    pub inner_gap: GapSize,
+    /// The space between the tiles and the padding, initially 0
+    pub outer_gap: GapSize,
+    /// The space reserved on every side of the screen
+    pub padding: Padding,
+    /// Whether a single shown tile gets no gaps
+    pub smart_gaps: bool,
+    /// Whether a single shown tile gets no border
+    pub smart_borders: bool,
+}
+
+impl GapsWM {
+    /// Return true when the current layout shows a single tile: when there is only one tile, or
+    /// when the layout has tabs.
+    fn shows_single_tile(&self) -> bool {
+        let tiles = &self.tiling_wm.tiles;
+        tiles.len() == 1 || (!tiles.is_empty() && self.tiling_wm.get_layout().has_tabs())
+    }
+
+    /// Return the area in which the tiles are arranged: the screen without the padding and the
+    /// outer gap. With smart gaps, there is no outer gap when a single tile is shown.
+    pub fn get_tiling_area(&self) -> Geometry {
+        let outer_gap = if self.smart_gaps && self.shows_single_tile() {
+            0
+        } else {
+            self.outer_gap
+        };
+        let outer_padding = Padding {
+            top: outer_gap,
+            bottom: outer_gap,
+            left: outer_gap,
+            right: outer_gap,
+        };
+        outer_padding.shrink(self.padding.shrink(self.tiling_wm.get_screen().to_geometry()))
+    }
+
+    /// Return the `WindowWithInfo` stored for the given window, see `TilingWM::get_stored_info`.
+    pub fn get_stored_info(&self, window: Window) -> Option<WindowWithInfo> {
+        self.tiling_wm.get_stored_info(window)
+    }
+
+    /// Return the `WindowWithInfo` stored for the given window, see
+    /// `TilingWM::get_stored_info_mut`.
+    pub fn get_stored_info_mut(&mut self, window: Window) -> Option<&mut WindowWithInfo> {
+        self.tiling_wm.get_stored_info_mut(window)
+    }
+
+    /// Add the given managed window to the back of the tiles.
+    pub fn add_tile(&mut self, window: Window) {
+        self.tiling_wm.add_tile(window)
+    }
+
+    /// Remove the given window from the tiles, together with its weight.
+    pub fn remove_tile(&mut self, window: Window) {
+        self.tiling_wm.remove_tile(window)
+    }
+
+    /// Swap the places of the two given tiles.
+    pub fn swap_tiles(&mut self, a: Window, b: Window) {
+        self.tiling_wm.swap_tiles(a, b)
+    }
 }
 
 impl WindowManager for GapsWM {
@@ -44,7 +109,11 @@ impl WindowManager for GapsWM {
     fn new(screen: Screen) -> GapsWM {
         GapsWM {
             tiling_wm: TilingWM::new(screen),
-            gap: (0 as GapSize),
+            inner_gap: 0,
+            outer_gap: 0,
+            padding: Padding::new(),
+            smart_gaps: false,
+            smart_borders: false,
         }
     }
 
@@ -70,21 +139,21 @@ impl WindowManager for GapsWM {
 
     /// Return the current window layout.
     ///
-    /// This is the only function that changes, if there is no gap return the layout from the
-    /// wrapped WM.
-    /// Otherwise add the gap to the x and y coordinates of each window geometry and remove gap*2
-    /// from the width and height of each window geometry.
+    /// This is the only function that changes: the wrapped WM arranges the tiles in the tiling
+    /// area instead of the whole screen, and then the tiles are separated by the inner gap.
+    /// With smart borders, a single shown tile has a border width of 0.
     fn get_window_layout(&self) -> WindowLayout {
         let mut layout = self.tiling_wm.get_window_layout();
-        if layout.windows.len() > 0 && self.gap > 0 {
-            for i in 0..layout.windows.len() {
-                layout.windows[i].1.x += self.gap as i32;
-                layout.windows[i].1.y += self.gap as i32;
-                layout.windows[i].1.width -= (self.gap * 2) as u32;
-                layout.windows[i].1.height -= (self.gap * 2) as u32;
-            }
+        if layout.windows.is_empty() {
             return layout;
         }
+        let area = self.get_tiling_area();
+        layout.windows = layout::add_inner_gaps(self.tiling_wm.arrange_tiles(area),
+                                                self.inner_gap,
+                                                area);
+        if self.smart_borders && self.shows_single_tile() {
+            layout.border_widths = layout.windows.iter().map(|&(w, _)| (w, 0)).collect();
+        }
         layout
     }
 
@@ -129,16 +198,166 @@ impl TilingSupport for GapsWM {
     fn swap_windows(&mut self, dir: PrevOrNext) {
         self.tiling_wm.swap_windows(dir)
     }
+
+    /// Return the master ratio.
+    fn get_master_ratio(&self) -> MasterRatio {
+        self.tiling_wm.get_master_ratio()
+    }
+
+    /// Set the master ratio.
+    fn set_master_ratio(&mut self, ratio: MasterRatio) {
+        self.tiling_wm.set_master_ratio(ratio)
+    }
+
+    /// Return the tabs of the wrapped WM.
+    fn get_tabs(&self) -> Vec<Window> {
+        self.tiling_wm.get_tabs()
+    }
+}
+
+impl LayoutSupport for GapsWM {
+    /// Switch to the previous or next layout.
+    fn cycle_layout(&mut self, dir: PrevOrNext) {
+        self.tiling_wm.cycle_layout(dir)
+    }
+
+    /// Return the number of windows in the master column of the wrapped WM.
+    fn get_nmaster(&self) -> usize {
+        self.tiling_wm.get_nmaster()
+    }
+
+    /// Set the number of windows in the master column of the wrapped WM.
+    fn set_nmaster(&mut self, nmaster: usize) {
+        self.tiling_wm.set_nmaster(nmaster)
+    }
+
+    /// Return the weight of the window in the wrapped WM.
+    fn get_tile_weight(&self, window: Window) -> Result<TileWeight, Self::Error> {
+        self.tiling_wm.get_tile_weight(window)
+    }
+
+    /// Set the weight of the tile in the wrapped WM.
+    fn set_tile_weight(&mut self, window: Window, weight: TileWeight) -> Result<(), Self::Error> {
+        self.tiling_wm.set_tile_weight(window, weight)
+    }
+
+    /// Reset the weights of the tiles in the wrapped WM.
+    fn reset_tile_weights(&mut self) {
+        self.tiling_wm.reset_tile_weights()
+    }
+
+    /// Return the layout transforms of the wrapped WM.
+    fn get_layout_transforms(&self) -> Vec<LayoutTransform> {
+        self.tiling_wm.get_layout_transforms()
+    }
+
+    /// Toggle the layout transform in the wrapped WM.
+    fn toggle_layout_transform(&mut self, transform: LayoutTransform) {
+        self.tiling_wm.toggle_layout_transform(transform)
+    }
 }
 
 impl GapSupport for GapsWM {
-    /// Return the current gap size. Initially 0.
+    /// Return the current gap size, i.e. the outer gap. Initially 0.
     fn get_gap(&self) -> GapSize {
-        self.gap
+        self.outer_gap
+    }
+
+    /// Set the gap size: the outer gap becomes `gap` and the inner gap twice as much.
+    fn set_gap(&mut self, gap: GapSize) {
+        self.outer_gap = gap;
+        self.inner_gap = 2 * gap;
+    }
+
+    /// Return the `inner_gap`.
+    fn get_inner_gap(&self) -> GapSize {
+        self.inner_gap
+    }
+
+    /// Set the `inner_gap`.
+    fn set_inner_gap(&mut self, gap: GapSize) {
+        self.inner_gap = gap;
+    }
+
+    /// Return the `outer_gap`.
+    fn get_outer_gap(&self) -> GapSize {
+        self.outer_gap
+    }
+
+    /// Set the `outer_gap`.
+    fn set_outer_gap(&mut self, gap: GapSize) {
+        self.outer_gap = gap;
     }
-    /// Set the gap size.
-    fn set_gap(&mut self, gapsize: GapSize) {
-        self.gap = gapsize;
+
+    /// Return the `padding`.
+    fn get_padding(&self) -> Padding {
+        self.padding
+    }
+
+    /// Set the `padding`.
+    fn set_padding(&mut self, padding: Padding) {
+        self.padding = padding;
+    }
+
+    /// Return `smart_gaps`.
+    fn get_smart_gaps(&self) -> bool {
+        self.smart_gaps
+    }
+
+    /// Set `smart_gaps`.
+    fn set_smart_gaps(&mut self, smart_gaps: bool) {
+        self.smart_gaps = smart_gaps;
+    }
+
+    /// Return `smart_borders`.
+    fn get_smart_borders(&self) -> bool {
+        self.smart_borders
+    }
+
+    /// Set `smart_borders`.
+    fn set_smart_borders(&mut self, smart_borders: bool) {
+        self.smart_borders = smart_borders;
+    }
+}
+
+impl FocusHistorySupport for GapsWM {
+    /// Call the wrapped function.
+    fn get_focus_history(&self) -> Vec<Window> {
+        self.tiling_wm.get_focus_history()
+    }
+
+    /// Call the wrapped function.
+    fn is_focus_history_frozen(&self) -> bool {
+        self.tiling_wm.is_focus_history_frozen()
+    }
+
+    /// Call the wrapped function.
+    fn set_focus_history_frozen(&mut self, frozen: bool) {
+        self.tiling_wm.set_focus_history_frozen(frozen)
+    }
+}
+
+impl UrgencySupport for GapsWM {
+    /// Call the wrapped function.
+    fn get_urgent_windows(&self) -> Vec<Window> {
+        self.tiling_wm.get_urgent_windows()
+    }
+
+    /// Call the wrapped function.
+    fn set_urgent(&mut self, window: Window, urgent: bool) -> Result<(), Self::Error> {
+        self.tiling_wm.set_urgent(window, urgent)
+    }
+}
+
+impl DirectionalSupport for GapsWM {
+    /// Call the wrapped function.
+    fn focus_direction(&mut self, direction: Direction) {
+        self.tiling_wm.focus_direction(direction)
+    }
+
+    /// Call the wrapped function.
+    fn swap_direction(&mut self, direction: Direction) {
+        self.tiling_wm.swap_direction(direction)
     }
 }
 
@@ -146,7 +365,8 @@ impl GapSupport for GapsWM {
 mod tests {
 
     use super::WMName;
-    use cplwm_api::wm::{GapSupport, TilingSupport, WindowManager};
+    use layout::TilingLayout;
+    use cplwm_api::wm::{GapSupport, LayoutSupport, TilingSupport, WindowManager};
     use cplwm_api::types::*;
 
     // We define a static variable for the screen we will use in the tests.
@@ -438,6 +658,117 @@ mod tests {
         assert_eq!(master6, Some(1));
     }
 
+    #[test]
+    fn test_gaps_and_padding() {
+        // Let's make a new `GapsWM` with `SCREEN` as screen.
+        let mut wm = WMName::new(SCREEN);
+        for w in 1..4 {
+            wm.add_window(WindowWithInfo::new_tiled(w, SOME_GEOM)).unwrap();
+        }
+        // Reserve room for a bar at the top, with small gaps at the edges of the screen and
+        // larger ones between the tiles
+        let padding = Padding { top: 20, ..Padding::new() };
+        wm.set_padding(padding);
+        wm.set_outer_gap(5);
+        wm.set_inner_gap(10);
+        assert_eq!(padding, wm.get_padding());
+        assert_eq!(5, wm.get_gap());
+        let three_tiles_layout: Vec<(Window, Geometry)> = vec![(1,
+                                                                Geometry {
+                                                                   x: 5,
+                                                                   y: 25,
+                                                                   width: 390,
+                                                                   height: 570,
+                                                               }),
+                                                               (2,
+                                                                Geometry {
+                                                                   x: 405,
+                                                                   y: 25,
+                                                                   width: 390,
+                                                                   height: 280,
+                                                               }),
+                                                               (3,
+                                                                Geometry {
+                                                                   x: 405,
+                                                                   y: 315,
+                                                                   width: 390,
+                                                                   height: 280,
+                                                               })];
+        assert_eq!(three_tiles_layout, wm.get_window_layout().windows);
+
+        // The gaps are the same whatever the layout
+        wm.toggle_layout_transform(LayoutTransform::MirrorHorizontally);
+        assert_eq!(Geometry {
+                       x: 405,
+                       y: 25,
+                       width: 390,
+                       height: 570,
+                   },
+                   wm.get_window_layout().windows[0].1);
+        wm.toggle_layout_transform(LayoutTransform::MirrorHorizontally);
+
+        // `set_gap` surrounds every tile by the same space
+        wm.set_padding(Padding::new());
+        wm.set_gap(5);
+        assert_eq!(10, wm.get_inner_gap());
+        assert_eq!(Geometry {
+                       x: 5,
+                       y: 5,
+                       width: 390,
+                       height: 590,
+                   },
+                   wm.get_window_layout().windows[0].1);
+    }
+
+    #[test]
+    fn test_smart_gaps_and_borders() {
+        // Let's make a new `GapsWM` with `SCREEN` as screen, a bar at the top and gaps.
+        let mut wm = WMName::new(SCREEN);
+        wm.set_padding(Padding { top: 20, ..Padding::new() });
+        wm.set_outer_gap(5);
+        wm.add_window(WindowWithInfo::new_tiled(1, SOME_GEOM)).unwrap();
+        // Both are off initially
+        assert!(!wm.get_smart_gaps());
+        assert!(!wm.get_smart_borders());
+        assert_eq!(vec![(1,
+                         Geometry {
+                            x: 5,
+                            y: 25,
+                            width: 790,
+                            height: 570,
+                        })],
+                   wm.get_window_layout().windows);
+        assert!(wm.get_window_layout().border_widths.is_empty());
+
+        // A single tile gets no gaps, but the padding stays
+        wm.set_smart_gaps(true);
+        assert_eq!(vec![(1,
+                         Geometry {
+                            x: 0,
+                            y: 20,
+                            width: 800,
+                            height: 580,
+                        })],
+                   wm.get_window_layout().windows);
+        // ... and no border
+        wm.set_smart_borders(true);
+        assert_eq!(vec![(1, 0)], wm.get_window_layout().border_widths);
+
+        // With two tiles, the gaps and the borders come back
+        wm.add_window(WindowWithInfo::new_tiled(2, SOME_GEOM)).unwrap();
+        let window_layout = wm.get_window_layout();
+        assert_eq!(5, window_layout.windows[0].1.x);
+        assert!(window_layout.border_widths.is_empty());
+
+        // A layout with tabs shows a single tile
+        while wm.tiling_wm.get_layout() != TilingLayout::Monocle {
+            wm.cycle_layout(PrevOrNext::Next);
+        }
+        let window_layout = wm.get_window_layout();
+        assert_eq!(0, window_layout.windows[0].1.x);
+        assert_eq!(vec![(2, 0)], window_layout.border_widths);
+    }
+
     // To run these tests, run the command `cargo test` in the `solution`
     // directory.
 }
@@ -27,11 +27,16 @@
 // Add imports here
 
 use cplwm_api::types::*;
-use cplwm_api::wm::{FloatSupport, FullscreenSupport, MinimiseSupport, MultiWorkspaceSupport,
-                    TilingSupport, WindowManager};
+use cplwm_api::wm::{DirectionalSupport, FloatSupport, FocusHistorySupport, FullscreenSupport,
+                    GapSupport, LayoutSupport, MinimiseSupport, MultiScreenSupport,
+                    MultiWorkspaceSupport, ScratchpadSupport, TilingSupport, UrgencySupport,
+                    WindowManager};
 use e_fullscreen_windows::FullWM;
 use wm_error::WMError;
 
+use std::cmp::{max, min};
+use std::os::raw::c_int;
+
 /// Name of the WM
 pub type WMName = MultiWorkspaceWM;
 /// Window Manager to extend
@@ -42,39 +47,60 @@ pub type WM = FullWM;
 pub struct MultiWorkspaceWM {
     /// `Vec` of the different workspaces
     workspaces: Vec<WM>,
+    /// The names of the workspaces, in the same order as `workspaces`
+    names: Vec<String>,
     /// Index of the current workspaces
     index: WorkspaceIndex,
+    /// Index of the workspace that was current before the last switch
+    previous: Option<WorkspaceIndex>,
+    /// The scratchpad window, which belongs to no workspace. While it is shown, it floats on the
+    /// current workspace, otherwise only this field contains it.
+    scratchpad: Option<WindowWithInfo>,
+    /// Whether workspaces are deleted at the end of the operation that leaves them empty and
+    /// hidden.
+    delete_empty: bool,
+    /// The geometries of the screens, in the coordinates of the root window
+    screens: Vec<Geometry>,
+    /// The index of the workspace shown on each screen, in the same order as `screens`. The
+    /// current workspace is always one of them.
+    visible: Vec<WorkspaceIndex>,
 }
 
 impl WindowManager for MultiWorkspaceWM {
     type Error = WMError;
 
-    /// create MAX_WORKSPACE_INDEX + 1 workspaces, then initialize the WM with 0 as current workspace index
+    /// Create a single workspace named "1", the others are created on demand. It is shown on a
+    /// single screen of the given size.
     fn new(screen: Screen) -> MultiWorkspaceWM {
-        let mut wms: Vec<WM> = Vec::new();
-        let mut i = 0;
-        loop {
-            wms.push(WM::new(screen));
-            if i == MAX_WORKSPACE_INDEX {
-                break;
-            }
-            i += 1;
-        }
         MultiWorkspaceWM {
-            workspaces: wms,
+            workspaces: vec![WM::new(screen)],
+            names: vec![String::from("1")],
             index: 0,
+            previous: None,
+            scratchpad: None,
+            delete_empty: false,
+            screens: vec![Geometry {
+                              x: 0,
+                              y: 0,
+                              width: screen.width,
+                              height: screen.height,
+                          }],
+            visible: vec![0],
         }
     }
 
     /// Return all the windows managed by the current workspace.
-    /// Retrieve all the windows from each workspace.
+    /// Retrieve all the windows from each workspace, and the hidden scratchpad.
     fn get_windows(&self) -> Vec<Window> {
         let mut windows: Vec<Window> = Vec::new();
         let mut partial: Vec<Window>;
-        for i in 0..MAX_WORKSPACE_INDEX {
-            partial = self.workspaces[i].get_windows().clone();
+        for workspace in &self.workspaces {
+            partial = workspace.get_windows().clone();
             windows.append(&mut partial);
         }
+        if let Some(scratchpad) = self.get_hidden_scratchpad() {
+            windows.push(scratchpad.window);
+        }
         windows
     }
 
@@ -92,30 +118,46 @@ impl WindowManager for MultiWorkspaceWM {
     }
 
     /// If the window is managed find it in the different workspaces and remove it from the
-    /// workspaces.
+    /// workspaces. Removing the scratchpad window leaves no scratchpad. Removing the last window
+    /// of a hidden workspace can delete it, see `set_delete_empty_workspaces`.
     fn remove_window(&mut self, window: Window) -> Result<(), Self::Error> {
         if !self.is_managed(window) {
             return Err(WMError::UnknownWindow(window));
         }
-
-        for i in 0..MAX_WORKSPACE_INDEX {
-            if self.workspaces[i].is_managed(window) {
-                self.workspaces[i].remove_window(window).unwrap();
-                break;
-            }
-        }
+        let emptied = self.take_window(window);
+        self.prune_empty_workspaces(emptied.into_iter().collect());
         Ok(())
     }
 
-    /// Return the WindowLayout of the current workspace.
+    /// Combine the WindowLayouts of the workspaces shown on the screens, every window is moved to
+    /// the position of its screen. The current workspace comes last, its focused window is the
+    /// focused one.
     fn get_window_layout(&self) -> WindowLayout {
-        self.workspaces[self.index].get_window_layout()
+        let current_screen = self.get_current_screen_index();
+        let mut order: Vec<ScreenIndex> = (0..self.screens.len())
+            .filter(|&screen| screen != current_screen)
+            .collect();
+        order.push(current_screen);
+        let mut window_layout = WindowLayout::new();
+        for screen in order {
+            let origin = self.screens[screen];
+            let mut layout = self.workspaces[self.visible[screen]].get_window_layout();
+            for (window, mut geometry) in layout.windows {
+                geometry.x += origin.x;
+                geometry.y += origin.y;
+                window_layout.windows.push((window, geometry));
+            }
+            window_layout.border_widths.append(&mut layout.border_widths);
+            window_layout.focused_window = layout.focused_window;
+        }
+        window_layout
     }
 
     /// If the given window is `None` remove the focus from the given workspace
     /// If it is `Some`, if it's not managed return an error.
     /// Otherwise find the workspace that contains it and call focus_window on that workspace.
     /// If it's not the current one switch to that workspace.
+    /// Focusing the hidden scratchpad shows it.
     fn focus_window(&mut self, window: Option<Window>) -> Result<(), Self::Error> {
         if window.is_none() {
             return self.workspaces[self.index].focus_window(window);
@@ -123,8 +165,12 @@ impl WindowManager for MultiWorkspaceWM {
         if !self.is_managed(window.unwrap()) {
             return Err(WMError::UnknownWindow(window.unwrap()));
         }
+        if self.get_hidden_scratchpad().map(|info| info.window) == window {
+            self.show_scratchpad();
+            return Ok(());
+        }
 
-        for i in 0..MAX_WORKSPACE_INDEX {
+        for i in 0..self.workspaces.len() {
             if self.workspaces[i].is_managed(window.unwrap()) {
                 self.workspaces[i].focus_window(window).unwrap();
                 if i != self.index {
@@ -146,20 +192,29 @@ impl WindowManager for MultiWorkspaceWM {
         if !self.is_managed(window) {
             return Err(WMError::UnknownWindow(window));
         }
-        let index = self.find_index(window);
+        if let Some(scratchpad) = self.get_hidden_scratchpad() {
+            if scratchpad.window == window {
+                return Ok(scratchpad);
+            }
+        }
+        let index = self.find_index(window).unwrap();
         self.workspaces[index].get_window_info(window)
     }
 
-    /// Return the screen managed by the window manager.
+    /// Return the screen of the current workspace, i.e. the size of the current screen.
     fn get_screen(&self) -> Screen {
         self.workspaces[self.index].get_screen()
     }
 
-    /// Resize the screen according to the given Screen in every workspace.
+    /// Replace the screens with a single one of the given size, see `set_screens`.
     fn resize_screen(&mut self, screen: Screen) {
-        for i in 0..(MAX_WORKSPACE_INDEX + 1) {
-            self.workspaces[i].resize_screen(screen);
-        }
+        let geometry = Geometry {
+            x: 0,
+            y: 0,
+            width: screen.width,
+            height: screen.height,
+        };
+        self.set_screens(vec![geometry]).unwrap();
     }
 }
 
@@ -177,28 +232,220 @@ impl TilingSupport for MultiWorkspaceWM {
     fn swap_windows(&mut self, dir: PrevOrNext) {
         self.workspaces[self.index].swap_windows(dir);
     }
+    /// Return the master ratio of the current workspace.
+    fn get_master_ratio(&self) -> MasterRatio {
+        self.workspaces[self.index].get_master_ratio()
+    }
+    /// Call `set_master_ratio` on the current workspace, the other workspaces keep their own.
+    fn set_master_ratio(&mut self, ratio: MasterRatio) {
+        self.workspaces[self.index].set_master_ratio(ratio);
+    }
+    /// Return the tabs of the current workspace.
+    fn get_tabs(&self) -> Vec<Window> {
+        self.workspaces[self.index].get_tabs()
+    }
+}
+
+impl LayoutSupport for MultiWorkspaceWM {
+    /// Call `cycle_layout` on the current workspace.
+    fn cycle_layout(&mut self, dir: PrevOrNext) {
+        self.workspaces[self.index].cycle_layout(dir);
+    }
+    /// Return the number of windows in the master column of the current workspace.
+    fn get_nmaster(&self) -> usize {
+        self.workspaces[self.index].get_nmaster()
+    }
+    /// Call `set_nmaster` on the current workspace.
+    fn set_nmaster(&mut self, nmaster: usize) {
+        self.workspaces[self.index].set_nmaster(nmaster);
+    }
+    /// Return the weight of the window in the workspace that contains it. The hidden scratchpad
+    /// is on no workspace, it has the `DEFAULT_TILE_WEIGHT` like any other float.
+    fn get_tile_weight(&self, window: Window) -> Result<TileWeight, Self::Error> {
+        if !self.is_managed(window) {
+            return Err(WMError::UnknownWindow(window));
+        }
+        match self.find_index(window) {
+            Some(index) => self.workspaces[index].get_tile_weight(window),
+            None => Ok(DEFAULT_TILE_WEIGHT),
+        }
+    }
+    /// Set the weight of the tile in the workspace that contains it. The hidden scratchpad is on
+    /// no workspace and isn't a tile, so its weight isn't set.
+    fn set_tile_weight(&mut self, window: Window, weight: TileWeight) -> Result<(), Self::Error> {
+        if !self.is_managed(window) {
+            return Err(WMError::UnknownWindow(window));
+        }
+        match self.find_index(window) {
+            Some(index) => self.workspaces[index].set_tile_weight(window, weight),
+            None => Ok(()),
+        }
+    }
+    /// Call `reset_tile_weights` on the current workspace.
+    fn reset_tile_weights(&mut self) {
+        self.workspaces[self.index].reset_tile_weights();
+    }
+    /// Return the layout transforms of the current workspace.
+    fn get_layout_transforms(&self) -> Vec<LayoutTransform> {
+        self.workspaces[self.index].get_layout_transforms()
+    }
+    /// Call `toggle_layout_transform` on the current workspace, the other workspaces keep their
+    /// own transforms.
+    fn toggle_layout_transform(&mut self, transform: LayoutTransform) {
+        self.workspaces[self.index].toggle_layout_transform(transform);
+    }
+}
+
+impl GapSupport for MultiWorkspaceWM {
+    /// Return the gap size of the current workspace, all the workspaces have the same.
+    fn get_gap(&self) -> GapSize {
+        self.workspaces[self.index].get_gap()
+    }
+    /// Set the gap size in every workspace.
+    fn set_gap(&mut self, gap: GapSize) {
+        for workspace in &mut self.workspaces {
+            workspace.set_gap(gap);
+        }
+    }
+    /// Return the inner gap of the current workspace, all the workspaces have the same.
+    fn get_inner_gap(&self) -> GapSize {
+        self.workspaces[self.index].get_inner_gap()
+    }
+    /// Set the inner gap in every workspace.
+    fn set_inner_gap(&mut self, gap: GapSize) {
+        for workspace in &mut self.workspaces {
+            workspace.set_inner_gap(gap);
+        }
+    }
+    /// Return the outer gap of the current workspace, all the workspaces have the same.
+    fn get_outer_gap(&self) -> GapSize {
+        self.workspaces[self.index].get_outer_gap()
+    }
+    /// Set the outer gap in every workspace.
+    fn set_outer_gap(&mut self, gap: GapSize) {
+        for workspace in &mut self.workspaces {
+            workspace.set_outer_gap(gap);
+        }
+    }
+    /// Return the padding of the current workspace, all the workspaces have the same.
+    fn get_padding(&self) -> Padding {
+        self.workspaces[self.index].get_padding()
+    }
+    /// Set the padding in every workspace, the external bars are visible on all of them.
+    fn set_padding(&mut self, padding: Padding) {
+        for workspace in &mut self.workspaces {
+            workspace.set_padding(padding);
+        }
+    }
+    /// Return whether the current workspace uses smart gaps, all the workspaces have the same.
+    fn get_smart_gaps(&self) -> bool {
+        self.workspaces[self.index].get_smart_gaps()
+    }
+    /// Set whether every workspace uses smart gaps.
+    fn set_smart_gaps(&mut self, smart_gaps: bool) {
+        for workspace in &mut self.workspaces {
+            workspace.set_smart_gaps(smart_gaps);
+        }
+    }
+    /// Return whether the current workspace uses smart borders, all the workspaces have the same.
+    fn get_smart_borders(&self) -> bool {
+        self.workspaces[self.index].get_smart_borders()
+    }
+    /// Set whether every workspace uses smart borders.
+    fn set_smart_borders(&mut self, smart_borders: bool) {
+        for workspace in &mut self.workspaces {
+            workspace.set_smart_borders(smart_borders);
+        }
+    }
+}
+
+impl FocusHistorySupport for MultiWorkspaceWM {
+    /// Return the focus history of the current workspace, every workspace has its own.
+    fn get_focus_history(&self) -> Vec<Window> {
+        self.workspaces[self.index].get_focus_history()
+    }
+    /// Return whether the focus history of the current workspace is frozen.
+    fn is_focus_history_frozen(&self) -> bool {
+        self.workspaces[self.index].is_focus_history_frozen()
+    }
+    /// Freeze or unfreeze the focus history of every workspace, so no workspace stays frozen when
+    /// the user switches workspaces in the meantime.
+    fn set_focus_history_frozen(&mut self, frozen: bool) {
+        for workspace in &mut self.workspaces {
+            workspace.set_focus_history_frozen(frozen);
+        }
+    }
+}
+
+impl UrgencySupport for MultiWorkspaceWM {
+    /// Return the urgent windows of all the workspaces, starting with the current workspace and
+    /// followed by the next ones. The urgent windows of a workspace are in the order in which
+    /// they became urgent.
+    fn get_urgent_windows(&self) -> Vec<Window> {
+        let mut urgent: Vec<Window> = Vec::new();
+        for i in 0..self.workspaces.len() {
+            let index = (self.index + i) % self.workspaces.len();
+            urgent.append(&mut self.workspaces[index].get_urgent_windows());
+        }
+        urgent
+    }
+    /// Mark the window as (not) urgent in the workspace that contains it. The hidden scratchpad
+    /// is on no workspace, so it can't become urgent.
+    fn set_urgent(&mut self, window: Window, urgent: bool) -> Result<(), Self::Error> {
+        if !self.is_managed(window) {
+            return Err(WMError::UnknownWindow(window));
+        }
+        if self.get_hidden_scratchpad().map(|info| info.window) == Some(window) {
+            return Ok(());
+        }
+        let index = self.find_index(window).unwrap();
+        self.workspaces[index].set_urgent(window, urgent)
+    }
+    /// Focus the first urgent window, see `get_urgent_windows`, and switch to the workspace that
+    /// contains it.
+    fn focus_urgent_window(&mut self) -> Result<(), Self::Error> {
+        match self.get_urgent_windows().first() {
+            Some(&window) => self.focus_window(Some(window)),
+            None => Ok(()),
+        }
+    }
+}
+
+impl DirectionalSupport for MultiWorkspaceWM {
+    /// Focus a window in the given direction in the current workspace.
+    fn focus_direction(&mut self, direction: Direction) {
+        self.workspaces[self.index].focus_direction(direction)
+    }
+    /// Swap the focused window with a window in the given direction in the current workspace.
+    fn swap_direction(&mut self, direction: Direction) {
+        self.workspaces[self.index].swap_direction(direction)
+    }
 }
 
 impl FloatSupport for MultiWorkspaceWM {
     /// Return the VISIBLE floating windows of all the workspaces.
     fn get_floating_windows(&self) -> Vec<Window> {
         let mut floats: Vec<Window> = Vec::new();
-        for i in 0..MAX_WORKSPACE_INDEX {
-            let mut current = self.workspaces[i].get_floating_windows().clone();
+        for workspace in &self.workspaces {
+            let mut current = workspace.get_floating_windows().clone();
             floats.append(&mut current);
         }
         floats
     }
 
-    /// If the window is managed call `toggle_floating` on the workspace that manages it.
+    /// If the window is managed call `toggle_floating` on the workspace that manages it. The
+    /// hidden scratchpad is on no workspace, it can't be toggled.
     fn toggle_floating(&mut self, window: Window) -> Result<(), Self::Error> {
-        if !self.is_managed(window) {
-            return Err(WMError::UnknownWindow(window));
+        match self.find_index(window) {
+            Some(index) => self.workspaces[index].toggle_floating(window),
+            None if self.is_managed(window) => Err(WMError::HiddenScratchpad(window)),
+            None => Err(WMError::UnknownWindow(window)),
         }
-        let index = self.find_index(window);
-        self.workspaces[index].toggle_floating(window)
     }
     /// If the window is managed call `set_window_geometry` on the workspaces that manages it.
+    /// The geometry is relative to the screen showing that workspace, like the one of
+    /// `get_window_info`. The hidden scratchpad only remembers the size of the new geometry, it is
+    /// centred when it is shown.
     fn set_window_geometry(&mut self,
                            window: Window,
                            new_geometry: Geometry)
@@ -207,7 +454,14 @@ impl FloatSupport for MultiWorkspaceWM {
         if !self.is_managed(window) {
             return Err(WMError::UnknownWindow(window));
         }
-        let index = self.find_index(window);
+        if let Some(mut scratchpad) = self.get_hidden_scratchpad() {
+            if scratchpad.window == window {
+                scratchpad.geometry = new_geometry;
+                self.scratchpad = Some(scratchpad);
+                return Ok(());
+            }
+        }
+        let index = self.find_index(window).unwrap();
         self.workspaces[index].set_window_geometry(window, new_geometry)
     }
 }
@@ -216,8 +470,8 @@ impl MinimiseSupport for MultiWorkspaceWM {
     /// Return all the minimised window.
     fn get_minimised_windows(&self) -> Vec<Window> {
         let mut min: Vec<Window> = Vec::new();
-        for i in 0..MAX_WORKSPACE_INDEX {
-            let mut current = self.workspaces[i].get_minimised_windows().clone();
+        for workspace in &self.workspaces {
+            let mut current = workspace.get_minimised_windows().clone();
             min.append(&mut current);
         }
         min
@@ -225,14 +479,18 @@ impl MinimiseSupport for MultiWorkspaceWM {
 
     /// Call `toggle_minimised` on the workspaces that contains the window.
     /// If the window is going to be unminimised and the workspace that contains it is not the
-    /// current one, switch to that workspace.
+    /// current one, switch to that workspace. The hidden scratchpad is on no workspace, it can't
+    /// be minimised.
     fn toggle_minimised(&mut self, window: Window) -> Result<(), Self::Error> {
-        if !self.is_managed(window) {
-            return Err(WMError::UnknownWindow(window));
-        }
-        let index = self.find_index(window);
+        let mut index = match self.find_index(window) {
+            Some(index) => index,
+            None if self.is_managed(window) => return Err(WMError::HiddenScratchpad(window)),
+            None => return Err(WMError::UnknownWindow(window)),
+        };
         if index != self.index && self.is_minimised(window) {
             self.switch_workspace(index).unwrap();
+            // Switching can delete an empty workspace before it
+            index = self.index;
         }
 
         self.workspaces[index].toggle_minimised(window)
@@ -248,16 +506,22 @@ impl FullscreenSupport for MultiWorkspaceWM {
     /// If it's the current fullscreen window, toggle it.
     /// Otherwise find the workspace that manages it and call the `toggle_fullscreen` on that
     /// workspace. If the workspace is different from the current one swith to that workspace.
+    /// The hidden scratchpad is shown on the current workspace first.
     fn toggle_fullscreen(&mut self, window: Window) -> Result<(), Self::Error> {
         if !self.is_managed(window) {
             return Err(WMError::UnknownWindow(window));
         } else if self.get_fullscreen_window() == Some(window) {
             self.workspaces[self.index].toggle_fullscreen(window)
         } else {
-            let index = self.find_index(window);
+            if self.get_hidden_scratchpad().map(|info| info.window) == Some(window) {
+                self.show_scratchpad();
+            }
+            let index = self.find_index(window).unwrap();
             if index != self.index {
                 self.switch_workspace(index).unwrap();
             }
+            // Switching can delete an empty workspace before it
+            let index = self.index;
             self.workspaces[index].toggle_fullscreen(window)
         }
     }
@@ -269,53 +533,471 @@ impl MultiWorkspaceSupport<WM> for MultiWorkspaceWM {
         self.index
     }
 
-    /// Return an error if index exceeds the `MAX_WORKSPACE_INDEX`.
+    /// Return the index of the previous workspace.
+    fn get_previous_workspace_index(&self) -> Option<WorkspaceIndex> {
+        self.previous
+    }
+
+    /// Return the number of workspaces.
+    fn get_workspace_count(&self) -> usize {
+        self.workspaces.len()
+    }
+
+    /// Return an error if there is no workspace at the index.
     /// Otherwise return the current workspace.
     fn get_workspace(&self, index: WorkspaceIndex) -> Result<&WM, Self::Error> {
-        if index > MAX_WORKSPACE_INDEX {
+        if index >= self.workspaces.len() {
             return Err(WMError::WorkspaceIndexNotValid(index));
         }
         Ok(&self.workspaces[index])
     }
 
-    /// Return an error if index exceeds the `MAX_WORKSPACE_INDEX`.
+    /// Return an error if there is no workspace at the index.
     /// Otherwise return the current workspace as mutable.
     fn get_workspace_mut(&mut self, index: WorkspaceIndex) -> Result<&mut WM, Self::Error> {
-        if index > MAX_WORKSPACE_INDEX {
+        if index >= self.workspaces.len() {
             return Err(WMError::WorkspaceIndexNotValid(index));
         }
         Ok(&mut self.workspaces[index])
     }
 
-    /// Return an error if index exceeds the `MAX_WORKSPACE_INDEX`.
+    /// Return an error if there is no workspace at the index.
     /// If the workspace is the same as the current one, do nothing.
-    /// If is different, if there is a fullscreen window, toggle it (to respect the invariant).
+    /// A shown scratchpad is hidden, it belongs to no workspace.
+    /// A workspace shown on another screen makes that screen the current one, a hidden workspace
+    /// is shown on the current screen instead of the current workspace. When the workspace we
+    /// leave becomes hidden and has a fullscreen window, it is toggled (to respect the
+    /// invariant), a workspace that stays shown on its screen keeps it.
+    /// The workspace we leave becomes the previous one, the focused window of the new current
+    /// one is no longer urgent.
+    /// When it becomes hidden while empty it can be deleted, see `set_delete_empty_workspaces`,
+    /// so the index of the new current workspace can be one less than the given one.
     fn switch_workspace(&mut self, index: WorkspaceIndex) -> Result<(), Self::Error> {
-        if index > MAX_WORKSPACE_INDEX {
+        if index >= self.workspaces.len() {
             return Err(WMError::WorkspaceIndexNotValid(index));
         } else if index == self.index {
             return Ok(());
         }
-        if self.get_fullscreen_window().is_some() {
-            let fullscreen = self.get_fullscreen_window().unwrap();
-            self.toggle_fullscreen(fullscreen).unwrap();
+        let hidden = self.enter_workspace(index);
+        self.prune_empty_workspaces(hidden.into_iter().collect());
+        Ok(())
+    }
+
+    /// Return an error if there is no workspace at the index or if the window is already
+    /// managed. Otherwise add the window to the workspace at the given index.
+    fn add_window_to_workspace(&mut self,
+                               index: WorkspaceIndex,
+                               window_with_info: WindowWithInfo)
+                               -> Result<(), Self::Error> {
+        if index >= self.workspaces.len() {
+            return Err(WMError::WorkspaceIndexNotValid(index));
+        } else if self.is_managed(window_with_info.window) {
+            return Err(WMError::AlreadyManagedWindow(window_with_info.window));
         }
-        self.index = index;
+        self.workspaces[index].add_window(window_with_info)
+    }
+
+    /// Return an error if the window isn't managed or if there is no workspace at the index.
+    /// Otherwise remove the window from its workspace and add it to the other one, then restore the
+    /// state `add_window` doesn't know about: the tile weight and whether it was minimised.
+    /// The workspace the window leaves can only be deleted once the window is on the target
+    /// workspace, so the target, even a new empty one, is never deleted and its index stays
+    /// valid. The hidden scratchpad is on no workspace, moving it makes it an ordinary window of
+    /// the target workspace.
+    fn move_window_to_workspace(&mut self,
+                                window: Window,
+                                index: WorkspaceIndex,
+                                follow: bool)
+                                -> Result<(), Self::Error> {
+        if !self.is_managed(window) {
+            return Err(WMError::UnknownWindow(window));
+        } else if index >= self.workspaces.len() {
+            return Err(WMError::WorkspaceIndexNotValid(index));
+        }
+        // The workspaces that may be left empty and hidden, they are only deleted at the end
+        let mut candidates = Vec::new();
+        if self.get_window_workspace(window) != Some(index) {
+            let mut window_with_info = try!(self.get_window_info(window));
+            // The info of a window doesn't say whether it is fullscreen now
+            window_with_info.fullscreen = self.get_window_workspace(window)
+                .map_or(false, |i| self.workspaces[i].get_fullscreen_window() == Some(window));
+            let weight = self.get_tile_weight(window).unwrap_or(DEFAULT_TILE_WEIGHT);
+            let minimised = self.is_minimised(window);
+            candidates.extend(self.take_window(window));
+            try!(self.workspaces[index].add_window(window_with_info));
+            try!(self.workspaces[index].set_tile_weight(window, weight));
+            if minimised {
+                try!(self.workspaces[index].toggle_minimised(window));
+            }
+        }
+        if follow {
+            if index != self.index {
+                candidates.extend(self.enter_workspace(index));
+            }
+            if !self.is_minimised(window) {
+                try!(self.focus_window(Some(window)));
+            }
+        }
+        self.prune_empty_workspaces(candidates);
+        Ok(())
+    }
+
+    /// Return the name of the workspace at the given index.
+    fn get_workspace_name(&self, index: WorkspaceIndex) -> Result<String, Self::Error> {
+        if index >= self.workspaces.len() {
+            return Err(WMError::WorkspaceIndexNotValid(index));
+        }
+        Ok(self.names[index].clone())
+    }
+
+    /// Check the name, then add a new workspace with the settings of the current one, see
+    /// `new_workspace`.
+    fn create_workspace(&mut self, name: &str) -> Result<WorkspaceIndex, Self::Error> {
+        try!(self.check_workspace_name(name));
+        let workspace = self.new_workspace();
+        self.workspaces.push(workspace);
+        self.names.push(String::from(name));
+        Ok(self.workspaces.len() - 1)
+    }
+
+    /// Return an error if there is no workspace at the index, otherwise check the name, unless it
+    /// is the name the workspace already has.
+    fn rename_workspace(&mut self, index: WorkspaceIndex, name: &str) -> Result<(), Self::Error> {
+        if index >= self.workspaces.len() {
+            return Err(WMError::WorkspaceIndexNotValid(index));
+        } else if self.names[index] == name {
+            return Ok(());
+        }
+        try!(self.check_workspace_name(name));
+        self.names[index] = String::from(name);
+        Ok(())
+    }
+
+    /// Return an error if there is no workspace at the index, if it is the current one, if it is
+    /// shown on another screen or if it still contains windows. Otherwise remove it, the current,
+    /// previous and shown indices shift down when they came after the removed workspace. Removing
+    /// the previous workspace leaves none.
+    fn delete_workspace(&mut self, index: WorkspaceIndex) -> Result<(), Self::Error> {
+        if index >= self.workspaces.len() {
+            return Err(WMError::WorkspaceIndexNotValid(index));
+        } else if index == self.index {
+            return Err(WMError::CurrentWorkspace(index));
+        } else if self.visible.contains(&index) {
+            return Err(WMError::VisibleWorkspace(index));
+        } else if !self.workspaces[index].get_windows().is_empty() {
+            return Err(WMError::WorkspaceNotEmpty(index));
+        }
+        self.workspaces.remove(index);
+        self.names.remove(index);
+        if index < self.index {
+            self.index -= 1;
+        }
+        for shown in &mut self.visible {
+            if index < *shown {
+                *shown -= 1;
+            }
+        }
+        self.previous = match self.previous {
+            Some(previous) if previous == index => None,
+            Some(previous) if previous > index => Some(previous - 1),
+            previous => previous,
+        };
+        Ok(())
+    }
+
+    /// Return `delete_empty`.
+    fn get_delete_empty_workspaces(&self) -> bool {
+        self.delete_empty
+    }
+
+    /// Set `delete_empty` and delete the empty workspaces that aren't shown on a screen.
+    fn set_delete_empty_workspaces(&mut self, delete_empty: bool) {
+        self.delete_empty = delete_empty;
+        let all = (0..self.workspaces.len()).collect();
+        self.prune_empty_workspaces(all);
+    }
+}
+
+impl MultiScreenSupport<WM> for MultiWorkspaceWM {
+    /// Return `screens`.
+    fn get_screens(&self) -> Vec<Geometry> {
+        self.screens.clone()
+    }
+    /// Return an error when there are no screens.
+    /// Otherwise the screens that are gone stop showing their workspace, and every new screen
+    /// shows the first hidden workspace, or a new one named after the first free number.
+    /// When the current screen is gone, switch to the workspace of the first screen.
+    /// Every shown workspace gets the size of its screen, the hidden ones the size of the first
+    /// screen. Finally the empty workspaces that became hidden can be deleted, see
+    /// `set_delete_empty_workspaces`.
+    fn set_screens(&mut self, screens: Vec<Geometry>) -> Result<(), Self::Error> {
+        if screens.is_empty() {
+            return Err(WMError::NoScreens);
+        }
+        let current_screen = self.get_current_screen_index();
+        let shown = self.visible.clone();
+        self.screens = screens;
+        self.visible.truncate(self.screens.len());
+        while self.visible.len() < self.screens.len() {
+            let hidden = (0..self.workspaces.len()).find(|index| !self.visible.contains(index));
+            let index = match hidden {
+                Some(index) => index,
+                None => {
+                    let name = (1..)
+                        .map(|number: usize| number.to_string())
+                        .find(|name| self.find_workspace(name).is_none())
+                        .unwrap();
+                    try!(self.create_workspace(&name))
+                }
+            };
+            self.visible.push(index);
+        }
+        if current_screen >= self.screens.len() {
+            let index = self.visible[0];
+            self.enter_workspace(index);
+        }
+        let first = screen_size(&self.screens[0]);
+        for workspace in &mut self.workspaces {
+            workspace.resize_screen(first);
+        }
+        for screen in 0..self.screens.len() {
+            let index = self.visible[screen];
+            self.show_workspace(index, screen);
+        }
+        let hidden = shown.into_iter().filter(|index| !self.visible.contains(index)).collect();
+        self.prune_empty_workspaces(hidden);
+        Ok(())
+    }
+    /// Return the index of the current workspace in `visible`.
+    fn get_current_screen_index(&self) -> ScreenIndex {
+        self.visible.iter().position(|&index| index == self.index).unwrap()
+    }
+    /// Return an error if there is no screen at the index.
+    /// Otherwise return the workspace shown on it.
+    fn get_screen_workspace(&self, screen: ScreenIndex) -> Result<WorkspaceIndex, Self::Error> {
+        if screen >= self.screens.len() {
+            return Err(WMError::ScreenIndexNotValid(screen));
+        }
+        Ok(self.visible[screen])
+    }
+}
+
+impl ScratchpadSupport for MultiWorkspaceWM {
+    /// Return the window of `scratchpad`.
+    fn get_scratchpad_window(&self) -> Option<Window> {
+        self.scratchpad.map(|info| info.window)
+    }
+
+    /// The scratchpad is shown when the current workspace contains it.
+    fn is_scratchpad_shown(&self) -> bool {
+        self.scratchpad.is_some() && self.get_hidden_scratchpad().is_none()
+    }
+
+    /// Take the window off its workspace and show it as the scratchpad. A hidden previous
+    /// scratchpad is added to the current workspace as a floating window, a shown one already
+    /// floats there.
+    fn set_scratchpad_window(&mut self, window: Window) -> Result<(), Self::Error> {
+        if !self.is_managed(window) {
+            return Err(WMError::UnknownWindow(window));
+        }
+        if self.get_scratchpad_window() == Some(window) {
+            self.show_scratchpad();
+            return Ok(());
+        }
+        if let Some(mut previous) = self.get_hidden_scratchpad() {
+            previous.float_or_tile = FloatOrTile::Float;
+            self.workspaces[self.index].add_window(previous).unwrap();
+        }
+        let window_with_info = self.get_window_info(window).unwrap();
+        let index = self.find_index(window).unwrap();
+        self.workspaces[index].remove_window(window).unwrap();
+        self.scratchpad = Some(window_with_info);
+        self.show_scratchpad();
+        self.prune_empty_workspaces(vec![index]);
         Ok(())
     }
+
+    /// Hide the scratchpad when it is shown, otherwise show it.
+    fn toggle_scratchpad(&mut self) {
+        if self.is_scratchpad_shown() {
+            self.hide_scratchpad();
+        } else {
+            self.show_scratchpad();
+        }
+    }
 }
 
 impl MultiWorkspaceWM {
-    /// Helper function to find the index of the workspace that contain the given window.
-    fn find_index(&self, window: Window) -> WorkspaceIndex {
-        let mut index = 0;
-        for i in 0..MAX_WORKSPACE_INDEX {
-            if self.workspaces[i].is_managed(window) {
-                index = i;
-                break;
+    /// Return the scratchpad when it is hidden, i.e. when the current workspace doesn't contain
+    /// it.
+    fn get_hidden_scratchpad(&self) -> Option<WindowWithInfo> {
+        match self.scratchpad {
+            Some(info) if !self.workspaces[self.index].is_managed(info.window) => Some(info),
+            _ => None,
+        }
+    }
+
+    /// Add the hidden scratchpad to the current workspace as a floating window centred on the
+    /// screen, and focus it. Do nothing when there is no hidden scratchpad.
+    fn show_scratchpad(&mut self) {
+        if let Some(mut info) = self.get_hidden_scratchpad() {
+            let screen = self.get_screen();
+            info.float_or_tile = FloatOrTile::Float;
+            info.fullscreen = false;
+            info.geometry.width = min(info.geometry.width, screen.width);
+            info.geometry.height = min(info.geometry.height, screen.height);
+            info.geometry.x = ((screen.width - info.geometry.width) / 2) as c_int;
+            info.geometry.y = ((screen.height - info.geometry.height) / 2) as c_int;
+            self.workspaces[self.index].add_window(info).unwrap();
+            self.workspaces[self.index].focus_window(Some(info.window)).unwrap();
+        }
+    }
+
+    /// Remove the shown scratchpad from the current workspace, remembering its size. Do nothing
+    /// when the scratchpad is not shown.
+    fn hide_scratchpad(&mut self) {
+        if self.is_scratchpad_shown() {
+            let window = self.get_scratchpad_window().unwrap();
+            let info = self.workspaces[self.index].get_window_info(window).unwrap();
+            self.workspaces[self.index].remove_window(window).unwrap();
+            self.scratchpad = Some(info);
+        }
+    }
+
+    /// Remove the given managed window from its workspace, or forget it when it is the
+    /// scratchpad, without deleting the workspace when it becomes empty. Return the index of the
+    /// workspace that contained the window, `None` for the hidden scratchpad.
+    fn take_window(&mut self, window: Window) -> Option<WorkspaceIndex> {
+        if self.get_scratchpad_window() == Some(window) {
+            let hidden = !self.is_scratchpad_shown();
+            self.scratchpad = None;
+            if hidden {
+                return None;
+            }
+        }
+        let index = self.find_index(window).unwrap();
+        self.workspaces[index].remove_window(window).unwrap();
+        Some(index)
+    }
+
+    /// Make the workspace at the given index, which must not be the current one, the current
+    /// one, see `switch_workspace`, without deleting the workspace we leave. Return its index
+    /// when it became hidden.
+    fn enter_workspace(&mut self, index: WorkspaceIndex) -> Option<WorkspaceIndex> {
+        self.hide_scratchpad();
+        if self.get_workspace_screen(index).is_none() {
+            let screen = self.get_current_screen_index();
+            self.show_workspace(index, screen);
+        }
+        let left = self.index;
+        self.previous = Some(left);
+        self.index = index;
+        // The focused window of the workspace gets the attention of the user
+        if let Some(window) = self.workspaces[index].get_focused_window() {
+            self.workspaces[index].set_urgent(window, false).unwrap();
+        }
+        if self.visible.contains(&left) {
+            return None;
+        }
+        if let Some(fullscreen) = self.workspaces[left].get_fullscreen_window() {
+            self.workspaces[left].toggle_fullscreen(fullscreen).unwrap();
+        }
+        Some(left)
+    }
+
+    /// Show the workspace at the given index on the given screen, it gets the size of that screen.
+    /// The workspace that was shown there becomes hidden.
+    fn show_workspace(&mut self, index: WorkspaceIndex, screen: ScreenIndex) {
+        let size = screen_size(&self.screens[screen]);
+        self.visible[screen] = index;
+        self.workspaces[index].resize_screen(size);
+        self.fit_floating_windows(index);
+    }
+
+    /// Move the floating windows of the workspace at the given index that stick out of its
+    /// screen, e.g. after the screen was made smaller or rotated, back onto it. A window larger
+    /// than the screen is shrunk to fit. The fullscreen window is left alone, it would stop being
+    /// fullscreen.
+    fn fit_floating_windows(&mut self, index: WorkspaceIndex) {
+        let workspace = &mut self.workspaces[index];
+        let screen = workspace.get_screen();
+        let fullscreen = workspace.get_fullscreen_window();
+        for window in workspace.get_floating_windows() {
+            if Some(window) == fullscreen {
+                continue;
+            }
+            let geometry = workspace.get_window_info(window).unwrap().geometry;
+            let width = min(geometry.width, screen.width);
+            let height = min(geometry.height, screen.height);
+            let fitted = Geometry {
+                x: max(0, min(geometry.x, (screen.width - width) as c_int)),
+                y: max(0, min(geometry.y, (screen.height - height) as c_int)),
+                width: width,
+                height: height,
+            };
+            if fitted != geometry {
+                workspace.set_window_geometry(window, fitted).unwrap();
             }
         }
-        return index;
+    }
+
+    /// Return a new, empty workspace. The settings that are the same on every workspace, like the
+    /// gaps and the screen, are copied from the current workspace.
+    fn new_workspace(&self) -> WM {
+        let current = &self.workspaces[self.index];
+        let mut workspace = WM::new(current.get_screen());
+        workspace.set_inner_gap(current.get_inner_gap());
+        workspace.set_outer_gap(current.get_outer_gap());
+        workspace.set_padding(current.get_padding());
+        workspace.set_smart_gaps(current.get_smart_gaps());
+        workspace.set_smart_borders(current.get_smart_borders());
+        workspace.set_focus_history_frozen(current.is_focus_history_frozen());
+        workspace
+    }
+
+    /// Return an error if the name is empty, contains a NUL character or is the name of a
+    /// workspace.
+    fn check_workspace_name(&self, name: &str) -> Result<(), WMError> {
+        if name.is_empty() || name.contains('\0') {
+            return Err(WMError::WorkspaceNameNotValid(String::from(name)));
+        } else if self.find_workspace(name).is_some() {
+            return Err(WMError::WorkspaceNameInUse(String::from(name)));
+        }
+        Ok(())
+    }
+
+    /// When `delete_empty` is set, delete the given workspaces that are empty and not shown on a
+    /// screen.
+    ///
+    /// Only the workspaces an operation emptied or hid are given, and only at the end of the
+    /// operation. So a new workspace isn't deleted before it is used, and the indices an operation
+    /// was given stay valid while it runs. The workspaces are deleted from the last one to the
+    /// first, so deleting one doesn't shift the indices of the others.
+    fn prune_empty_workspaces(&mut self, mut candidates: Vec<WorkspaceIndex>) {
+        if !self.delete_empty {
+            return;
+        }
+        candidates.sort();
+        candidates.dedup();
+        for index in candidates.into_iter().rev() {
+            if !self.visible.contains(&index) && self.workspaces[index].get_windows().is_empty() {
+                self.delete_workspace(index).unwrap();
+            }
+        }
+    }
+
+    /// Helper function to find the index of the workspace that contain the given window, `None`
+    /// when no workspace contains it, e.g. when it is the hidden scratchpad.
+    fn find_index(&self, window: Window) -> Option<WorkspaceIndex> {
+        self.workspaces.iter().position(|workspace| workspace.is_managed(window))
+    }
+}
+
+/// Return the size of the given screen geometry.
+fn screen_size(geometry: &Geometry) -> Screen {
+    Screen {
+        width: geometry.width,
+        height: geometry.height,
     }
 }
 
@@ -323,8 +1005,11 @@ impl MultiWorkspaceWM {
 mod tests {
 
     use super::WMName;
-    use cplwm_api::wm::{FloatSupport, FullscreenSupport, MinimiseSupport, MultiWorkspaceSupport,
-                        WindowManager};
+    use layout::TilingLayout;
+    use cplwm_api::wm::{FloatSupport, FullscreenSupport, GapSupport, LayoutSupport,
+                        MinimiseSupport, MultiScreenSupport, MultiWorkspaceSupport,
+                        ScratchpadSupport, TilingSupport, UrgencySupport, WindowManager};
+    use rustc_serialize::json::{decode, encode};
     use cplwm_api::types::*;
 
     // We define a static variable for the screen we will use in the tests.
@@ -351,13 +1036,22 @@ mod tests {
         height: 100,
     };
 
+    // Most tests need a couple of workspaces, create them up front.
+    fn new_with_workspaces() -> WMName {
+        let mut wm = WMName::new(SCREEN);
+        for name in &["2", "3", "4"] {
+            wm.create_workspace(name).unwrap();
+        }
+        wm
+    }
+
     // Now let's write our test.
     //
     // Note that tests are annotated with `#[test]`, and cannot take arguments
     // nor return anything.
     #[test]
     fn test_adding_and_removing_some_windows() {
-        let mut wm = WMName::new(SCREEN);
+        let mut wm = new_with_workspaces();
 
         // current ws should be 0
         assert_eq!(wm.get_current_workspace_index(), 0);
@@ -365,8 +1059,9 @@ mod tests {
         wm.switch_workspace(1).unwrap();
         // current ws should be 1
         assert_eq!(wm.get_current_workspace_index(), 1);
-        assert!(wm.switch_workspace(MAX_WORKSPACE_INDEX + 1).is_err());
-        assert!(wm.get_workspace_mut(MAX_WORKSPACE_INDEX + 1).is_err());
+        let count = wm.get_workspace_count();
+        assert!(wm.switch_workspace(count).is_err());
+        assert!(wm.get_workspace_mut(count).is_err());
 
         // add a new window to the current workspace
         wm.add_window(WindowWithInfo::new_tiled(1, SOME_GEOM)).unwrap();
@@ -402,7 +1097,7 @@ mod tests {
 
     #[test]
     fn test_focusing_some_windows() {
-        let mut wm = WMName::new(SCREEN);
+        let mut wm = new_with_workspaces();
         // add a new window
         let wi = WindowWithInfo::new_float(1, SOME_GEOM);
         wm.add_window(wi).unwrap();
@@ -428,7 +1123,7 @@ mod tests {
 
     #[test]
     fn test_floating_windows() {
-        let mut wm = WMName::new(SCREEN);
+        let mut wm = new_with_workspaces();
 
         // add a floating window 1
         wm.add_window(WindowWithInfo::new_float(1, SOME_GEOM)).unwrap();
@@ -450,7 +1145,7 @@ mod tests {
 
     #[test]
     fn minimise_some_windows() {
-        let mut wm = WMName::new(SCREEN);
+        let mut wm = new_with_workspaces();
         wm.add_window(WindowWithInfo::new_float(1, SOME_GEOM)).unwrap();
         wm.switch_workspace(1).unwrap();
         // add window 2 to workspace 1
@@ -473,7 +1168,7 @@ mod tests {
 
     #[test]
     fn focus_fullscreen_windows() {
-        let mut wm = WMName::new(SCREEN);
+        let mut wm = new_with_workspaces();
 
         // add a fulscreen window on ws 0
         wm.add_window(WindowWithInfo::new_fullscreen(1, SOME_GEOM)).unwrap();
@@ -509,6 +1204,771 @@ mod tests {
         assert_eq!(wm.get_fullscreen_window(), Some(2));
     }
 
+    #[test]
+    fn test_master_ratio_per_workspace() {
+        let mut wm = new_with_workspaces();
+
+        // set the master ratio of ws 0
+        wm.set_master_ratio(0.7);
+        assert_eq!(wm.get_master_ratio(), 0.7);
+        // change workspace to 1
+        wm.switch_workspace(1).unwrap();
+        // ws 1 still has the default ratio
+        assert_eq!(wm.get_master_ratio(), DEFAULT_MASTER_RATIO);
+        wm.set_master_ratio(0.3);
+        // back to ws 0, its ratio didn't change
+        wm.switch_workspace(0).unwrap();
+        assert_eq!(wm.get_master_ratio(), 0.7);
+    }
+
+    #[test]
+    fn test_layout_per_workspace() {
+        let mut wm = new_with_workspaces();
+        // add three windows on ws 0
+        for w in 1..4 {
+            wm.add_window(WindowWithInfo::new_tiled(w, SOME_GEOM)).unwrap();
+        }
+        let master_stack = wm.get_window_layout();
+        // select the centered master layout on ws 1
+        wm.switch_workspace(1).unwrap();
+        let steps = TilingLayout::all()
+            .iter()
+            .position(|l| *l == TilingLayout::CenteredMaster)
+            .unwrap();
+        for _ in 0..steps {
+            wm.cycle_layout(PrevOrNext::Next);
+        }
+        for w in 4..7 {
+            wm.add_window(WindowWithInfo::new_tiled(w, SOME_GEOM)).unwrap();
+        }
+        // the master is in the middle column
+        assert_eq!(wm.get_window_layout().windows[0].1,
+                   Geometry {
+                       x: 200,
+                       y: 0,
+                       width: 400,
+                       height: 600,
+                   });
+        // back to ws 0, its layout didn't change
+        wm.switch_workspace(0).unwrap();
+        assert_eq!(wm.get_window_layout(), master_stack);
+    }
+
+    #[test]
+    fn test_layout_transform_per_workspace() {
+        let mut wm = new_with_workspaces();
+        wm.add_window(WindowWithInfo::new_tiled(1, SOME_GEOM)).unwrap();
+        wm.add_window(WindowWithInfo::new_tiled(2, SOME_GEOM)).unwrap();
+        let master_left = wm.get_window_layout();
+        // mirror the layout of ws 1
+        wm.switch_workspace(1).unwrap();
+        wm.toggle_layout_transform(LayoutTransform::MirrorHorizontally);
+        wm.add_window(WindowWithInfo::new_tiled(3, SOME_GEOM)).unwrap();
+        wm.add_window(WindowWithInfo::new_tiled(4, SOME_GEOM)).unwrap();
+        // the master is on the right
+        assert_eq!(400, wm.get_window_layout().windows[0].1.x);
+        // back to ws 0, it isn't mirrored
+        wm.switch_workspace(0).unwrap();
+        assert!(wm.get_layout_transforms().is_empty());
+        assert_eq!(master_left, wm.get_window_layout());
+    }
+
+    #[test]
+    fn test_gaps_on_every_workspace() {
+        let mut wm = new_with_workspaces();
+        let padding = Padding { bottom: 30, ..Padding::new() };
+        wm.set_padding(padding);
+        wm.set_gap(4);
+        // the settings apply to every workspace, also to the ones we didn't visit yet
+        wm.switch_workspace(2).unwrap();
+        assert_eq!(padding, wm.get_padding());
+        assert_eq!(4, wm.get_outer_gap());
+        // and to the ones created afterwards
+        let index = wm.create_workspace("5").unwrap();
+        wm.switch_workspace(index).unwrap();
+        assert_eq!(padding, wm.get_padding());
+        assert_eq!(4, wm.get_outer_gap());
+        assert_eq!(8, wm.get_inner_gap());
+        wm.add_window(WindowWithInfo::new_tiled(1, SOME_GEOM)).unwrap();
+        assert_eq!(vec![(1,
+                         Geometry {
+                            x: 4,
+                            y: 4,
+                            width: 792,
+                            height: 562,
+                        })],
+                   wm.get_window_layout().windows);
+    }
+
+    #[test]
+    fn test_focus_urgent_window_on_another_workspace() {
+        let mut wm = new_with_workspaces();
+        wm.add_window(WindowWithInfo::new_tiled(1, SOME_GEOM)).unwrap();
+        wm.add_window(WindowWithInfo::new_tiled(2, SOME_GEOM)).unwrap();
+        wm.switch_workspace(1).unwrap();
+        wm.add_window(WindowWithInfo::new_tiled(3, SOME_GEOM)).unwrap();
+        wm.add_window(WindowWithInfo::new_tiled(4, SOME_GEOM)).unwrap();
+        wm.switch_workspace(2).unwrap();
+        wm.set_urgent(3, true).unwrap();
+        wm.set_urgent(1, true).unwrap();
+        // the urgent windows of the workspaces after the current one come first
+        assert_eq!(vec![1, 3], wm.get_urgent_windows());
+        wm.focus_urgent_window().unwrap();
+        assert_eq!(0, wm.get_current_workspace_index());
+        assert_eq!(Some(1), wm.get_focused_window());
+        wm.focus_urgent_window().unwrap();
+        assert_eq!(1, wm.get_current_workspace_index());
+        assert_eq!(Some(3), wm.get_focused_window());
+        assert!(wm.get_urgent_windows().is_empty());
+        assert!(wm.set_urgent(5, true).is_err());
+    }
+
+    #[test]
+    fn test_urgent_focused_window_on_another_workspace() {
+        let mut wm = new_with_workspaces();
+        wm.add_window(WindowWithInfo::new_tiled(1, SOME_GEOM)).unwrap();
+        // a new window is the focused window of its hidden workspace
+        wm.add_window_to_workspace(1, WindowWithInfo::new_tiled(2, SOME_GEOM)).unwrap();
+        wm.set_urgent(2, true).unwrap();
+        assert_eq!(vec![2], wm.get_urgent_windows());
+        // also when the user switched away from its workspace
+        wm.add_window(WindowWithInfo::new_tiled(3, SOME_GEOM)).unwrap();
+        wm.switch_workspace(2).unwrap();
+        wm.set_urgent(3, true).unwrap();
+        assert_eq!(vec![3, 2], wm.get_urgent_windows());
+        // it gets the attention of the user when its workspace becomes the current one
+        wm.focus_urgent_window().unwrap();
+        assert_eq!(0, wm.get_current_workspace_index());
+        assert_eq!(Some(3), wm.get_focused_window());
+        assert_eq!(vec![2], wm.get_urgent_windows());
+        wm.switch_workspace(1).unwrap();
+        assert!(wm.get_urgent_windows().is_empty());
+    }
+
+    #[test]
+    fn test_add_window_to_another_workspace() {
+        let mut wm = new_with_workspaces();
+        wm.add_window(WindowWithInfo::new_tiled(1, SOME_GEOM)).unwrap();
+        wm.add_window_to_workspace(2, WindowWithInfo::new_float(2, SOME_GEOM)).unwrap();
+        // the current workspace and its focus are unchanged
+        assert_eq!(0, wm.get_current_workspace_index());
+        assert_eq!(Some(1), wm.get_focused_window());
+        assert_eq!(vec![(1, SCREEN_GEOM)], wm.get_window_layout().windows);
+        assert!(wm.is_managed(2));
+        assert_eq!(Some(2), wm.get_window_workspace(2));
+        // the window waits on its own workspace
+        wm.switch_workspace(2).unwrap();
+        assert_eq!(vec![(2, SOME_GEOM)], wm.get_window_layout().windows);
+        // the fn should return an error
+        assert!(wm.add_window_to_workspace(1, WindowWithInfo::new_tiled(1, SOME_GEOM)).is_err());
+        let count = wm.get_workspace_count();
+        assert!(wm.add_window_to_workspace(count, WindowWithInfo::new_tiled(3, SOME_GEOM))
+            .is_err());
+    }
+
+    #[test]
+    fn test_scratchpad() {
+        let mut wm = new_with_workspaces();
+        // Nothing happens without a scratchpad
+        wm.toggle_scratchpad();
+        assert_eq!(None, wm.get_scratchpad_window());
+        assert!(wm.set_scratchpad_window(1).is_err());
+
+        wm.add_window(WindowWithInfo::new_tiled(1, SOME_GEOM)).unwrap();
+        wm.add_window(WindowWithInfo::new_tiled(2, SOME_GEOM)).unwrap();
+        // The scratchpad floats centred on top of the current workspace
+        wm.set_scratchpad_window(2).unwrap();
+        assert_eq!(Some(2), wm.get_scratchpad_window());
+        assert!(wm.is_scratchpad_shown());
+        assert_eq!(Some(2), wm.get_focused_window());
+        let centred = Geometry {
+            x: 350,
+            y: 250,
+            width: 100,
+            height: 100,
+        };
+        assert_eq!(vec![(1, SCREEN_GEOM), (2, centred)],
+                   wm.get_window_layout().windows);
+
+        // Hiding it leaves it managed, but not in the layout
+        wm.toggle_scratchpad();
+        assert!(!wm.is_scratchpad_shown());
+        assert!(wm.is_managed(2));
+        assert_eq!(vec![(1, SCREEN_GEOM)], wm.get_window_layout().windows);
+        assert_eq!(Some(1), wm.get_focused_window());
+
+        // It belongs to no workspace: it shows up on the current one
+        wm.switch_workspace(1).unwrap();
+        wm.toggle_scratchpad();
+        assert_eq!(vec![(2, centred)], wm.get_window_layout().windows);
+        // and it is hidden when switching to another workspace
+        wm.switch_workspace(0).unwrap();
+        assert!(!wm.is_scratchpad_shown());
+        assert!(wm.get_workspace(1).unwrap().get_windows().is_empty());
+        assert_eq!(None, wm.get_window_workspace(2));
+
+        // The hidden scratchpad survives a restart
+        let mut restored: WMName = decode(&encode(&wm).unwrap()).unwrap();
+        assert_eq!(Some(2), restored.get_scratchpad_window());
+        assert_eq!(wm.get_window_layout(), restored.get_window_layout());
+        // Focusing it shows it
+        restored.focus_window(Some(2)).unwrap();
+        assert!(restored.is_scratchpad_shown());
+
+        // Removing it leaves no scratchpad
+        wm.remove_window(2).unwrap();
+        assert_eq!(None, wm.get_scratchpad_window());
+        assert!(!wm.is_managed(2));
+    }
+
+    #[test]
+    fn test_hidden_scratchpad_is_on_no_workspace() {
+        let mut wm = new_with_workspaces();
+        wm.add_window(WindowWithInfo::new_tiled(1, SOME_GEOM)).unwrap();
+        wm.add_window(WindowWithInfo::new_tiled(2, SOME_GEOM)).unwrap();
+        wm.set_scratchpad_window(2).unwrap();
+        wm.toggle_scratchpad();
+        wm.switch_workspace(1).unwrap();
+        wm.add_window(WindowWithInfo::new_tiled(3, SOME_GEOM)).unwrap();
+
+        // It can't be minimised or sunk, and we stay on the current workspace
+        assert!(wm.toggle_minimised(2).is_err());
+        assert!(wm.toggle_floating(2).is_err());
+        assert_eq!(1, wm.get_current_workspace_index());
+        assert!(!wm.is_scratchpad_shown());
+        // It is not a tile, so it has no weight of its own
+        wm.set_tile_weight(2, 2.0).unwrap();
+        assert_eq!(DEFAULT_TILE_WEIGHT, wm.get_tile_weight(2).unwrap());
+        assert_eq!(1, wm.get_current_workspace_index());
+
+        // Making it fullscreen shows it on the current workspace
+        wm.toggle_fullscreen(2).unwrap();
+        assert_eq!(1, wm.get_current_workspace_index());
+        assert!(wm.is_scratchpad_shown());
+        assert_eq!(Some(2), wm.get_fullscreen_window());
+        assert_eq!(vec![(2, SCREEN_GEOM)], wm.get_window_layout().windows);
+    }
+
+    #[test]
+    fn test_named_workspaces() {
+        let mut wm = WMName::new(SCREEN);
+        // initially there is a single workspace
+        assert_eq!(1, wm.get_workspace_count());
+        assert_eq!("1", wm.get_workspace_name(0).unwrap());
+        assert!(wm.get_workspace_name(1).is_err());
+
+        // workspaces are created on demand, after the existing ones
+        assert_eq!(1, wm.create_workspace("web").unwrap());
+        assert_eq!(Some(1), wm.find_workspace("web"));
+        assert_eq!(None, wm.find_workspace("mail"));
+        assert_eq!(2, wm.get_or_create_workspace("mail").unwrap());
+        assert_eq!(2, wm.get_or_create_workspace("mail").unwrap());
+        assert_eq!(3, wm.get_workspace_count());
+        // the fn should return an error
+        assert!(wm.create_workspace("web").is_err());
+        assert!(wm.create_workspace("").is_err());
+        assert!(wm.create_workspace("a\0b").is_err());
+
+        // renaming
+        wm.rename_workspace(2, "chat").unwrap();
+        wm.rename_workspace(2, "chat").unwrap();
+        assert_eq!(Some(2), wm.find_workspace("chat"));
+        assert!(wm.rename_workspace(2, "web").is_err());
+        assert!(wm.rename_workspace(3, "music").is_err());
+
+        // deleting
+        wm.add_window_to_workspace(2, WindowWithInfo::new_tiled(1, SOME_GEOM)).unwrap();
+        wm.switch_workspace(2).unwrap();
+        assert!(wm.delete_workspace(2).is_err());
+        wm.switch_workspace(1).unwrap();
+        assert!(wm.delete_workspace(2).is_err());
+        assert!(wm.delete_workspace(3).is_err());
+        wm.delete_workspace(0).unwrap();
+        // the indices after the deleted workspace shift down
+        assert_eq!(0, wm.get_current_workspace_index());
+        assert_eq!("web", wm.get_workspace_name(0).unwrap());
+        assert_eq!(Some(1), wm.find_workspace("chat"));
+        wm.focus_window(Some(1)).unwrap();
+        assert_eq!(1, wm.get_current_workspace_index());
+
+        // the names survive a restart
+        let restored: WMName = decode(&encode(&wm).unwrap()).unwrap();
+        assert_eq!(Some(1), restored.find_workspace("chat"));
+    }
+
+    #[test]
+    fn test_delete_empty_workspaces() {
+        let mut wm = new_with_workspaces();
+        wm.add_window_to_workspace(2, WindowWithInfo::new_tiled(1, SOME_GEOM)).unwrap();
+        wm.add_window_to_workspace(3, WindowWithInfo::new_tiled(2, SOME_GEOM)).unwrap();
+        assert!(!wm.get_delete_empty_workspaces());
+        // the current workspace stays, even though it is empty
+        wm.set_delete_empty_workspaces(true);
+        assert_eq!(3, wm.get_workspace_count());
+        assert_eq!(Some(1), wm.find_workspace("3"));
+        // the workspace we leave is deleted when it is empty
+        wm.switch_workspace(1).unwrap();
+        assert_eq!(0, wm.get_current_workspace_index());
+        assert_eq!(vec!["3", "4"],
+                   (0..wm.get_workspace_count())
+                       .map(|i| wm.get_workspace_name(i).unwrap())
+                       .collect::<Vec<_>>());
+        // as is a workspace that isn't the current one when its last window is removed
+        wm.remove_window(2).unwrap();
+        assert_eq!(1, wm.get_workspace_count());
+        // the current one stays until we leave it
+        wm.remove_window(1).unwrap();
+        assert_eq!(1, wm.get_workspace_count());
+        let index = wm.create_workspace("5").unwrap();
+        wm.switch_workspace(index).unwrap();
+        assert_eq!(vec!["5"],
+                   (0..wm.get_workspace_count())
+                       .map(|i| wm.get_workspace_name(i).unwrap())
+                       .collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_new_workspace_is_kept_until_used() {
+        let mut wm = new_with_workspaces();
+        wm.add_window_to_workspace(1, WindowWithInfo::new_tiled(1, SOME_GEOM)).unwrap();
+        wm.set_delete_empty_workspaces(true);
+        let index = wm.create_workspace("5").unwrap();
+        // other operations that delete empty workspaces leave the new one alone
+        wm.remove_window(1).unwrap();
+        assert_eq!(None, wm.find_workspace("2"));
+        wm.add_window(WindowWithInfo::new_tiled(2, SOME_GEOM)).unwrap();
+        wm.set_scratchpad_window(2).unwrap();
+        assert_eq!(Some(index - 1), wm.find_workspace("5"));
+        // it is deleted once it was shown and left empty
+        wm.add_window(WindowWithInfo::new_tiled(3, SOME_GEOM)).unwrap();
+        wm.switch_workspace(index - 1).unwrap();
+        wm.switch_workspace(0).unwrap();
+        assert_eq!(None, wm.find_workspace("5"));
+    }
+
+    #[test]
+    fn test_move_floating_window() {
+        let mut wm = new_with_workspaces();
+        let geom = Geometry {
+            x: 20,
+            y: 30,
+            width: 200,
+            height: 150,
+        };
+        wm.add_window(WindowWithInfo::new_tiled(1, SOME_GEOM)).unwrap();
+        wm.add_window(WindowWithInfo::new_float(2, SOME_GEOM)).unwrap();
+        wm.add_window(WindowWithInfo::new_float(3, SOME_GEOM)).unwrap();
+        wm.set_window_geometry(2, geom).unwrap();
+
+        // without following, the current workspace stays the same
+        wm.move_window_to_workspace(2, 1, false).unwrap();
+        assert_eq!(0, wm.get_current_workspace_index());
+        assert_eq!(Some(1), wm.get_window_workspace(2));
+        assert_eq!(vec![(1, SCREEN_GEOM), (3, SOME_GEOM)],
+                   wm.get_window_layout().windows);
+        // the window still floats with the same geometry
+        assert_eq!(WindowWithInfo::new_float(2, geom), wm.get_window_info(2).unwrap());
+
+        // when following, the window is focused on the other workspace
+        wm.move_window_to_workspace(3, 1, true).unwrap();
+        assert_eq!(1, wm.get_current_workspace_index());
+        assert_eq!(Some(3), wm.get_focused_window());
+        assert_eq!(vec![(2, geom), (3, SOME_GEOM)], wm.get_window_layout().windows);
+
+        // moving a window to its own workspace doesn't change it
+        wm.move_window_to_workspace(2, 1, false).unwrap();
+        assert_eq!(Some(3), wm.get_focused_window());
+        assert_eq!(vec![(2, geom), (3, SOME_GEOM)], wm.get_window_layout().windows);
+
+        // the fn should return an error
+        assert!(wm.move_window_to_workspace(4, 0, false).is_err());
+        let count = wm.get_workspace_count();
+        assert!(wm.move_window_to_workspace(2, count, false).is_err());
+    }
+
+    #[test]
+    fn test_move_fullscreen_window() {
+        let mut wm = new_with_workspaces();
+        wm.add_window(WindowWithInfo::new_tiled(1, SOME_GEOM)).unwrap();
+        wm.add_window(WindowWithInfo::new_fullscreen(2, SOME_GEOM)).unwrap();
+        assert_eq!(Some(2), wm.get_fullscreen_window());
+
+        wm.move_window_to_workspace(2, 1, false).unwrap();
+        assert_eq!(None, wm.get_fullscreen_window());
+        assert_eq!(vec![(1, SCREEN_GEOM)], wm.get_window_layout().windows);
+        // it is still fullscreen on the other workspace
+        wm.switch_workspace(1).unwrap();
+        assert_eq!(Some(2), wm.get_fullscreen_window());
+        assert_eq!(vec![(2, SCREEN_GEOM)], wm.get_window_layout().windows);
+
+        // and when it comes back
+        wm.move_window_to_workspace(2, 0, true).unwrap();
+        assert_eq!(0, wm.get_current_workspace_index());
+        assert_eq!(Some(2), wm.get_fullscreen_window());
+        assert_eq!(Some(2), wm.get_focused_window());
+    }
+
+    #[test]
+    fn test_move_minimised_window() {
+        let mut wm = new_with_workspaces();
+        wm.add_window(WindowWithInfo::new_tiled(1, SOME_GEOM)).unwrap();
+        wm.add_window(WindowWithInfo::new_tiled(2, SOME_GEOM)).unwrap();
+        wm.set_tile_weight(1, 2.0).unwrap();
+        wm.toggle_minimised(2).unwrap();
+
+        wm.move_window_to_workspace(2, 1, false).unwrap();
+        assert_eq!(Some(1), wm.get_window_workspace(2));
+        assert!(wm.is_minimised(2));
+        assert_eq!(Some(1), wm.get_focused_window());
+
+        // following a minimised window doesn't unminimise it
+        wm.move_window_to_workspace(2, 2, true).unwrap();
+        assert_eq!(2, wm.get_current_workspace_index());
+        assert!(wm.is_minimised(2));
+        assert_eq!(None, wm.get_focused_window());
+
+        // a tile keeps its weight
+        wm.move_window_to_workspace(1, 2, true).unwrap();
+        assert_eq!(2.0, wm.get_tile_weight(1).unwrap());
+        // and the minimised window is still a tile
+        wm.toggle_minimised(2).unwrap();
+        assert_eq!(2, wm.get_window_layout().windows.len());
+        assert!(!wm.is_floating(2));
+    }
+
+    #[test]
+    fn test_move_last_window_of_a_workspace() {
+        let mut wm = new_with_workspaces();
+        wm.add_window_to_workspace(1, WindowWithInfo::new_tiled(1, SOME_GEOM)).unwrap();
+        wm.add_window_to_workspace(2, WindowWithInfo::new_tiled(2, SOME_GEOM)).unwrap();
+        wm.add_window_to_workspace(3, WindowWithInfo::new_tiled(3, SOME_GEOM)).unwrap();
+        wm.set_delete_empty_workspaces(true);
+        // the workspace the window leaves is deleted, the window ends up on workspace "3"
+        wm.move_window_to_workspace(1, 2, false).unwrap();
+        assert_eq!(3, wm.get_workspace_count());
+        assert_eq!(Some(1), wm.get_window_workspace(1));
+        assert_eq!("3", wm.get_workspace_name(1).unwrap());
+    }
+
+    #[test]
+    fn test_move_last_window_to_a_new_workspace() {
+        let mut wm = new_with_workspaces();
+        wm.add_window_to_workspace(1, WindowWithInfo::new_tiled(1, SOME_GEOM)).unwrap();
+        wm.add_window_to_workspace(2, WindowWithInfo::new_tiled(2, SOME_GEOM)).unwrap();
+        wm.set_delete_empty_workspaces(true);
+        // the new workspace isn't deleted, it receives the window
+        let index = wm.create_workspace("5").unwrap();
+        wm.move_window_to_workspace(1, index, false).unwrap();
+        assert_eq!(wm.find_workspace("5"), wm.get_window_workspace(1));
+        // the workspace the window left is deleted
+        assert_eq!(None, wm.find_workspace("2"));
+        assert_eq!(0, wm.get_current_workspace_index());
+        // also when following the window
+        let index = wm.create_workspace("6").unwrap();
+        wm.move_window_to_workspace(2, index, true).unwrap();
+        assert_eq!(wm.find_workspace("6"), wm.get_window_workspace(2));
+        assert_eq!(wm.find_workspace("6"), Some(wm.get_current_workspace_index()));
+        assert_eq!(Some(2), wm.get_focused_window());
+        assert_eq!(None, wm.find_workspace("3"));
+    }
+
+    #[test]
+    fn test_back_and_forth() {
+        let mut wm = new_with_workspaces();
+        // there is no previous workspace yet
+        assert_eq!(None, wm.get_previous_workspace_index());
+        wm.switch_to_previous_workspace().unwrap();
+        assert_eq!(0, wm.get_current_workspace_index());
+
+        wm.add_window(WindowWithInfo::new_tiled(1, SOME_GEOM)).unwrap();
+        wm.add_window(WindowWithInfo::new_float(2, SOME_GEOM)).unwrap();
+        let layout = wm.get_window_layout();
+        wm.switch_workspace(2).unwrap();
+        wm.add_window(WindowWithInfo::new_tiled(3, SOME_GEOM)).unwrap();
+        assert_eq!(Some(0), wm.get_previous_workspace_index());
+        // going back restores the layout
+        wm.switch_to_previous_workspace().unwrap();
+        assert_eq!(0, wm.get_current_workspace_index());
+        assert_eq!(Some(2), wm.get_previous_workspace_index());
+        assert_eq!(layout, wm.get_window_layout());
+
+        // the previous workspace survives a restart
+        let mut restored: WMName = decode(&encode(&wm).unwrap()).unwrap();
+        restored.switch_to_previous_workspace().unwrap();
+        assert_eq!(2, restored.get_current_workspace_index());
+
+        // deleting a workspace shifts the previous index
+        wm.delete_workspace(1).unwrap();
+        assert_eq!(Some(1), wm.get_previous_workspace_index());
+        // deleting the previous workspace forgets it
+        wm.set_delete_empty_workspaces(true);
+        let index = wm.create_workspace("5").unwrap();
+        wm.switch_workspace(index).unwrap();
+        wm.switch_to_previous_workspace().unwrap();
+        assert_eq!(0, wm.get_current_workspace_index());
+        assert_eq!(None, wm.get_previous_workspace_index());
+    }
+
+    #[test]
+    fn test_cycle_workspace() {
+        let mut wm = WMName::new(SCREEN);
+        // there is no other workspace
+        wm.cycle_workspace(PrevOrNext::Next, false).unwrap();
+        assert_eq!(0, wm.get_current_workspace_index());
+
+        let mut wm = new_with_workspaces();
+        wm.add_window_to_workspace(2, WindowWithInfo::new_tiled(1, SOME_GEOM)).unwrap();
+        wm.cycle_workspace(PrevOrNext::Next, false).unwrap();
+        assert_eq!(1, wm.get_current_workspace_index());
+        wm.cycle_workspace(PrevOrNext::Prev, false).unwrap();
+        assert_eq!(0, wm.get_current_workspace_index());
+        // the last workspace comes before the first one
+        wm.cycle_workspace(PrevOrNext::Prev, false).unwrap();
+        assert_eq!(3, wm.get_current_workspace_index());
+        // skip the empty workspaces
+        wm.cycle_workspace(PrevOrNext::Next, true).unwrap();
+        assert_eq!(2, wm.get_current_workspace_index());
+        // there is no other non-empty workspace
+        wm.cycle_workspace(PrevOrNext::Next, true).unwrap();
+        assert_eq!(2, wm.get_current_workspace_index());
+    }
+
+    // A second, smaller screen to the right of `SCREEN`.
+    static RIGHT_GEOM: Geometry = Geometry {
+        x: 800,
+        y: 0,
+        width: 400,
+        height: 300,
+    };
+
+    #[test]
+    fn test_multiple_screens() {
+        let mut wm = new_with_workspaces();
+        assert_eq!(vec![SCREEN_GEOM], wm.get_screens());
+        wm.set_screens(vec![SCREEN_GEOM, RIGHT_GEOM]).unwrap();
+        assert_eq!(0, wm.get_current_screen_index());
+        assert_eq!(1, wm.get_screen_workspace(1).unwrap());
+        assert_eq!(Some(1), wm.get_workspace_screen(1));
+        assert_eq!(None, wm.get_workspace_screen(2));
+
+        // the windows of both screens are in the layout, the current one last
+        wm.add_window(WindowWithInfo::new_tiled(1, SOME_GEOM)).unwrap();
+        wm.add_window_to_workspace(1, WindowWithInfo::new_tiled(2, SOME_GEOM)).unwrap();
+        let layout = wm.get_window_layout();
+        assert_eq!(vec![(2, RIGHT_GEOM), (1, SCREEN_GEOM)], layout.windows);
+        assert_eq!(Some(1), layout.focused_window);
+
+        // switching to a shown workspace changes the current screen
+        wm.switch_workspace(1).unwrap();
+        assert_eq!(1, wm.get_current_screen_index());
+        assert_eq!(Some(0), wm.get_workspace_screen(0));
+        assert_eq!(vec![(1, SCREEN_GEOM), (2, RIGHT_GEOM)], wm.get_window_layout().windows);
+        // focusing a window on the other screen does the same
+        wm.focus_window(Some(1)).unwrap();
+        assert_eq!(0, wm.get_current_screen_index());
+        wm.focus_screen(1).unwrap();
+        assert_eq!(1, wm.get_current_workspace_index());
+
+        // switching to a hidden workspace shows it on the current screen
+        wm.switch_workspace(2).unwrap();
+        assert_eq!(1, wm.get_current_screen_index());
+        assert_eq!(2, wm.get_screen_workspace(1).unwrap());
+        assert_eq!(None, wm.get_workspace_screen(1));
+        assert_eq!(Screen { width: 400, height: 300 }, wm.get_screen());
+        assert_eq!(vec![(1, SCREEN_GEOM)], wm.get_window_layout().windows);
+
+        // the fn should return an error
+        assert!(wm.get_screen_workspace(2).is_err());
+        assert!(wm.focus_screen(2).is_err());
+        assert!(wm.delete_workspace(0).is_err());
+        assert!(wm.set_screens(Vec::new()).is_err());
+    }
+
+    #[test]
+    fn test_fullscreen_on_multiple_screens() {
+        let mut wm = new_with_workspaces();
+        wm.set_screens(vec![SCREEN_GEOM, RIGHT_GEOM]).unwrap();
+        wm.add_window(WindowWithInfo::new_fullscreen(1, SOME_GEOM)).unwrap();
+        wm.add_window_to_workspace(1, WindowWithInfo::new_tiled(2, SOME_GEOM)).unwrap();
+
+        // the workspace we leave stays shown on its screen, so its window stays fullscreen
+        wm.switch_workspace(1).unwrap();
+        assert_eq!(1, wm.get_current_screen_index());
+        assert_eq!(Some(1), wm.get_workspace(0).unwrap().get_fullscreen_window());
+        assert_eq!(vec![(1, SCREEN_GEOM), (2, RIGHT_GEOM)], wm.get_window_layout().windows);
+        wm.focus_screen(0).unwrap();
+        assert_eq!(Some(1), wm.get_fullscreen_window());
+
+        // a workspace that becomes hidden doesn't keep a fullscreen window
+        wm.switch_workspace(2).unwrap();
+        assert_eq!(None, wm.get_workspace(0).unwrap().get_fullscreen_window());
+        wm.switch_workspace(0).unwrap();
+        assert_eq!(None, wm.get_fullscreen_window());
+    }
+
+    #[test]
+    fn test_windows_on_multiple_screens() {
+        let mut wm = new_with_workspaces();
+        wm.set_screens(vec![SCREEN_GEOM, RIGHT_GEOM]).unwrap();
+        wm.add_window(WindowWithInfo::new_tiled(1, SOME_GEOM)).unwrap();
+        wm.add_window(WindowWithInfo::new_float(2, SOME_GEOM)).unwrap();
+
+        // a floating window moved to another screen keeps its place on the screen
+        wm.move_window_to_screen(2, 1, false).unwrap();
+        assert_eq!(Some(1), wm.get_window_workspace(2));
+        assert_eq!(0, wm.get_current_screen_index());
+        let geom = Geometry {
+            x: 810,
+            y: 10,
+            width: 100,
+            height: 100,
+        };
+        assert_eq!(vec![(2, geom), (1, SCREEN_GEOM)], wm.get_window_layout().windows);
+
+        // `set_window_geometry` uses the coordinates of the screen, like `get_window_info`
+        let geom = Geometry {
+            x: 50,
+            y: 50,
+            width: 100,
+            height: 100,
+        };
+        wm.set_window_geometry(2, geom).unwrap();
+        assert_eq!(geom, wm.get_window_info(2).unwrap().geometry);
+        let geom = Geometry { x: 850, ..geom };
+        assert_eq!(vec![(2, geom), (1, SCREEN_GEOM)], wm.get_window_layout().windows);
+        // so setting the geometry it returns doesn't move the window
+        let info = wm.get_window_info(2).unwrap();
+        wm.set_window_geometry(2, info.geometry).unwrap();
+        assert_eq!(info, wm.get_window_info(2).unwrap());
+        assert_eq!(vec![(2, geom), (1, SCREEN_GEOM)], wm.get_window_layout().windows);
+
+        // following a window makes its screen the current one
+        wm.move_window_to_screen(1, 1, true).unwrap();
+        assert_eq!(1, wm.get_current_screen_index());
+        assert_eq!(Some(1), wm.get_focused_window());
+        assert_eq!(vec![(1, RIGHT_GEOM), (2, geom)], wm.get_window_layout().windows);
+
+        // the fn should return an error
+        assert!(wm.move_window_to_screen(1, 2, false).is_err());
+        assert!(wm.move_window_to_screen(3, 0, false).is_err());
+    }
+
+    #[test]
+    fn test_set_screens() {
+        let mut wm = WMName::new(SCREEN);
+        wm.set_delete_empty_workspaces(true);
+        wm.add_window(WindowWithInfo::new_tiled(1, SOME_GEOM)).unwrap();
+
+        // a new screen gets a new workspace when there is no hidden one
+        wm.set_screens(vec![SCREEN_GEOM, RIGHT_GEOM]).unwrap();
+        assert_eq!(2, wm.get_workspace_count());
+        assert_eq!("2", wm.get_workspace_name(1).unwrap());
+        wm.focus_screen(1).unwrap();
+        wm.add_window(WindowWithInfo::new_tiled(2, SOME_GEOM)).unwrap();
+
+        // the windows of a screen that is gone stay on its workspace
+        wm.set_screens(vec![SCREEN_GEOM]).unwrap();
+        assert_eq!(0, wm.get_current_screen_index());
+        assert_eq!(0, wm.get_current_workspace_index());
+        assert_eq!(Some(1), wm.get_previous_workspace_index());
+        assert_eq!(vec![(1, SCREEN_GEOM)], wm.get_window_layout().windows);
+        assert_eq!(Some(1), wm.get_window_workspace(2));
+
+        // a new screen shows a hidden workspace
+        wm.set_screens(vec![SCREEN_GEOM, RIGHT_GEOM]).unwrap();
+        assert_eq!(2, wm.get_workspace_count());
+        assert_eq!(1, wm.get_screen_workspace(1).unwrap());
+        assert_eq!(vec![(2, RIGHT_GEOM), (1, SCREEN_GEOM)], wm.get_window_layout().windows);
+
+        // an empty workspace that becomes hidden is deleted
+        wm.remove_window(2).unwrap();
+        assert_eq!(2, wm.get_workspace_count());
+        wm.resize_screen(Screen {
+            width: 1024,
+            height: 768,
+        });
+        assert_eq!(1, wm.get_workspace_count());
+        let geom = Geometry {
+            x: 0,
+            y: 0,
+            width: 1024,
+            height: 768,
+        };
+        assert_eq!(vec![geom], wm.get_screens());
+        assert_eq!(vec![(1, geom)], wm.get_window_layout().windows);
+    }
+
+    #[test]
+    fn test_floating_windows_stay_on_screen() {
+        let mut wm = WMName::new(SCREEN);
+        let geom = Geometry {
+            x: 600,
+            y: 500,
+            width: 100,
+            height: 100,
+        };
+        wm.add_window(WindowWithInfo::new_float(1, geom)).unwrap();
+        wm.add_window(WindowWithInfo::new_float(2, SOME_GEOM)).unwrap();
+
+        // the screen becomes smaller, the window that sticks out is moved back onto it
+        let small = Geometry {
+            x: 0,
+            y: 0,
+            width: 400,
+            height: 300,
+        };
+        wm.set_screens(vec![small]).unwrap();
+        let moved = Geometry {
+            x: 300,
+            y: 200,
+            width: 100,
+            height: 100,
+        };
+        assert_eq!(vec![(1, moved), (2, SOME_GEOM)], wm.get_window_layout().windows);
+
+        // a window that is too large is shrunk
+        let large = Geometry {
+            x: 0,
+            y: 0,
+            width: 800,
+            height: 100,
+        };
+        wm.set_window_geometry(2, large).unwrap();
+        wm.resize_screen(Screen {
+            width: 300,
+            height: 400,
+        });
+        let shrunk = Geometry {
+            x: 0,
+            y: 0,
+            width: 300,
+            height: 100,
+        };
+        let moved = Geometry {
+            x: 200,
+            y: 200,
+            width: 100,
+            height: 100,
+        };
+        assert_eq!(vec![(1, moved), (2, shrunk)], wm.get_window_layout().windows);
+
+        // the same happens when a hidden workspace is shown on a smaller screen
+        let mut wm = WMName::new(SCREEN);
+        wm.add_window(WindowWithInfo::new_float(1, geom)).unwrap();
+        wm.set_screens(vec![SCREEN_GEOM, RIGHT_GEOM]).unwrap();
+        let index = wm.create_workspace("3").unwrap();
+        wm.switch_workspace(index).unwrap();
+        wm.focus_screen(1).unwrap();
+        wm.switch_workspace(0).unwrap();
+        let moved = Geometry {
+            x: 1100,
+            y: 200,
+            width: 100,
+            height: 100,
+        };
+        assert_eq!(vec![(1, moved)], wm.get_window_layout().windows);
+    }
+
     // To run these tests, run the command `cargo test` in the `solution`
     // directory.
 }
@@ -0,0 +1,1266 @@
+//! Optional: Binary Space Partitioning
+//!
+//! A tiling window manager that does not tile its windows according to a
+//! fixed layout algorithm, but that stores its tiles in a binary tree in
+//! which every inner node splits the area of its subtree in two parts, like
+//! [bspwm](https://github.com/baskerville/bspwm) does.
+//!
+//! A new window splits the tile of the focused window. By default the
+//! longest side of that tile is split and the new window is placed on the
+//! right or at the bottom. The user can *preselect* the side of the focused
+//! tile where the next window must be placed with `preselect`. The
+//! preselection is stored in the tile and forgotten when another window gets
+//! the focus.
+//!
+//! Besides the [`WindowManager`], [`TilingSupport`], [`FloatSupport`],
+//! [`MinimiseSupport`] and [`FullscreenSupport`] traits, so the X11 backend
+//! can run it, the window manager offers some methods to manipulate the
+//! split containing the focused window: `rotate_split`, `flip_split` and
+//! `set_split_ratio`.
+//!
+//! The tree is part of the state of the window manager, so it survives a
+//! restart of the window manager.
+//!
+//! [`WindowManager`]: ../../cplwm_api/wm/trait.WindowManager.html
+//! [`TilingSupport`]: ../../cplwm_api/wm/trait.TilingSupport.html
+//! [`FloatSupport`]: ../../cplwm_api/wm/trait.FloatSupport.html
+//! [`MinimiseSupport`]: ../../cplwm_api/wm/trait.MinimiseSupport.html
+//! [`FullscreenSupport`]: ../../cplwm_api/wm/trait.FullscreenSupport.html
+//!
+//! # Status
+//!
+//! COMPLETED: YES
+//!
+//! COMMENTS:
+//!
+//! There is no master column in the tree: the master window is the first
+//! tile of the tree and the master ratio is the ratio of the split at the
+//! root of the tree. The tree is the only layout, so the window manager
+//! doesn't implement [`LayoutSupport`]: there are no layouts to cycle
+//! through, no tile weights and no layout transforms.
+//!
+//! [`LayoutSupport`]: ../../cplwm_api/wm/trait.LayoutSupport.html
+//!
+//! A minimised tile leaves the tree, when it is unminimised it splits the
+//! focused tile like a new window.
+//!
+
+use cplwm_api::types::*;
+use cplwm_api::wm::{FloatSupport, FullscreenSupport, MinimiseSupport, TilingSupport,
+                    WindowManager};
+use wm_error::WMError;
+
+use std::collections::{HashMap, VecDeque};
+
+/// The name of the Window Manager
+pub type WMName = BspWM;
+
+/// How the area of a split is divided between its two children.
+#[derive(RustcDecodable, RustcEncodable, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Orientation {
+    /// The first child on the left, the second one on the right.
+    Horizontal,
+    /// The first child on the top, the second one at the bottom.
+    Vertical,
+}
+
+/// An inner node of the tree.
+#[derive(RustcDecodable, RustcEncodable, Debug, Clone, PartialEq)]
+pub struct Split {
+    /// How the area is divided between the children
+    pub orientation: Orientation,
+    /// The fraction of the area taken by the first child
+    pub ratio: MasterRatio,
+    /// The left or top child
+    pub first: Box<Node>,
+    /// The right or bottom child
+    pub second: Box<Node>,
+}
+
+/// A node of the tree: either a tile containing a window or a split.
+#[derive(RustcDecodable, RustcEncodable, Debug, Clone, PartialEq)]
+pub enum Node {
+    /// A tile, with the side preselected for the next window
+    Leaf(Window, Option<Direction>),
+    /// A split of the area in two parts
+    Split(Split),
+}
+
+impl Node {
+    /// Return true if this node is the tile of the given window.
+    fn is_leaf_of(&self, window: Window) -> bool {
+        match *self {
+            Node::Leaf(w, _) => w == window,
+            Node::Split(_) => false,
+        }
+    }
+
+    /// Return true if the given window is tiled in this subtree.
+    fn contains(&self, window: Window) -> bool {
+        match *self {
+            Node::Leaf(w, _) => w == window,
+            Node::Split(ref s) => s.first.contains(window) || s.second.contains(window),
+        }
+    }
+
+    /// Push the windows of the tiles of this subtree from left to right.
+    fn leaves(&self, leaves: &mut Vec<Window>) {
+        match *self {
+            Node::Leaf(w, _) => leaves.push(w),
+            Node::Split(ref s) => {
+                s.first.leaves(leaves);
+                s.second.leaves(leaves);
+            }
+        }
+    }
+
+    /// Push the tiles of this subtree, dividing the given area, from left to
+    /// right.
+    fn arrange(&self, area: Geometry, windows: &mut Vec<(Window, Geometry)>) {
+        match *self {
+            Node::Leaf(w, _) => windows.push((w, area)),
+            Node::Split(ref s) => {
+                let mut first = area;
+                let mut second = area;
+                match s.orientation {
+                    Orientation::Horizontal => {
+                        first.width = (area.width as MasterRatio * s.ratio) as u32;
+                        second.x += first.width as i32;
+                        second.width = area.width - first.width;
+                    }
+                    Orientation::Vertical => {
+                        first.height = (area.height as MasterRatio * s.ratio) as u32;
+                        second.y += first.height as i32;
+                        second.height = area.height - first.height;
+                    }
+                }
+                s.first.arrange(first, windows);
+                s.second.arrange(second, windows);
+            }
+        }
+    }
+
+    /// Split the tile of `target` and put `window` on the given side of it.
+    ///
+    /// Return false when `target` is not tiled in this subtree.
+    fn insert(&mut self,
+              target: Window,
+              window: Window,
+              side: Direction,
+              ratio: MasterRatio)
+              -> bool {
+        if self.is_leaf_of(target) {
+            let (orientation, first, second) = match side {
+                Direction::Left => (Orientation::Horizontal, window, target),
+                Direction::Right => (Orientation::Horizontal, target, window),
+                Direction::Up => (Orientation::Vertical, window, target),
+                Direction::Down => (Orientation::Vertical, target, window),
+            };
+            *self = Node::Split(Split {
+                orientation: orientation,
+                ratio: ratio,
+                first: Box::new(Node::Leaf(first, None)),
+                second: Box::new(Node::Leaf(second, None)),
+            });
+            return true;
+        }
+        match *self {
+            Node::Leaf(..) => false,
+            Node::Split(ref mut s) => {
+                s.first.insert(target, window, side, ratio) ||
+                s.second.insert(target, window, side, ratio)
+            }
+        }
+    }
+
+    /// Remove the tile of the given window from this subtree, its sibling
+    /// takes the place of their split.
+    ///
+    /// Return `None` when nothing is left of this subtree.
+    fn remove(self, window: Window) -> Option<Node> {
+        match self {
+            Node::Leaf(w, side) => {
+                if w == window {
+                    None
+                } else {
+                    Some(Node::Leaf(w, side))
+                }
+            }
+            Node::Split(s) => {
+                let Split { orientation, ratio, first, second } = s;
+                match ((*first).remove(window), (*second).remove(window)) {
+                    (Some(first), Some(second)) => {
+                        Some(Node::Split(Split {
+                            orientation: orientation,
+                            ratio: ratio,
+                            first: Box::new(first),
+                            second: Box::new(second),
+                        }))
+                    }
+                    (Some(node), None) | (None, Some(node)) => Some(node),
+                    (None, None) => None,
+                }
+            }
+        }
+    }
+
+    /// Exchange the tiles of the two given windows. The preselections stay
+    /// in the tiles, see `BspWM::swap_tiles`.
+    fn swap_leaves(&mut self, a: Window, b: Window) {
+        match *self {
+            Node::Leaf(ref mut w, _) => {
+                if *w == a {
+                    *w = b;
+                } else if *w == b {
+                    *w = a;
+                }
+            }
+            Node::Split(ref mut s) => {
+                s.first.swap_leaves(a, b);
+                s.second.swap_leaves(a, b);
+            }
+        }
+    }
+
+    /// Return the split of which the tile of the given window is a child.
+    fn parent_of(&self, window: Window) -> Option<&Split> {
+        match *self {
+            Node::Leaf(..) => None,
+            Node::Split(ref s) => {
+                if s.first.is_leaf_of(window) || s.second.is_leaf_of(window) {
+                    Some(s)
+                } else if s.first.contains(window) {
+                    s.first.parent_of(window)
+                } else {
+                    s.second.parent_of(window)
+                }
+            }
+        }
+    }
+
+    /// Return the split of which the tile of the given window is a child, so
+    /// it can be changed.
+    fn parent_of_mut(&mut self, window: Window) -> Option<&mut Split> {
+        match *self {
+            Node::Leaf(..) => None,
+            Node::Split(ref mut s) => {
+                if s.first.is_leaf_of(window) || s.second.is_leaf_of(window) {
+                    Some(s)
+                } else if s.first.contains(window) {
+                    s.first.parent_of_mut(window)
+                } else {
+                    s.second.parent_of_mut(window)
+                }
+            }
+        }
+    }
+
+    /// Return the side preselected in the tile of the given window.
+    fn preselection_of(&self, window: Window) -> Option<Direction> {
+        match *self {
+            Node::Leaf(w, side) => if w == window { side } else { None },
+            Node::Split(ref s) => {
+                s.first.preselection_of(window).or(s.second.preselection_of(window))
+            }
+        }
+    }
+
+    /// Return the preselection of the tile of the given window, so it can be
+    /// changed, or `None` when the window is not tiled in this subtree.
+    fn preselection_mut(&mut self, window: Window) -> Option<&mut Option<Direction>> {
+        match *self {
+            Node::Leaf(w, ref mut side) => if w == window { Some(side) } else { None },
+            Node::Split(ref mut s) => {
+                if s.first.contains(window) {
+                    s.first.preselection_mut(window)
+                } else {
+                    s.second.preselection_mut(window)
+                }
+            }
+        }
+    }
+
+    /// Forget the preselections of all the tiles, except the one of the
+    /// given window.
+    fn clear_preselections(&mut self, except: Option<Window>) {
+        match *self {
+            Node::Leaf(w, ref mut side) => {
+                if Some(w) != except {
+                    *side = None;
+                }
+            }
+            Node::Split(ref mut s) => {
+                s.first.clear_preselections(except);
+                s.second.clear_preselections(except);
+            }
+        }
+    }
+
+    /// Rotate this subtree by 90 degrees clockwise.
+    fn rotate(&mut self) {
+        if let Node::Split(ref mut s) = *self {
+            s.rotate();
+        }
+    }
+
+    /// Mirror this subtree, see `Split::flip`.
+    fn flip(&mut self, orientation: Orientation) {
+        if let Node::Split(ref mut s) = *self {
+            s.flip(orientation);
+        }
+    }
+}
+
+impl Split {
+    /// Rotate the split and its subtrees by 90 degrees clockwise: what was on
+    /// the left goes to the top, what was on the top goes to the right.
+    fn rotate(&mut self) {
+        match self.orientation {
+            Orientation::Horizontal => self.orientation = Orientation::Vertical,
+            Orientation::Vertical => {
+                self.orientation = Orientation::Horizontal;
+                self.swap_children();
+            }
+        }
+        self.first.rotate();
+        self.second.rotate();
+    }
+
+    /// Mirror the split and its subtrees: with `Horizontal`, what was on the
+    /// left goes to the right, with `Vertical`, what was on the top goes to
+    /// the bottom.
+    fn flip(&mut self, orientation: Orientation) {
+        if self.orientation == orientation {
+            self.swap_children();
+        }
+        self.first.flip(orientation);
+        self.second.flip(orientation);
+    }
+
+    /// Swap the children, they keep the size of their area.
+    fn swap_children(&mut self) {
+        ::std::mem::swap(&mut self.first, &mut self.second);
+        self.ratio = 1.0 - self.ratio;
+    }
+}
+
+/// The BspWM struct
+#[derive(RustcDecodable, RustcEncodable, Debug, Clone)]
+pub struct BspWM {
+    /// A VecDeque of all the windows, the one in the back is the focused window
+    pub windows: VecDeque<Window>,
+    /// A HashMap to store the info associated to windows, as passed to `add_window`
+    pub windows_info: HashMap<Window, WindowWithInfo>,
+    /// The tree of tiles, `None` when there are no tiled windows
+    pub root: Option<Node>,
+    /// An HashMap of floating windows <Window, Geometry>
+    pub floats: HashMap<Window, Geometry>,
+    /// The minimised windows, in the order in which they were minimised
+    pub minimised: Vec<Window>,
+    /// The fullscreen window, if any
+    pub fullscreen: Option<Window>,
+    /// The ratio of the split at the root of the tree
+    pub master_ratio: MasterRatio,
+    /// The screen managed by the window manager
+    pub screen: Screen,
+    /// Boolean variable to indicate if there is a focused window
+    pub is_focus: bool,
+}
+
+impl BspWM {
+    /// Return the tiled windows from left to right.
+    fn get_tiles(&self) -> Vec<Window> {
+        let mut leaves = Vec::new();
+        if let Some(ref root) = self.root {
+            root.leaves(&mut leaves);
+        }
+        leaves
+    }
+
+    /// Return the focused window if it is tiled.
+    fn get_focused_tile(&self) -> Option<Window> {
+        match (self.get_focused_window(), self.root.as_ref()) {
+            (Some(window), Some(root)) if root.contains(window) => Some(window),
+            _ => None,
+        }
+    }
+
+    /// Return the geometry of the tile of the given window, as arranged by the tree, whether
+    /// or not a window is fullscreen. `None` when the window isn't tiled.
+    fn get_tile_geometry(&self, window: Window) -> Option<Geometry> {
+        let mut windows = Vec::new();
+        if let Some(ref root) = self.root {
+            root.arrange(self.screen.to_geometry(), &mut windows);
+        }
+        windows.into_iter().find(|&(w, _)| w == window).map(|(_, geometry)| geometry)
+    }
+
+    /// Put the given window in the tree.
+    ///
+    /// Split the tile of the focused window, or when no tile is focused, the
+    /// last tile. Use the preselected side or otherwise split the longest
+    /// side of the tile.
+    fn insert_tile(&mut self, window: Window) {
+        let target = self.get_focused_tile().or(self.get_tiles().last().map(|w| *w));
+        match target {
+            None => self.root = Some(Node::Leaf(window, None)),
+            Some(target) => {
+                let preselection = self.root
+                    .as_mut()
+                    .and_then(|root| root.preselection_mut(target))
+                    .and_then(|side| side.take());
+                let side = match preselection {
+                    Some(side) => side,
+                    None => {
+                        let geometry = self.get_tile_geometry(target).unwrap();
+                        if geometry.width >= geometry.height {
+                            Direction::Right
+                        } else {
+                            Direction::Down
+                        }
+                    }
+                };
+                let root = self.root.as_mut().unwrap();
+                // The split at the root gets the master ratio
+                let ratio = if root.is_leaf_of(target) {
+                    self.master_ratio
+                } else {
+                    DEFAULT_MASTER_RATIO
+                };
+                root.insert(target, window, side, ratio);
+            }
+        }
+    }
+
+    /// Remove the given window from the tree.
+    fn remove_tile(&mut self, window: Window) {
+        self.root = self.root.take().and_then(|root| root.remove(window));
+    }
+
+    /// Exchange the tiles of the two given windows, their preselections go
+    /// along with them.
+    fn swap_tiles(&mut self, a: Window, b: Window) {
+        if let Some(ref mut root) = self.root {
+            let side_a = root.preselection_mut(a).and_then(|side| side.take());
+            let side_b = root.preselection_mut(b).and_then(|side| side.take());
+            root.swap_leaves(a, b);
+            if let Some(side) = root.preselection_mut(a) {
+                *side = side_a;
+            }
+            if let Some(side) = root.preselection_mut(b) {
+                *side = side_b;
+            }
+        }
+    }
+
+    /// Forget the preselections of the tiles that do not have the focus.
+    ///
+    /// Call this whenever the focus may have moved, so a preselection only
+    /// lasts while its tile stays focused.
+    fn forget_unfocused_preselections(&mut self) {
+        let focused = self.get_focused_window();
+        if let Some(ref mut root) = self.root {
+            root.clear_preselections(focused);
+        }
+    }
+
+    /// Remove the given window from the floats or the tree, it stays managed.
+    fn hide_window(&mut self, window: Window) {
+        if self.floats.remove(&window).is_none() {
+            self.remove_tile(window);
+        }
+    }
+
+    /// Focus the most recently focused window that is not minimised, or
+    /// nothing when all the windows are minimised.
+    fn focus_most_recent_visible(&mut self) {
+        let visible = self.windows.iter().rev().find(|w| !self.minimised.contains(w)).map(|w| *w);
+        self.focus_window(visible).unwrap();
+    }
+
+    /// Place the next window on the given side of the focused tile, instead
+    /// of splitting its longest side. Do nothing when no tile is focused.
+    ///
+    /// Preselecting the same side twice cancels the preselection.
+    pub fn preselect(&mut self, side: Direction) {
+        if let Some(window) = self.get_focused_tile() {
+            let preselection = self.root.as_mut().unwrap().preselection_mut(window).unwrap();
+            if *preselection == Some(side) {
+                *preselection = None;
+            } else {
+                *preselection = Some(side);
+            }
+        }
+    }
+
+    /// Return the side preselected in the focused tile, if any.
+    pub fn get_preselection(&self) -> Option<Direction> {
+        self.get_focused_tile()
+            .and_then(|window| self.root.as_ref().and_then(|root| root.preselection_of(window)))
+    }
+
+    /// Rotate the split containing the focused tile, and everything in it, by
+    /// 90 degrees clockwise. Do nothing when no tile is focused or when it is
+    /// the only tile.
+    pub fn rotate_split(&mut self) {
+        if let Some(window) = self.get_focused_tile() {
+            if let Some(split) = self.root.as_mut().and_then(|root| root.parent_of_mut(window)) {
+                split.rotate();
+            }
+        }
+    }
+
+    /// Mirror the split containing the focused tile, and everything in it.
+    /// `Horizontal` swaps left and right, `Vertical` swaps top and bottom. Do
+    /// nothing when no tile is focused or when it is the only tile.
+    pub fn flip_split(&mut self, orientation: Orientation) {
+        if let Some(window) = self.get_focused_tile() {
+            if let Some(split) = self.root.as_mut().and_then(|root| root.parent_of_mut(window)) {
+                split.flip(orientation);
+            }
+        }
+    }
+
+    /// Return the ratio of the split containing the focused tile, or `None`
+    /// when no tile is focused or when it is the only tile.
+    pub fn get_split_ratio(&self) -> Option<MasterRatio> {
+        self.get_focused_tile().and_then(|window| {
+            self.root.as_ref().and_then(|root| root.parent_of(window)).map(|s| s.ratio)
+        })
+    }
+
+    /// Set the ratio of the split containing the focused tile, clamping it
+    /// between `MIN_MASTER_RATIO` and `MAX_MASTER_RATIO`. Do nothing when no
+    /// tile is focused or when it is the only tile.
+    pub fn set_split_ratio(&mut self, ratio: MasterRatio) {
+        if let Some(window) = self.get_focused_tile() {
+            if let Some(split) = self.root.as_mut().and_then(|root| root.parent_of_mut(window)) {
+                split.ratio = ratio.max(MIN_MASTER_RATIO).min(MAX_MASTER_RATIO);
+            }
+        }
+    }
+}
+
+impl WindowManager for BspWM {
+    /// We use `WMError` as our `Error` type.
+    type Error = WMError;
+
+    /// The constructor is straightforward.
+    fn new(screen: Screen) -> BspWM {
+        BspWM {
+            windows: VecDeque::new(),
+            windows_info: HashMap::new(),
+            root: None,
+            floats: HashMap::new(),
+            minimised: Vec::new(),
+            fullscreen: None,
+            master_ratio: DEFAULT_MASTER_RATIO,
+            screen: screen,
+            is_focus: false,
+        }
+    }
+
+    /// The `windows` field contains all the windows we manage.
+    fn get_windows(&self) -> Vec<Window> {
+        self.windows.iter().map(|w| *w).collect()
+    }
+
+    /// The last window in the `windows` `VecDeque` is the focused one.
+    fn get_focused_window(&self) -> Option<Window> {
+        if self.is_focus {
+            self.windows.back().map(|x| *x)
+        } else {
+            None
+        }
+    }
+
+    /// Add the window to the floats or split the focused tile, then focus it. A fullscreen
+    /// window stops being fullscreen, unless the new window is fullscreen itself.
+    ///
+    /// The function returns an error if the window is already managed by the window manager.
+    fn add_window(&mut self, window_with_info: WindowWithInfo) -> Result<(), Self::Error> {
+        let window = window_with_info.window;
+        if self.is_managed(window) {
+            return Err(WMError::AlreadyManagedWindow(window));
+        }
+        match window_with_info.float_or_tile {
+            FloatOrTile::Float => {
+                self.floats.insert(window, window_with_info.geometry);
+            }
+            FloatOrTile::Tile => self.insert_tile(window),
+        }
+        self.windows_info.insert(window, window_with_info);
+        self.windows.push_back(window);
+        self.is_focus = true;
+        self.fullscreen = if window_with_info.fullscreen {
+            Some(window)
+        } else {
+            None
+        };
+        self.forget_unfocused_preselections();
+        Ok(())
+    }
+
+    /// Remove the window from the floats or the tree, or from the minimised windows.
+    ///
+    /// When the focus passes to a minimised window, focus the most recently focused window that
+    /// is visible instead.
+    fn remove_window(&mut self, window: Window) -> Result<(), Self::Error> {
+        match self.windows.iter().position(|w| *w == window) {
+            None => Err(WMError::UnknownWindow(window)),
+            Some(i) => {
+                self.windows.remove(i);
+                self.windows_info.remove(&window);
+                self.minimised.retain(|w| *w != window);
+                if self.fullscreen == Some(window) {
+                    self.fullscreen = None;
+                }
+                self.hide_window(window);
+                if self.windows.is_empty() {
+                    self.is_focus = false;
+                }
+                if let Some(focused) = self.get_focused_window() {
+                    if self.is_minimised(focused) {
+                        self.focus_most_recent_visible();
+                    }
+                }
+                self.forget_unfocused_preselections();
+                Ok(())
+            }
+        }
+    }
+
+    /// The tiles are arranged according to the tree, dividing the whole screen. The floating
+    /// windows follow, in the order of the focus.
+    ///
+    /// A fullscreen window is the only window, it takes the whole screen without a border.
+    fn get_window_layout(&self) -> WindowLayout {
+        if let Some(fullscreen) = self.fullscreen {
+            return WindowLayout {
+                focused_window: Some(fullscreen),
+                windows: vec![(fullscreen, self.screen.to_geometry())],
+                border_widths: vec![(fullscreen, 0)],
+            };
+        }
+        let mut windows = Vec::new();
+        if let Some(ref root) = self.root {
+            root.arrange(self.screen.to_geometry(), &mut windows);
+        }
+        for window in self.windows.iter() {
+            if let Some(geometry) = self.floats.get(window) {
+                windows.push((*window, *geometry));
+            }
+        }
+        WindowLayout {
+            focused_window: self.get_focused_window(),
+            windows: windows,
+            border_widths: Vec::new(),
+        }
+    }
+
+    /// Focus the given window, or when passed None, focus nothing.
+    ///
+    /// Move the new focused window in the last position of the windows `VecDeque`. A minimised
+    /// window is unminimised, a fullscreen window that loses the focus is no longer fullscreen.
+    fn focus_window(&mut self, window: Option<Window>) -> Result<(), Self::Error> {
+        match window {
+            None => {
+                self.is_focus = false;
+                self.fullscreen = None;
+            }
+            Some(window) => {
+                match self.windows.iter().position(|w| *w == window) {
+                    None => return Err(WMError::UnknownWindow(window)),
+                    Some(i) => {
+                        if self.is_minimised(window) {
+                            // unminimising focuses the window
+                            return self.toggle_minimised(window);
+                        }
+                        let w = self.windows.remove(i).unwrap();
+                        self.windows.push_back(w);
+                        self.is_focus = true;
+                        if self.fullscreen != Some(window) {
+                            self.fullscreen = None;
+                        }
+                    }
+                }
+            }
+        }
+        self.forget_unfocused_preselections();
+        Ok(())
+    }
+
+    /// Focus the previous or next window.
+    ///
+    /// Behaves as the `cycle_focus` of the `TilingWM`. When the window that gets the focus is
+    /// minimised, it is unminimised.
+    fn cycle_focus(&mut self, dir: PrevOrNext) {
+        if self.windows.is_empty() {
+            return;
+        }
+        if self.is_focus {
+            match dir {
+                PrevOrNext::Prev => {
+                    let w = self.windows.pop_back().unwrap();
+                    self.windows.push_front(w);
+                }
+                PrevOrNext::Next => {
+                    let w = self.windows.pop_front().unwrap();
+                    self.windows.push_back(w);
+                }
+            }
+        }
+        self.is_focus = true;
+        let window = self.get_focused_window().unwrap();
+        if self.fullscreen != Some(window) {
+            self.fullscreen = None;
+        }
+        if self.is_minimised(window) {
+            self.toggle_minimised(window).unwrap();
+        }
+        self.forget_unfocused_preselections();
+    }
+
+    /// Get the info (WindowWithInfo) belonging to the given window, with its current geometry.
+    ///
+    /// A fullscreen window keeps the geometry of its tile or the one it floats at.
+    fn get_window_info(&self, window: Window) -> Result<WindowWithInfo, Self::Error> {
+        match self.windows_info.get(&window) {
+            None => Err(WMError::UnknownWindow(window)),
+            Some(window_with_info) => {
+                let mut info = *window_with_info;
+                if let Some(geometry) = self.get_tile_geometry(window) {
+                    info.geometry = geometry;
+                }
+                info.fullscreen = self.fullscreen == Some(window);
+                Ok(info)
+            }
+        }
+    }
+
+    /// Return the screen managed by the window manager.
+    fn get_screen(&self) -> Screen {
+        self.screen
+    }
+
+    /// Resize the screen according to the given Screen.
+    fn resize_screen(&mut self, screen: Screen) {
+        self.screen = screen;
+    }
+}
+
+impl TilingSupport for BspWM {
+    /// The master window is the first tile of the tree, i.e. the top-left one.
+    fn get_master_window(&self) -> Option<Window> {
+        self.get_tiles().first().map(|w| *w)
+    }
+
+    /// Exchange the tiles of the given window and the master window, then focus the window.
+    ///
+    /// Do nothing when the window is floating.
+    fn swap_with_master(&mut self, window: Window) -> Result<(), Self::Error> {
+        if !self.is_managed(window) {
+            return Err(WMError::UnknownWindow(window));
+        }
+        if self.is_floating(window) {
+            return Ok(());
+        }
+        let master = self.get_master_window().unwrap();
+        self.swap_tiles(window, master);
+        self.focus_window(Some(window))
+    }
+
+    /// Exchange the tile of the focused window with the previous or next tile, from left to
+    /// right, considering the tiles as circular.
+    ///
+    /// Do nothing when there is no focused tile.
+    fn swap_windows(&mut self, dir: PrevOrNext) {
+        if let Some(window) = self.get_focused_tile() {
+            let tiles = self.get_tiles();
+            let len = tiles.len();
+            let i = tiles.iter().position(|w| *w == window).unwrap();
+            let j = match dir {
+                PrevOrNext::Prev => (i + len - 1) % len,
+                PrevOrNext::Next => (i + 1) % len,
+            };
+            self.swap_tiles(window, tiles[j]);
+        }
+    }
+
+    /// Return the ratio of the split at the root of the tree.
+    fn get_master_ratio(&self) -> MasterRatio {
+        match self.root {
+            Some(Node::Split(ref s)) => s.ratio,
+            _ => self.master_ratio,
+        }
+    }
+
+    /// Set the ratio of the split at the root of the tree, clamping it between
+    /// `MIN_MASTER_RATIO` and `MAX_MASTER_RATIO`.
+    ///
+    /// When there is no split yet, the ratio is used when the root is split.
+    fn set_master_ratio(&mut self, ratio: MasterRatio) {
+        self.master_ratio = ratio.max(MIN_MASTER_RATIO).min(MAX_MASTER_RATIO);
+        if let Some(Node::Split(ref mut s)) = self.root {
+            s.ratio = self.master_ratio;
+        }
+    }
+
+    /// All the tiles are always shown, so there are no tabs.
+    fn get_tabs(&self) -> Vec<Window> {
+        Vec::new()
+    }
+}
+
+impl FloatSupport for BspWM {
+    /// Return the list of floating windows.
+    fn get_floating_windows(&self) -> Vec<Window> {
+        self.floats.keys().map(|w| *w).collect()
+    }
+
+    /// If the given window is floating, let it sink, if it is not floating, let it float.
+    ///
+    /// A sinking window splits the focused tile like a new window, a window that starts to float
+    /// gets its original geometry back and its tile disappears. A minimised window only changes
+    /// how it comes back when it is unminimised.
+    fn toggle_floating(&mut self, window: Window) -> Result<(), Self::Error> {
+        let geometry = match self.windows_info.get(&window) {
+            None => return Err(WMError::UnknownWindow(window)),
+            Some(window_with_info) => window_with_info.geometry,
+        };
+        if self.is_minimised(window) {
+            let window_with_info = self.windows_info.get_mut(&window).unwrap();
+            window_with_info.float_or_tile = match window_with_info.float_or_tile {
+                FloatOrTile::Float => FloatOrTile::Tile,
+                FloatOrTile::Tile => FloatOrTile::Float,
+            };
+        } else if self.floats.remove(&window).is_some() {
+            self.insert_tile(window);
+            self.windows_info.get_mut(&window).unwrap().float_or_tile = FloatOrTile::Tile;
+        } else {
+            self.remove_tile(window);
+            self.floats.insert(window, geometry);
+            self.windows_info.get_mut(&window).unwrap().float_or_tile = FloatOrTile::Float;
+        }
+        Ok(())
+    }
+
+    /// Resize/move the given floating window according to the given geometry, also when it is
+    /// minimised. A fullscreen window is no longer fullscreen.
+    ///
+    /// Do nothing when the window is tiled.
+    fn set_window_geometry(&mut self,
+                           window: Window,
+                           new_geometry: Geometry)
+                           -> Result<(), Self::Error> {
+        match self.windows_info.get_mut(&window) {
+            None => Err(WMError::UnknownWindow(window)),
+            Some(window_with_info) => {
+                if window_with_info.float_or_tile == FloatOrTile::Float {
+                    window_with_info.geometry = new_geometry;
+                    if self.floats.contains_key(&window) {
+                        self.floats.insert(window, new_geometry);
+                    }
+                    if self.fullscreen == Some(window) {
+                        self.fullscreen = None;
+                    }
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl MinimiseSupport for BspWM {
+    /// Return the `minimised` `Vec`.
+    fn get_minimised_windows(&self) -> Vec<Window> {
+        self.minimised.clone()
+    }
+
+    /// Minimise the window: it leaves the floats or the tree, and the most recently focused
+    /// window that is visible gets the focus when it had it.
+    ///
+    /// Unminimise the window: it floats at its geometry again or splits the focused tile like a
+    /// new window, and it gets the focus.
+    fn toggle_minimised(&mut self, window: Window) -> Result<(), Self::Error> {
+        let window_with_info = match self.windows_info.get(&window) {
+            None => return Err(WMError::UnknownWindow(window)),
+            Some(window_with_info) => *window_with_info,
+        };
+        if self.is_minimised(window) {
+            self.minimised.retain(|w| *w != window);
+            match window_with_info.float_or_tile {
+                FloatOrTile::Float => {
+                    self.floats.insert(window, window_with_info.geometry);
+                }
+                FloatOrTile::Tile => self.insert_tile(window),
+            }
+            self.focus_window(Some(window))
+        } else {
+            if self.fullscreen == Some(window) {
+                self.fullscreen = None;
+            }
+            self.minimised.push(window);
+            self.hide_window(window);
+            if self.get_focused_window() == Some(window) {
+                self.focus_most_recent_visible();
+            }
+            Ok(())
+        }
+    }
+}
+
+impl FullscreenSupport for BspWM {
+    /// Return the `fullscreen` window.
+    fn get_fullscreen_window(&self) -> Option<Window> {
+        self.fullscreen
+    }
+
+    /// Make the given window fullscreen and focus it, unminimising it when needed, or when it is
+    /// already fullscreen, undo it.
+    ///
+    /// The window keeps its tile or its floating geometry, so undoing it restores the layout.
+    fn toggle_fullscreen(&mut self, window: Window) -> Result<(), Self::Error> {
+        if !self.is_managed(window) {
+            return Err(WMError::UnknownWindow(window));
+        }
+        if self.fullscreen == Some(window) {
+            self.fullscreen = None;
+        } else {
+            try!(self.focus_window(Some(window)));
+            self.fullscreen = Some(window);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::{BspWM, Node, Orientation};
+    use cplwm_api::wm::{FloatSupport, FullscreenSupport, MinimiseSupport, TilingSupport,
+                        WindowManager};
+    use cplwm_api::types::*;
+    use rustc_serialize::json::{decode, encode};
+
+    // We define a static variable for the screen we will use in the tests.
+    static SCREEN: Screen = Screen {
+        width: 800,
+        height: 600,
+    };
+
+    // We define a static variable for the geometry of a fullscreen window.
+    // Note that it matches the dimensions of `SCREEN`.
+    static SCREEN_GEOM: Geometry = Geometry {
+        x: 0,
+        y: 0,
+        width: 800,
+        height: 600,
+    };
+
+    // We define a static variable for some random geometry that we will use
+    // when adding windows to a window manager.
+    static SOME_GEOM: Geometry = Geometry {
+        x: 10,
+        y: 10,
+        width: 100,
+        height: 100,
+    };
+
+    // Shorthand to build the expected geometries.
+    fn geom(x: i32, y: i32, width: u32, height: u32) -> Geometry {
+        Geometry {
+            x: x,
+            y: y,
+            width: width,
+            height: height,
+        }
+    }
+
+    #[test]
+    fn test_adding_and_removing_some_windows() {
+        let mut wm = BspWM::new(SCREEN);
+        assert_eq!(WindowLayout::new(), wm.get_window_layout());
+
+        // The first window takes the whole screen
+        wm.add_window(WindowWithInfo::new_tiled(1, SOME_GEOM)).unwrap();
+        assert_eq!(vec![(1, SCREEN_GEOM)], wm.get_window_layout().windows);
+        assert!(wm.add_window(WindowWithInfo::new_tiled(1, SOME_GEOM)).is_err());
+
+        // The screen is wider than high, so the second window goes to the right
+        wm.add_window(WindowWithInfo::new_tiled(2, SOME_GEOM)).unwrap();
+        // The tile of 2 is higher than wide, so the third window goes to the bottom
+        wm.add_window(WindowWithInfo::new_tiled(3, SOME_GEOM)).unwrap();
+        //  _ _ _ _ _ _
+        // |     |  2  |
+        // |  1  |_ _ _|
+        // |     |  3  |
+        // |_ _ _|_ _ _|
+        let wl = wm.get_window_layout();
+        assert_eq!(Some(3), wl.focused_window);
+        assert_eq!(vec![(1, geom(0, 0, 400, 600)),
+                        (2, geom(400, 0, 400, 300)),
+                        (3, geom(400, 300, 400, 300))],
+                   wl.windows);
+        assert_eq!(geom(400, 0, 400, 300), wm.get_window_info(2).unwrap().geometry);
+
+        // Focus 1 and put the next window on top of it
+        wm.focus_window(Some(1)).unwrap();
+        wm.preselect(Direction::Up);
+        wm.add_window(WindowWithInfo::new_tiled(4, SOME_GEOM)).unwrap();
+        //  _ _ _ _ _ _
+        // |  4  |  2  |
+        // |_ _ _|_ _ _|
+        // |  1  |  3  |
+        // |_ _ _|_ _ _|
+        assert_eq!(vec![(4, geom(0, 0, 400, 300)),
+                        (1, geom(0, 300, 400, 300)),
+                        (2, geom(400, 0, 400, 300)),
+                        (3, geom(400, 300, 400, 300))],
+                   wm.get_window_layout().windows);
+        // The preselection is used only once
+        wm.focus_window(Some(1)).unwrap();
+        assert_eq!(None, wm.get_preselection());
+
+        // The preselection belongs to the focused tile, it is forgotten when the focus moves
+        wm.preselect(Direction::Left);
+        assert_eq!(Some(Direction::Left), wm.get_preselection());
+        wm.focus_window(Some(2)).unwrap();
+        assert_eq!(None, wm.get_preselection());
+        wm.focus_window(Some(1)).unwrap();
+        assert_eq!(None, wm.get_preselection());
+        // but it follows the window when its tile is swapped
+        wm.preselect(Direction::Left);
+        wm.swap_windows(PrevOrNext::Next);
+        assert_eq!(Some(Direction::Left), wm.get_preselection());
+        wm.swap_windows(PrevOrNext::Prev);
+        wm.preselect(Direction::Left);
+        assert_eq!(None, wm.get_preselection());
+
+        // Removing 2 gives its space to its sibling
+        wm.remove_window(2).unwrap();
+        assert_eq!(vec![(4, geom(0, 0, 400, 300)),
+                        (1, geom(0, 300, 400, 300)),
+                        (3, geom(400, 0, 400, 600))],
+                   wm.get_window_layout().windows);
+        assert!(wm.remove_window(2).is_err());
+        wm.remove_window(4).unwrap();
+        wm.remove_window(1).unwrap();
+        assert_eq!(vec![(3, SCREEN_GEOM)], wm.get_window_layout().windows);
+        wm.remove_window(3).unwrap();
+        assert_eq!(None, wm.root);
+        assert_eq!(WindowLayout::new(), wm.get_window_layout());
+    }
+
+    #[test]
+    fn test_manipulating_splits() {
+        let mut wm = BspWM::new(SCREEN);
+        wm.add_window(WindowWithInfo::new_tiled(1, SOME_GEOM)).unwrap();
+        wm.add_window(WindowWithInfo::new_tiled(2, SOME_GEOM)).unwrap();
+        wm.add_window(WindowWithInfo::new_tiled(3, SOME_GEOM)).unwrap();
+
+        // Rotating the split of 3 puts 2 on the right and 3 on the left
+        wm.rotate_split();
+        assert_eq!(vec![(1, geom(0, 0, 400, 600)),
+                        (3, geom(400, 0, 200, 600)),
+                        (2, geom(600, 0, 200, 600))],
+                   wm.get_window_layout().windows);
+        // Flipping it horizontally brings them back to their side
+        wm.flip_split(Orientation::Horizontal);
+        assert_eq!(vec![(1, geom(0, 0, 400, 600)),
+                        (2, geom(400, 0, 200, 600)),
+                        (3, geom(600, 0, 200, 600))],
+                   wm.get_window_layout().windows);
+        // Flipping it vertically does nothing
+        wm.flip_split(Orientation::Vertical);
+        assert_eq!(geom(600, 0, 200, 600), wm.get_window_info(3).unwrap().geometry);
+
+        // Make 2 wider than 3
+        wm.set_split_ratio(0.75);
+        assert_eq!(Some(0.75), wm.get_split_ratio());
+        assert_eq!(geom(700, 0, 100, 600), wm.get_window_info(3).unwrap().geometry);
+        // The ratio is clamped
+        wm.set_split_ratio(2.0);
+        assert_eq!(Some(MAX_MASTER_RATIO), wm.get_split_ratio());
+
+        // The master ratio is the ratio of the root split
+        wm.set_master_ratio(0.25);
+        assert_eq!(0.25, wm.get_master_ratio());
+        assert_eq!(geom(0, 0, 200, 600), wm.get_window_info(1).unwrap().geometry);
+
+        // Swapping moves the windows through the tree
+        assert_eq!(Some(1), wm.get_master_window());
+        wm.swap_with_master(3).unwrap();
+        assert_eq!(Some(3), wm.get_master_window());
+        assert_eq!(Some(3), wm.get_focused_window());
+        wm.swap_windows(PrevOrNext::Next);
+        wm.swap_windows(PrevOrNext::Prev);
+        assert_eq!(Some(3), wm.get_master_window());
+        wm.swap_windows(PrevOrNext::Prev);
+        assert_eq!(Some(1), wm.get_master_window());
+        assert_eq!(geom(0, 0, 200, 600), wm.get_window_info(1).unwrap().geometry);
+    }
+
+    #[test]
+    fn test_floating_windows() {
+        let mut wm = BspWM::new(SCREEN);
+        wm.add_window(WindowWithInfo::new_tiled(1, SOME_GEOM)).unwrap();
+        wm.add_window(WindowWithInfo::new_float(2, SOME_GEOM)).unwrap();
+        // Floating windows are not in the tree
+        assert_eq!(vec![(1, SCREEN_GEOM), (2, SOME_GEOM)],
+                   wm.get_window_layout().windows);
+        assert_eq!(vec![2], wm.get_floating_windows());
+
+        // A sinking window splits the last tile when a floating window is focused
+        wm.toggle_floating(2).unwrap();
+        assert!(!wm.is_floating(2));
+        assert_eq!(vec![(1, geom(0, 0, 400, 600)), (2, geom(400, 0, 400, 600))],
+                   wm.get_window_layout().windows);
+        // and gets its original geometry back when floating again
+        wm.toggle_floating(2).unwrap();
+        assert!(wm.is_floating(2));
+        assert_eq!(vec![(1, SCREEN_GEOM), (2, SOME_GEOM)],
+                   wm.get_window_layout().windows);
+
+        // Only floating windows can be moved
+        wm.set_window_geometry(2, SCREEN_GEOM).unwrap();
+        wm.set_window_geometry(1, SOME_GEOM).unwrap();
+        assert_eq!(vec![(1, SCREEN_GEOM), (2, SCREEN_GEOM)],
+                   wm.get_window_layout().windows);
+        assert!(wm.toggle_floating(3).is_err());
+    }
+
+    #[test]
+    fn test_restoring_the_tree() {
+        let mut wm = BspWM::new(SCREEN);
+        wm.add_window(WindowWithInfo::new_tiled(1, SOME_GEOM)).unwrap();
+        wm.add_window(WindowWithInfo::new_tiled(2, SOME_GEOM)).unwrap();
+        wm.add_window(WindowWithInfo::new_tiled(3, SOME_GEOM)).unwrap();
+        wm.set_split_ratio(0.3);
+        wm.preselect(Direction::Left);
+
+        // The tree must survive serialising and deserialising the window manager
+        let restored: BspWM = decode(&encode(&wm).unwrap()).unwrap();
+        assert_eq!(wm.root, restored.root);
+        assert_eq!(Some(Direction::Left), restored.get_preselection());
+        assert_eq!(wm.get_window_layout(), restored.get_window_layout());
+        match restored.root {
+            Some(Node::Split(ref s)) => assert_eq!(Orientation::Horizontal, s.orientation),
+            _ => panic!("the root should be a split"),
+        }
+    }
+
+    #[test]
+    fn test_minimising_windows() {
+        let mut wm = BspWM::new(SCREEN);
+        wm.add_window(WindowWithInfo::new_tiled(1, SOME_GEOM)).unwrap();
+        wm.add_window(WindowWithInfo::new_tiled(2, SOME_GEOM)).unwrap();
+        wm.add_window(WindowWithInfo::new_float(3, SOME_GEOM)).unwrap();
+        assert!(wm.toggle_minimised(4).is_err());
+
+        // A minimised tile leaves the tree, the previously focused window gets the focus
+        wm.focus_window(Some(2)).unwrap();
+        wm.toggle_minimised(2).unwrap();
+        assert!(wm.is_minimised(2));
+        assert!(wm.is_managed(2));
+        assert_eq!(Some(3), wm.get_focused_window());
+        assert_eq!(vec![(1, SCREEN_GEOM), (3, SOME_GEOM)],
+                   wm.get_window_layout().windows);
+        // A minimised floating window leaves the floats
+        wm.toggle_minimised(3).unwrap();
+        assert_eq!(vec![2, 3], wm.get_minimised_windows());
+        assert_eq!(Vec::<Window>::new(), wm.get_floating_windows());
+        assert_eq!(Some(1), wm.get_focused_window());
+
+        // Unminimising the floating window puts it back where it was
+        wm.toggle_minimised(3).unwrap();
+        assert_eq!(Some(3), wm.get_focused_window());
+        assert_eq!(vec![(1, SCREEN_GEOM), (3, SOME_GEOM)],
+                   wm.get_window_layout().windows);
+        // Focusing a minimised tile unminimises it, it splits the last tile
+        wm.focus_window(Some(2)).unwrap();
+        assert!(!wm.is_minimised(2));
+        assert_eq!(vec![(1, geom(0, 0, 400, 600)), (2, geom(400, 0, 400, 600)), (3, SOME_GEOM)],
+                   wm.get_window_layout().windows);
+
+        // Removing a minimised window forgets it
+        wm.toggle_minimised(1).unwrap();
+        wm.remove_window(1).unwrap();
+        assert_eq!(Vec::<Window>::new(), wm.get_minimised_windows());
+        assert_eq!(vec![(2, SCREEN_GEOM), (3, SOME_GEOM)],
+                   wm.get_window_layout().windows);
+    }
+
+    #[test]
+    fn test_fullscreen_windows() {
+        let mut wm = BspWM::new(SCREEN);
+        wm.add_window(WindowWithInfo::new_tiled(1, SOME_GEOM)).unwrap();
+        wm.add_window(WindowWithInfo::new_tiled(2, SOME_GEOM)).unwrap();
+        wm.add_window(WindowWithInfo::new_float(3, SOME_GEOM)).unwrap();
+        let wl = wm.get_window_layout();
+        assert!(wm.toggle_fullscreen(4).is_err());
+
+        // A fullscreen window is the only visible window and gets the focus
+        wm.toggle_fullscreen(1).unwrap();
+        assert_eq!(Some(1), wm.get_fullscreen_window());
+        assert_eq!(Some(1), wm.get_focused_window());
+        assert_eq!(vec![(1, SCREEN_GEOM)], wm.get_window_layout().windows);
+        assert!(wm.get_window_info(1).unwrap().fullscreen);
+        // it keeps its tile
+        assert_eq!(geom(0, 0, 400, 600), wm.get_window_info(1).unwrap().geometry);
+
+        // Toggling it again restores the layout
+        wm.toggle_fullscreen(1).unwrap();
+        assert_eq!(None, wm.get_fullscreen_window());
+        assert_eq!(wl.windows, wm.get_window_layout().windows);
+
+        // Focusing another window ends the fullscreen
+        wm.toggle_fullscreen(3).unwrap();
+        assert_eq!(vec![(3, SCREEN_GEOM)], wm.get_window_layout().windows);
+        wm.focus_window(Some(2)).unwrap();
+        assert_eq!(None, wm.get_fullscreen_window());
+        // as does minimising it
+        wm.toggle_fullscreen(2).unwrap();
+        wm.toggle_minimised(2).unwrap();
+        assert_eq!(None, wm.get_fullscreen_window());
+        // A minimised window that becomes fullscreen is unminimised
+        wm.toggle_fullscreen(2).unwrap();
+        assert!(!wm.is_minimised(2));
+        assert_eq!(vec![(2, SCREEN_GEOM)], wm.get_window_layout().windows);
+
+        // A new fullscreen window takes over, removing it shows the others again
+        let mut window_with_info = WindowWithInfo::new_tiled(4, SOME_GEOM);
+        window_with_info.fullscreen = true;
+        wm.add_window(window_with_info).unwrap();
+        assert_eq!(Some(4), wm.get_fullscreen_window());
+        wm.remove_window(4).unwrap();
+        assert_eq!(None, wm.get_fullscreen_window());
+        assert_eq!(3, wm.get_window_layout().windows.len());
+    }
+
+    #[test]
+    fn test_fullscreen_float_and_new_tiles() {
+        let mut wm = BspWM::new(SCREEN);
+        wm.add_window(WindowWithInfo::new_tiled(1, SOME_GEOM)).unwrap();
+        wm.add_window(WindowWithInfo::new_float(2, SOME_GEOM)).unwrap();
+        wm.toggle_fullscreen(2).unwrap();
+
+        // No tile is focused, so the last tile is split, although it isn't visible
+        wm.add_window(WindowWithInfo::new_tiled(3, SOME_GEOM)).unwrap();
+        assert_eq!(vec![(1, geom(0, 0, 400, 600)), (3, geom(400, 0, 400, 600)), (2, SOME_GEOM)],
+                   wm.get_window_layout().windows);
+
+        // The same goes for sinking a fullscreen float
+        wm.add_window(WindowWithInfo::new_float(4, SOME_GEOM)).unwrap();
+        wm.toggle_fullscreen(4).unwrap();
+        wm.toggle_floating(4).unwrap();
+        assert_eq!(vec![(4, SCREEN_GEOM)], wm.get_window_layout().windows);
+        assert_eq!(geom(400, 300, 400, 300), wm.get_window_info(4).unwrap().geometry);
+
+        // and for unminimising a tile
+        wm.toggle_minimised(3).unwrap();
+        wm.toggle_fullscreen(2).unwrap();
+        wm.toggle_minimised(3).unwrap();
+        assert_eq!(3, wm.get_tiles().len());
+    }
+}
@@ -0,0 +1,820 @@
+//! Tiling Layouts
+//!
+//! The layout algorithms the tiling window managers can choose from at
+//! run-time. Every algorithm is a variant of the [`TilingLayout`] enum, which
+//! implements the [`Layout`] trait by dispatching to the function of the
+//! variant.
+//!
+//! We use an enum instead of boxed [`Layout`] trait objects because the
+//! window manager must remain `Encodable` and `Decodable`: this way the
+//! selected layout survives a restart of the window manager.
+//!
+//! The tiles arranged by any of these layouts can be transformed afterwards,
+//! e.g. mirrored or rotated, with the [`transform`] function, and separated
+//! from each other with the [`add_inner_gaps`] function.
+//!
+//! To move the focus around on the screen, [`window_in_direction`] finds the
+//! neighbour of a window in any arrangement of windows.
+//!
+//! [`TilingLayout`]: enum.TilingLayout.html
+//! [`Layout`]: ../../cplwm_api/wm/trait.Layout.html
+//! [`transform`]: fn.transform.html
+//! [`add_inner_gaps`]: fn.add_inner_gaps.html
+//! [`window_in_direction`]: fn.window_in_direction.html
+
+use cplwm_api::types::*;
+use cplwm_api::wm::Layout;
+
+use std::cmp::min;
+
+/// The layout algorithms a tiling window manager can cycle through.
+#[derive(RustcDecodable, RustcEncodable, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TilingLayout {
+    /// The master tile on the left half of the screen, the other tiles
+    /// stacked on top of each other on the right half. This is the layout
+    /// described in the documentation of `TilingSupport`.
+    MasterStack,
+    /// The tiles arranged in rows and columns, as close to a square grid as
+    /// possible.
+    Grid,
+    /// Every tile takes half of the area left by the previous tiles, turning
+    /// clockwise: left, top, right, bottom, left, ...
+    Spiral,
+    /// The master column in the center of the area, the other tiles
+    /// alternating between a column on the right and one on the left.
+    CenteredMaster,
+    /// Only the focused tile is shown, taking the whole area except for the
+    /// tab strip on the top.
+    Monocle,
+}
+
+impl TilingLayout {
+    /// Return all the available layouts, in the order in which
+    /// `cycle_layout` goes through them. The first one is the default.
+    pub fn all() -> Vec<TilingLayout> {
+        vec![TilingLayout::MasterStack,
+             TilingLayout::Grid,
+             TilingLayout::Spiral,
+             TilingLayout::CenteredMaster,
+             TilingLayout::Monocle]
+    }
+}
+
+impl Layout for TilingLayout {
+    /// Return the name of the layout.
+    fn get_name(&self) -> &'static str {
+        match *self {
+            TilingLayout::MasterStack => "master-stack",
+            TilingLayout::Grid => "grid",
+            TilingLayout::Spiral => "spiral",
+            TilingLayout::CenteredMaster => "centered-master",
+            TilingLayout::Monocle => "monocle",
+        }
+    }
+
+    /// Only the monocle layout has tabs.
+    fn has_tabs(&self) -> bool {
+        *self == TilingLayout::Monocle
+    }
+
+    /// Call the function implementing the layout.
+    ///
+    /// Tiles without a weight, i.e. when `weights` is shorter than `tiles`,
+    /// get the `DEFAULT_TILE_WEIGHT`.
+    fn arrange(&self,
+               tiles: &[Window],
+               weights: &[TileWeight],
+               focused: Option<Window>,
+               area: Geometry,
+               params: &LayoutParams)
+               -> Vec<(Window, Geometry)> {
+        let weights: Vec<TileWeight> = (0..tiles.len())
+            .map(|i| weights.get(i).map(|w| *w).unwrap_or(DEFAULT_TILE_WEIGHT))
+            .collect();
+        match *self {
+            TilingLayout::MasterStack => master_stack(tiles, &weights, area, params),
+            TilingLayout::Grid => grid(tiles, area),
+            TilingLayout::Spiral => spiral(tiles, area, params),
+            TilingLayout::CenteredMaster => centered_master(tiles, &weights, area, params),
+            TilingLayout::Monocle => monocle(tiles, focused, area),
+        }
+    }
+}
+
+/// Return the width of the master column of the given area.
+fn master_width(area: Geometry, params: &LayoutParams) -> u32 {
+    (area.width as MasterRatio * params.master_ratio) as u32
+}
+
+/// Stack the given tiles on top of each other in the given area, each of them
+/// getting a share of the height proportional to its weight. The last tile
+/// also gets the pixels left over by the rounding.
+fn column(tiles: &[Window], weights: &[TileWeight], area: Geometry) -> Vec<(Window, Geometry)> {
+    let mut windows: Vec<(Window, Geometry)> = Vec::new();
+    let total = weights.iter().fold(0.0, |total, w| total + w);
+    let mut y = 0;
+    for (i, tile) in tiles.iter().enumerate() {
+        let height = if i == tiles.len() - 1 {
+            area.height - y
+        } else {
+            (area.height as TileWeight * weights[i] / total) as u32
+        };
+        let mut geometry = area;
+        geometry.y += y as i32;
+        geometry.height = height;
+        windows.push((*tile, geometry));
+        y += height;
+    }
+    windows
+}
+
+/// The first `nmaster` tiles are stacked in the master column on the left,
+/// as wide as the master ratio says, the other tiles are stacked in the
+/// column on the right.
+///
+/// When all the tiles are in the master column (e.g. there is only one tile)
+/// or when `nmaster` is 0, there is only one column, taking the whole area.
+fn master_stack(tiles: &[Window],
+                weights: &[TileWeight],
+                area: Geometry,
+                params: &LayoutParams)
+                -> Vec<(Window, Geometry)> {
+    let nmaster = min(params.nmaster, tiles.len());
+    if nmaster == 0 || nmaster == tiles.len() {
+        return column(tiles, weights, area);
+    }
+    let mut master_area = area;
+    master_area.width = master_width(area, params);
+    let mut stack_area = area;
+    stack_area.x += master_area.width as i32;
+    stack_area.width = area.width - master_area.width;
+
+    let mut windows = column(&tiles[..nmaster], &weights[..nmaster], master_area);
+    windows.extend(column(&tiles[nmaster..], &weights[nmaster..], stack_area));
+    windows
+}
+
+/// Split `length` pixels in `parts` equal parts and return the offset and the
+/// size of the part with index `i`. The last part also gets the pixels left
+/// over by the division, so the parts always add up to `length`.
+fn split(length: u32, parts: usize, i: usize) -> (i32, u32) {
+    let size = length / (parts as u32);
+    let offset = size * (i as u32);
+    if i == parts - 1 {
+        (offset as i32, length - offset)
+    } else {
+        (offset as i32, size)
+    }
+}
+
+/// Arrange the tiles row by row in a grid with as many columns as the square
+/// root of the number of tiles, rounded up. The last row can contain fewer
+/// tiles, these share the whole width of the area. The last row and column
+/// get the pixels left over by the divisions, so the whole area is covered.
+fn grid(tiles: &[Window], area: Geometry) -> Vec<(Window, Geometry)> {
+    let mut windows: Vec<(Window, Geometry)> = Vec::new();
+    if tiles.is_empty() {
+        return windows;
+    }
+    let mut cols = 1;
+    while cols * cols < tiles.len() {
+        cols += 1;
+    }
+    let rows = (tiles.len() + cols - 1) / cols;
+    for (i, tile) in tiles.iter().enumerate() {
+        let (row, col) = (i / cols, i % cols);
+        // the number of tiles in this row
+        let cols_in_row = min(cols, tiles.len() - row * cols);
+        let (x, width) = split(area.width, cols_in_row, col);
+        let (y, height) = split(area.height, rows, row);
+        let geometry = Geometry {
+            x: area.x + x,
+            y: area.y + y,
+            width: width,
+            height: height,
+        };
+        windows.push((*tile, geometry));
+    }
+    windows
+}
+
+/// The first tile, the master, starts the spiral, taking the left part of
+/// the area, as wide as the master ratio says. Every next tile takes half of
+/// the area that is left, the side it takes turning clockwise, so the tiles
+/// form a spiral. The last tile takes all of the area that is left.
+fn spiral(tiles: &[Window], area: Geometry, params: &LayoutParams) -> Vec<(Window, Geometry)> {
+    let mut windows: Vec<(Window, Geometry)> = Vec::new();
+    let mut rest = area;
+    for (i, tile) in tiles.iter().enumerate() {
+        if i == tiles.len() - 1 {
+            windows.push((*tile, rest));
+            break;
+        }
+        let mut geometry = rest;
+        match i % 4 {
+            // take the left part
+            0 => {
+                geometry.width = if i == 0 {
+                    master_width(rest, params)
+                } else {
+                    rest.width / 2
+                };
+                rest.x += geometry.width as i32;
+                rest.width -= geometry.width;
+            }
+            // take the top half
+            1 => {
+                geometry.height = rest.height / 2;
+                rest.y += geometry.height as i32;
+                rest.height -= geometry.height;
+            }
+            // take the right half
+            2 => {
+                geometry.width = rest.width / 2;
+                geometry.x += (rest.width - geometry.width) as i32;
+                rest.width -= geometry.width;
+            }
+            // take the bottom half
+            _ => {
+                geometry.height = rest.height / 2;
+                geometry.y += (rest.height - geometry.height) as i32;
+                rest.height -= geometry.height;
+            }
+        }
+        windows.push((*tile, geometry));
+    }
+    windows
+}
+
+/// The first `nmaster` tiles are stacked in a column in the center of the
+/// area, as wide as the master ratio says. The other tiles alternate between
+/// the column on the right and the column on the left, which share the rest
+/// of the width.
+///
+/// With a single tile outside the master column there is nothing to put in
+/// the left column, so the tiles are arranged as by `master_stack`.
+fn centered_master(tiles: &[Window],
+                   weights: &[TileWeight],
+                   area: Geometry,
+                   params: &LayoutParams)
+                   -> Vec<(Window, Geometry)> {
+    let nmaster = min(params.nmaster, tiles.len());
+    if nmaster == 0 || tiles.len() - nmaster < 2 {
+        return master_stack(tiles, weights, area, params);
+    }
+    let mut master_area = area;
+    master_area.width = master_width(area, params);
+    let mut left_area = area;
+    left_area.width = (area.width - master_area.width) / 2;
+    master_area.x += left_area.width as i32;
+    let mut right_area = area;
+    right_area.x = master_area.x + master_area.width as i32;
+    right_area.width = area.width - master_area.width - left_area.width;
+
+    let (mut right, mut right_weights) = (Vec::new(), Vec::new());
+    let (mut left, mut left_weights) = (Vec::new(), Vec::new());
+    for (i, tile) in tiles[nmaster..].iter().enumerate() {
+        if i % 2 == 0 {
+            right.push(*tile);
+            right_weights.push(weights[nmaster + i]);
+        } else {
+            left.push(*tile);
+            left_weights.push(weights[nmaster + i]);
+        }
+    }
+    let mut right_windows = column(&right, &right_weights, right_area).into_iter();
+    let mut left_windows = column(&left, &left_weights, left_area).into_iter();
+
+    // Put the windows back in the order of the tiles.
+    let mut windows = column(&tiles[..nmaster], &weights[..nmaster], master_area);
+    for i in 0..tiles.len() - nmaster {
+        if i % 2 == 0 {
+            windows.extend(right_windows.next());
+        } else {
+            windows.extend(left_windows.next());
+        }
+    }
+    windows
+}
+
+/// Only show the focused tile, or the first one when no tile is focused. When
+/// there is more than one tile, leave room for the tab strip on top.
+fn monocle(tiles: &[Window], focused: Option<Window>, area: Geometry) -> Vec<(Window, Geometry)> {
+    match tiles.len() {
+        0 => Vec::new(),
+        1 => vec![(tiles[0], area)],
+        _ => {
+            let shown = focused.and_then(|f| tiles.iter().find(|t| **t == f))
+                .unwrap_or(&tiles[0]);
+            let strip = min(TAB_STRIP_HEIGHT, area.height);
+            let mut geometry = area;
+            geometry.y += strip as i32;
+            geometry.height -= strip;
+            vec![(*shown, geometry)]
+        }
+    }
+}
+
+/// Apply the given transforms to the tiles arranged in the given area.
+///
+/// The transforms are applied in the order of `LayoutTransform::all()`,
+/// whatever their order in `transforms`. The order of the tiles does not
+/// change.
+///
+/// Instead of the position and the size of every tile, the position of its
+/// edges are transformed. Edges shared by neighbouring tiles stay shared, so
+/// when the tiles covered the whole area, the transformed tiles cover it as
+/// well.
+pub fn transform(windows: Vec<(Window, Geometry)>,
+                 transforms: &[LayoutTransform],
+                 area: Geometry)
+                 -> Vec<(Window, Geometry)> {
+    let mut windows = windows;
+    for t in LayoutTransform::all().into_iter().filter(|t| transforms.contains(t)) {
+        windows = windows.into_iter()
+            .map(|(window, geometry)| (window, transform_geometry(geometry, t, area)))
+            .collect();
+    }
+    windows
+}
+
+/// Apply the transform to a single geometry in the given area.
+fn transform_geometry(geometry: Geometry, transform: LayoutTransform, area: Geometry) -> Geometry {
+    // The edges relative to the top left corner of the area
+    let left = (geometry.x - area.x) as u32;
+    let top = (geometry.y - area.y) as u32;
+    let right = left + geometry.width;
+    let bottom = top + geometry.height;
+    let (left, top, right, bottom) = match transform {
+        // Clockwise: the left edge becomes the top edge, the bottom edge the
+        // left edge, and so on.
+        LayoutTransform::Rotate => {
+            (area.width - scale(bottom, area.height, area.width),
+             scale(left, area.width, area.height),
+             area.width - scale(top, area.height, area.width),
+             scale(right, area.width, area.height))
+        }
+        LayoutTransform::MirrorHorizontally => (area.width - right, top, area.width - left, bottom),
+        LayoutTransform::MirrorVertically => (left, area.height - bottom, right, area.height - top),
+    };
+    Geometry {
+        x: area.x + left as i32,
+        y: area.y + top as i32,
+        width: right - left,
+        height: bottom - top,
+    }
+}
+
+/// Shrink the tiles arranged in the given area so there is a gap of `gap`
+/// pixels between neighbouring tiles.
+///
+/// Only the edges of a tile that are inside the area move, the edges on the
+/// border of the area stay where they are. Of every gap, the tile on the
+/// left or on the top gives up half of it, the tile on the right or at the
+/// bottom the other half.
+pub fn add_inner_gaps(windows: Vec<(Window, Geometry)>,
+                      gap: GapSize,
+                      area: Geometry)
+                      -> Vec<(Window, Geometry)> {
+    let before = gap / 2;
+    let after = gap - before;
+    windows.into_iter()
+        .map(|(window, geometry)| {
+            let mut left = geometry.x;
+            let mut top = geometry.y;
+            let mut right = geometry.x + geometry.width as i32;
+            let mut bottom = geometry.y + geometry.height as i32;
+            if left > area.x {
+                left += after as i32;
+            }
+            if top > area.y {
+                top += after as i32;
+            }
+            if right < area.x + area.width as i32 {
+                right -= before as i32;
+            }
+            if bottom < area.y + area.height as i32 {
+                bottom -= before as i32;
+            }
+            let geometry = Geometry {
+                x: left,
+                y: top,
+                width: if right > left { (right - left) as u32 } else { 0 },
+                height: if bottom > top { (bottom - top) as u32 } else { 0 },
+            };
+            (window, geometry)
+        })
+        .collect()
+}
+
+/// Return the window nearest to the given window in the given direction,
+/// among the given windows, e.g. the windows of a `WindowLayout`.
+///
+/// Only the windows whose center lies beyond the center of the given window
+/// in the direction are considered. Of those, the windows that lie next to
+/// the given window, i.e. that overlap with it across the direction, are
+/// preferred. Then the nearest one along the direction wins, then the one
+/// best aligned with the given window. Remaining ties are broken by the
+/// order of the windows.
+///
+/// Return `None` when the given window is not one of the windows or when no
+/// window lies in the direction.
+pub fn window_in_direction(windows: &[(Window, Geometry)],
+                           window: Window,
+                           direction: Direction)
+                           -> Option<Window> {
+    let from = match windows.iter().find(|&&(w, _)| w == window) {
+        Some(&(_, geometry)) => geometry,
+        None => return None,
+    };
+    let (from_along, from_across) = center(from, direction);
+    windows.iter()
+        .filter(|&&(w, _)| w != window)
+        .filter_map(|&(w, geometry)| {
+            let (along, across) = center(geometry, direction);
+            if along <= from_along {
+                return None;
+            }
+            let next_to = overlap_across(from, geometry, direction);
+            Some(((!next_to, along - from_along, (across - from_across).abs()), w))
+        })
+        .min_by_key(|&(key, _)| key)
+        .map(|(_, w)| w)
+}
+
+/// Return the coordinates of the center of the geometry, doubled to avoid
+/// rounding: first along the direction, growing in the direction, then
+/// across it.
+fn center(geometry: Geometry, direction: Direction) -> (i64, i64) {
+    let x = 2 * geometry.x as i64 + geometry.width as i64;
+    let y = 2 * geometry.y as i64 + geometry.height as i64;
+    match direction {
+        Direction::Left => (-x, y),
+        Direction::Right => (x, y),
+        Direction::Up => (-y, x),
+        Direction::Down => (y, x),
+    }
+}
+
+/// Return true when the two geometries overlap across the direction, e.g.
+/// when they share some rows for `Left` and `Right`.
+fn overlap_across(a: Geometry, b: Geometry, direction: Direction) -> bool {
+    match direction {
+        Direction::Left | Direction::Right => {
+            a.y < b.y + b.height as i32 && b.y < a.y + a.height as i32
+        }
+        Direction::Up | Direction::Down => {
+            a.x < b.x + b.width as i32 && b.x < a.x + a.width as i32
+        }
+    }
+}
+
+/// Scale the position `pos` on an axis of length `from` to an axis of length
+/// `to`, rounding to the nearest pixel. The ends of the axis are mapped onto
+/// the ends of the other axis.
+fn scale(pos: u32, from: u32, to: u32) -> u32 {
+    if from == 0 {
+        return 0;
+    }
+    ((pos as u64 * to as u64 + from as u64 / 2) / from as u64) as u32
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::{TilingLayout, transform, window_in_direction};
+    use cplwm_api::wm::Layout;
+    use cplwm_api::types::*;
+
+    // An area that does not start in the origin and can't be divided evenly.
+    static AREA: Geometry = Geometry {
+        x: 10,
+        y: 20,
+        width: 1001,
+        height: 601,
+    };
+
+    // Check that no two geometries overlap and that together they cover the
+    // whole area, by comparing the total surface.
+    fn assert_covers_area(windows: &Vec<(Window, Geometry)>, area: Geometry) {
+        let mut surface = 0;
+        for (i, &(_, g)) in windows.iter().enumerate() {
+            assert!(g.x >= area.x && g.y >= area.y);
+            assert!(g.x + g.width as i32 <= area.x + area.width as i32);
+            assert!(g.y + g.height as i32 <= area.y + area.height as i32);
+            for &(_, h) in windows.iter().skip(i + 1) {
+                let apart = g.x + g.width as i32 <= h.x || h.x + h.width as i32 <= g.x ||
+                            g.y + g.height as i32 <= h.y ||
+                            h.y + h.height as i32 <= g.y;
+                assert!(apart, "{} overlaps with {}", g, h);
+            }
+            surface += g.width * g.height;
+        }
+        assert_eq!(area.width * area.height, surface);
+    }
+
+    #[test]
+    fn test_grid() {
+        let params = LayoutParams::new();
+        assert!(TilingLayout::Grid.arrange(&[], &[], None, AREA, &params).is_empty());
+
+        for n in 1..12 {
+            let tiles: Vec<Window> = (0..n).collect();
+            let windows = TilingLayout::Grid.arrange(&tiles, &[], None, AREA, &params);
+            // Every tile occurs once, in the same order
+            let order: Vec<Window> = windows.iter().map(|&(w, _)| w).collect();
+            assert_eq!(tiles, order);
+            assert_covers_area(&windows, AREA);
+        }
+
+        // Five tiles: three on the first row, two on the second one
+        let windows = TilingLayout::Grid.arrange(&[1, 2, 3, 4, 5], &[], None, AREA, &params);
+        assert_eq!(Geometry {
+                       x: 10,
+                       y: 20,
+                       width: 333,
+                       height: 300,
+                   },
+                   windows[0].1);
+        assert_eq!(Geometry {
+                       x: 676,
+                       y: 20,
+                       width: 335,
+                       height: 300,
+                   },
+                   windows[2].1);
+        assert_eq!(Geometry {
+                       x: 510,
+                       y: 320,
+                       width: 501,
+                       height: 301,
+                   },
+                   windows[4].1);
+    }
+
+    #[test]
+    fn test_spiral() {
+        let params = LayoutParams::new();
+        assert!(TilingLayout::Spiral.arrange(&[], &[], None, AREA, &params).is_empty());
+        assert_eq!(vec![(1, AREA)],
+                   TilingLayout::Spiral.arrange(&[1], &[], None, AREA, &params));
+
+        for n in 1..12 {
+            let tiles: Vec<Window> = (0..n).collect();
+            let windows = TilingLayout::Spiral.arrange(&tiles, &[], None, AREA, &params);
+            // Every tile occurs once, in the same order
+            let order: Vec<Window> = windows.iter().map(|&(w, _)| w).collect();
+            assert_eq!(tiles, order);
+            assert_covers_area(&windows, AREA);
+        }
+
+        //  _ _ _ _ _ _ _ _
+        // |       |   2   |
+        // |   1   |_ _ _ _|
+        // |       | 4 | 3 |
+        // |_ _ _ _|_ _|_ _|
+        let windows = TilingLayout::Spiral.arrange(&[1, 2, 3, 4], &[], None, AREA, &params);
+        let geometries: Vec<Geometry> = windows.iter().map(|&(_, g)| g).collect();
+        assert_eq!(vec![Geometry {
+                            x: 10,
+                            y: 20,
+                            width: 500,
+                            height: 601,
+                        },
+                        Geometry {
+                            x: 510,
+                            y: 20,
+                            width: 501,
+                            height: 300,
+                        },
+                        Geometry {
+                            x: 761,
+                            y: 320,
+                            width: 250,
+                            height: 301,
+                        },
+                        Geometry {
+                            x: 510,
+                            y: 320,
+                            width: 251,
+                            height: 301,
+                        }],
+                   geometries);
+    }
+
+    #[test]
+    fn test_centered_master() {
+        let params = LayoutParams::new();
+        let layout = TilingLayout::CenteredMaster;
+        assert!(layout.arrange(&[], &[], None, AREA, &params).is_empty());
+
+        for n in 1..12 {
+            let tiles: Vec<Window> = (0..n).collect();
+            let windows = layout.arrange(&tiles, &[], None, AREA, &params);
+            // Every tile occurs once, in the same order
+            let order: Vec<Window> = windows.iter().map(|&(w, _)| w).collect();
+            assert_eq!(tiles, order);
+            assert_covers_area(&windows, AREA);
+        }
+
+        // With one or two tiles there are no empty side columns
+        assert_eq!(TilingLayout::MasterStack.arrange(&[1], &[], None, AREA, &params),
+                   layout.arrange(&[1], &[], None, AREA, &params));
+        assert_eq!(TilingLayout::MasterStack.arrange(&[1, 2], &[], None, AREA, &params),
+                   layout.arrange(&[1, 2], &[], None, AREA, &params));
+
+        //  _ _ _ _ _ _ _ _
+        // |   |       | 2 |
+        // | 3 |   1   |_ _|
+        // |   |       | 4 |
+        // |_ _|_ _ _ _|_ _|
+        let windows = layout.arrange(&[1, 2, 3, 4], &[], None, AREA, &params);
+        let geometries: Vec<Geometry> = windows.iter().map(|&(_, g)| g).collect();
+        assert_eq!(vec![Geometry {
+                            x: 260,
+                            y: 20,
+                            width: 500,
+                            height: 601,
+                        },
+                        Geometry {
+                            x: 760,
+                            y: 20,
+                            width: 251,
+                            height: 300,
+                        },
+                        Geometry {
+                            x: 10,
+                            y: 20,
+                            width: 250,
+                            height: 601,
+                        },
+                        Geometry {
+                            x: 760,
+                            y: 320,
+                            width: 251,
+                            height: 301,
+                        }],
+                   geometries);
+    }
+
+    #[test]
+    fn test_monocle() {
+        let params = LayoutParams::new();
+        let layout = TilingLayout::Monocle;
+        assert!(layout.has_tabs());
+        assert!(!TilingLayout::MasterStack.has_tabs());
+        assert!(layout.arrange(&[], &[], None, AREA, &params).is_empty());
+        // A single tile takes the whole area, there are no tabs
+        assert_eq!(vec![(1, AREA)], layout.arrange(&[1], &[], Some(1), AREA, &params));
+
+        // Only the focused tile is shown, below the tab strip
+        let below_strip = Geometry {
+            x: 10,
+            y: 40,
+            width: 1001,
+            height: 581,
+        };
+        assert_eq!(vec![(2, below_strip)],
+                   layout.arrange(&[1, 2, 3], &[], Some(2), AREA, &params));
+        // or the first one when there is no focused tile
+        assert_eq!(vec![(1, below_strip)],
+                   layout.arrange(&[1, 2, 3], &[], None, AREA, &params));
+    }
+
+    #[test]
+    fn test_transforms() {
+        let params = LayoutParams::new();
+        let all = LayoutTransform::all();
+        // Every combination of transforms
+        let combinations: Vec<Vec<LayoutTransform>> = (0..8)
+            .map(|bits| {
+                all.iter()
+                    .enumerate()
+                    .filter(|&(i, _)| bits & (1 << i) != 0)
+                    .map(|(_, t)| *t)
+                    .collect()
+            })
+            .collect();
+        for layout in TilingLayout::all().into_iter().filter(|l| !l.has_tabs()) {
+            for n in 1..8 {
+                let tiles: Vec<Window> = (0..n).collect();
+                let windows = layout.arrange(&tiles, &[], None, AREA, &params);
+                for transforms in combinations.iter() {
+                    let transformed = transform(windows.clone(), transforms, AREA);
+                    // The order of the tiles doesn't change
+                    let order: Vec<Window> = transformed.iter().map(|&(w, _)| w).collect();
+                    assert_eq!(tiles, order);
+                    assert_covers_area(&transformed, AREA);
+                }
+            }
+        }
+
+        let windows = TilingLayout::MasterStack.arrange(&[1, 2, 3], &[], None, AREA, &params);
+        assert_eq!(windows, transform(windows.clone(), &[], AREA));
+        //  _ _ _ _ _ _ _ _
+        // |       1       |
+        // |_ _ _ _ _ _ _ _|
+        // |   3   |   2   |
+        // |_ _ _ _|_ _ _ _|
+        let rotated = transform(windows.clone(), &[LayoutTransform::Rotate], AREA);
+        let geometries: Vec<Geometry> = rotated.iter().map(|&(_, g)| g).collect();
+        assert_eq!(vec![Geometry {
+                            x: 10,
+                            y: 20,
+                            width: 1001,
+                            height: 300,
+                        },
+                        Geometry {
+                            x: 511,
+                            y: 320,
+                            width: 500,
+                            height: 301,
+                        },
+                        Geometry {
+                            x: 10,
+                            y: 320,
+                            width: 501,
+                            height: 301,
+                        }],
+                   geometries);
+        // Rotating four times brings back the original tiles
+        let mut rotated = windows.clone();
+        for _ in 0..4 {
+            rotated = transform(rotated, &[LayoutTransform::Rotate], AREA);
+        }
+        assert_eq!(windows, rotated);
+        // Mirroring vertically afterwards moves the master row to the bottom,
+        // whatever the order in which the transforms are given
+        let both = transform(windows.clone(),
+                             &[LayoutTransform::MirrorVertically, LayoutTransform::Rotate],
+                             AREA);
+        assert_eq!(Geometry {
+                       x: 10,
+                       y: 321,
+                       width: 1001,
+                       height: 300,
+                   },
+                   both[0].1);
+        assert_eq!(Geometry {
+                       x: 511,
+                       y: 20,
+                       width: 500,
+                       height: 301,
+                   },
+                   both[1].1);
+        // Mirroring horizontally moves the master column to the right
+        let mirrored = transform(windows, &[LayoutTransform::MirrorHorizontally], AREA);
+        assert_eq!(Geometry {
+                       x: 511,
+                       y: 20,
+                       width: 500,
+                       height: 601,
+                   },
+                   mirrored[0].1);
+    }
+
+    #[test]
+    fn test_window_in_direction() {
+        let params = LayoutParams::new();
+        // Three tiles on the first row, two on the second one
+        let windows = TilingLayout::Grid.arrange(&[1, 2, 3, 4, 5], &[], None, AREA, &params);
+        assert_eq!(Some(2), window_in_direction(&windows, 1, Direction::Right));
+        assert_eq!(Some(5), window_in_direction(&windows, 4, Direction::Right));
+        assert_eq!(None, window_in_direction(&windows, 1, Direction::Left));
+        assert_eq!(None, window_in_direction(&windows, 1, Direction::Up));
+        assert_eq!(None, window_in_direction(&windows, 5, Direction::Down));
+        // The tile below or above
+        assert_eq!(Some(4), window_in_direction(&windows, 1, Direction::Down));
+        assert_eq!(Some(5), window_in_direction(&windows, 3, Direction::Down));
+        // Below the middle tile are two tiles, the best aligned one wins
+        assert_eq!(Some(4), window_in_direction(&windows, 2, Direction::Down));
+        assert_eq!(Some(3), window_in_direction(&windows, 5, Direction::Up));
+        // Unknown windows have no neighbours
+        assert_eq!(None, window_in_direction(&windows, 6, Direction::Up));
+
+        // A tile that lies next to the window is preferred over a nearer one that doesn't
+        let windows = vec![(1,
+                            Geometry {
+                               x: 0,
+                               y: 0,
+                               width: 100,
+                               height: 100,
+                           }),
+                           (2,
+                            Geometry {
+                               x: 50,
+                               y: 150,
+                               width: 100,
+                               height: 100,
+                           }),
+                           (3,
+                            Geometry {
+                               x: 300,
+                               y: 50,
+                               width: 100,
+                               height: 100,
+                           })];
+        assert_eq!(Some(3), window_in_direction(&windows, 1, Direction::Right));
+        assert_eq!(Some(2), window_in_direction(&windows, 1, Direction::Down));
+    }
+}
@@ -70,7 +70,10 @@ pub mod d_minimising_windows;
 pub mod e_fullscreen_windows;
 pub mod f_gaps;
 pub mod g_multiple_workspaces;
+pub mod h_bsp_wm;
 
 // Declare additional modules below or declare them in other modules.
 /// Error returned by Window Manager
-pub mod wm_error; 
+pub mod wm_error;
+/// Layout algorithms used by the tiling Window Managers
+pub mod layout;
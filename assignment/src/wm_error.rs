@@ -1,7 +1,7 @@
 use std::error;
 use std::fmt;
 
-use cplwm_api::types::{Window, WorkspaceIndex};
+use cplwm_api::types::{ScreenIndex, Window, WorkspaceIndex};
 
 /// The errors that this window manager can return.
 ///
@@ -21,6 +21,22 @@ pub enum WMError {
     AlreadyManagedWindow(Window),
     /// The workspace index is not valid.
     WorkspaceIndexNotValid(WorkspaceIndex),
+    /// The workspace name is empty or contains a NUL character.
+    WorkspaceNameNotValid(String),
+    /// Another workspace already has this name.
+    WorkspaceNameInUse(String),
+    /// The workspace still contains windows, so it can't be deleted.
+    WorkspaceNotEmpty(WorkspaceIndex),
+    /// The current workspace can't be deleted.
+    CurrentWorkspace(WorkspaceIndex),
+    /// The workspace is shown on a screen, so it can't be deleted.
+    VisibleWorkspace(WorkspaceIndex),
+    /// The screen index is not valid.
+    ScreenIndexNotValid(ScreenIndex),
+    /// There must be at least one screen.
+    NoScreens,
+    /// This window is the hidden scratchpad, which is on no workspace.
+    HiddenScratchpad(Window),
 }
 
 // This code is explained in the documentation of the associated [Error] type
@@ -35,6 +51,28 @@ impl fmt::Display for WMError {
             WMError::WorkspaceIndexNotValid(ref index) => {
                 write!(f, "The workspace index is not valid: {}", index)
             }
+            WMError::WorkspaceNameNotValid(ref name) => {
+                write!(f, "The workspace name is not valid: {:?}", name)
+            }
+            WMError::WorkspaceNameInUse(ref name) => {
+                write!(f, "The workspace name is already in use: {}", name)
+            }
+            WMError::WorkspaceNotEmpty(ref index) => {
+                write!(f, "The workspace is not empty: {}", index)
+            }
+            WMError::CurrentWorkspace(ref index) => {
+                write!(f, "The workspace is the current one: {}", index)
+            }
+            WMError::VisibleWorkspace(ref index) => {
+                write!(f, "The workspace is shown on a screen: {}", index)
+            }
+            WMError::ScreenIndexNotValid(ref screen) => {
+                write!(f, "The screen index is not valid: {}", screen)
+            }
+            WMError::NoScreens => write!(f, "There must be at least one screen"),
+            WMError::HiddenScratchpad(ref window) => {
+                write!(f, "The window is the hidden scratchpad: {}", window)
+            }
         }
     }
 }
@@ -47,6 +85,14 @@ impl error::Error for WMError {
             WMError::UnknownWindow(_) => "Unknown window",
             WMError::AlreadyManagedWindow(_) => "Already managed window",
             WMError::WorkspaceIndexNotValid(_) => "Workspace index not valid", 
+            WMError::WorkspaceNameNotValid(_) => "Workspace name not valid",
+            WMError::WorkspaceNameInUse(_) => "Workspace name in use",
+            WMError::WorkspaceNotEmpty(_) => "Workspace not empty",
+            WMError::CurrentWorkspace(_) => "Current workspace",
+            WMError::VisibleWorkspace(_) => "Visible workspace",
+            WMError::ScreenIndexNotValid(_) => "Screen index not valid",
+            WMError::NoScreens => "No screens",
+            WMError::HiddenScratchpad(_) => "Hidden scratchpad",
         }
     }
 }
@@ -11,7 +11,8 @@
 //! run a window manager from a previous assignment by providing dummy (just
 //! do nothing, or use the [`unimplemented!`] macro) implementations for the
 //! missing traits. You can also comment out code in this file that uses
-//! unimplemented functionality.
+//! unimplemented functionality, e.g. the extensions chosen in [`main`] that
+//! need more than `FloatSupport`, `FullscreenSupport` and `MinimiseSupport`.
 //!
 //! [`unimplemented!`]: https://doc.rust-lang.org/std/macro.unimplemented.html
 //!
@@ -106,6 +107,60 @@
 //! that your mouse cursor cannot leave the window anymore. Press
 //! <kbd>Ctrl-Shift</kbd> again to *uncapture* the input.
 //!
+//! ### Multiple monitors
+//!
+//! Xephyr can also pretend to have more than one monitor. Every `-screen`
+//! option opens a window that acts as a monitor, `+xinerama` glues them
+//! together into one display, the second one to the right of the first:
+//!
+//! ```bash
+//! $ Xephyr -ac -br -noreset +xinerama -screen 800x600 -screen 800x600 :1
+//! ```
+//!
+//! Every monitor shows its own workspace. Use <kbd>Super-w</kbd> and
+//! <kbd>Super-e</kbd> to go to the first and second monitor. Without a
+//! display at hand, e.g. to check the layout in the log file, the virtual
+//! framebuffer X server works as well:
+//!
+//! ```bash
+//! $ Xvfb :1 +xinerama -screen 0 800x600x24 -screen 1 800x600x24
+//! ```
+//!
+//! The window manager follows the changes made with `xrandr` while it runs.
+//! Start a nested X server *without* `+xinerama`, so the monitors are the
+//! ones RandR knows about, and the window manager in it. Resizing or rotating
+//! the screen shows how the layout adapts:
+//!
+//! ```bash
+//! $ Xephyr -ac -br -noreset -screen 1600x600 :1
+//! $ DISPLAY=:1 xrandr -s 1024x768
+//! $ DISPLAY=:1 xrandr -o left
+//! ```
+//!
+//! Plugging monitors in and out can be simulated by splitting the screen into
+//! virtual monitors. The first one takes the place of the monitor of the
+//! output, replace `OUTPUT` by the name `xrandr --listmonitors` shows. The
+//! workspace of a monitor that is removed is hidden, its windows are not
+//! lost, switch to it to get them back:
+//!
+//! ```bash
+//! $ DISPLAY=:1 xrandr --listmonitors
+//! $ DISPLAY=:1 xrandr --setmonitor left 800/0x600/0+0+0 OUTPUT
+//! $ DISPLAY=:1 xrandr --setmonitor right 800/0x600/0+800+0 none
+//! $ DISPLAY=:1 xrandr --delmonitor right
+//! ```
+//!
+//! The screens the window manager uses are logged as `update_screen_geometries` in
+//! `cplwm.log`.
+//!
+//! The script `test_screen_changes.sh` in the `runner` folder does this in
+//! Xvfb: it unplugs and plugs back in a monitor and checks that the
+//! workspaces, their windows and the place of a floating window survive.
+//!
+//! ```bash
+//! $ ./test_screen_changes.sh
+//! ```
+//!
 //! ## X server on another virtual console
 //!
 //! An alternative way to run another X server in parallel is the following.
@@ -169,6 +224,10 @@
 //! * You can use the [`spawn`](fn.spawn.html) function to launch new
 //!   applications.
 //!
+//! * Panels and task bars, e.g. polybar or tint2, are not managed: they stay
+//!   where they are, on top of the other windows, which are kept out of the
+//!   space the panel reserves.
+//!
 //! [`main`]: fn.main.html
 #![deny(missing_docs)]
 
@@ -188,11 +247,15 @@ use std::fs::File;
 use std::process;
 
 use cplwm_api::wm::*;
-use cplwm_api::types::{PrevOrNext, WorkspaceIndex};
+use cplwm_api::types::{Direction, FloatOrTile, Geometry, LayoutTransform, MasterRatio, PrevOrNext,
+                       ScreenIndex, TileWeight};
 
-use cplwm_x11::{X11Backend, X11Config, X11Error, X11Result};
+use cplwm_x11::{Rule, Scratchpad, Screens, TabStrip, Urgency, Workspaces, X11Backend, X11Config,
+                X11Error, X11Result};
 
-// Import the `WMName` of the window manager you want to run
+// Import the `WMName` of the window manager you want to run. The backend
+// needs `FloatSupport`, `FullscreenSupport` and `MinimiseSupport`, the key
+// bindings and the extensions chosen in `main` need the traits they use.
 use cplwm_assignment::g_multiple_workspaces::WMName;
 
 /// The name of the window manager you want to run.
@@ -225,23 +288,115 @@ pub fn spawn(command: &str) -> X11Result<()> {
     run(process::Command::new("sh").arg("-c").arg(command).spawn())
 }
 
-/// Move the focused window to the given workspace.
+/// How much the master ratio grows or shrinks with each key press.
+const MASTER_RATIO_STEP: MasterRatio = 0.05;
+
+/// Grow (positive `step`) or shrink (negative `step`) the master tile.
+///
+/// The window manager clamps the resulting ratio.
+fn change_master_ratio(backend: &mut X11Backend<WM>, step: MasterRatio) -> X11Result<()> {
+    let ratio = backend.get_wm().get_master_ratio();
+    backend.get_wm_mut().set_master_ratio(ratio + step);
+    Ok(())
+}
+
+/// How much the weight of a tile grows or shrinks with each key press.
+const TILE_WEIGHT_STEP: TileWeight = 0.25;
+
+/// Grow (positive `step`) or shrink (negative `step`) the focused tile
+/// within its column.
+///
+/// The window manager clamps the resulting weight.
+fn change_tile_weight(backend: &mut X11Backend<WM>, step: TileWeight) -> X11Result<()> {
+    if let Some(w) = backend.get_wm().get_focused_window() {
+        let weight = try!(backend.get_wm().get_tile_weight(w));
+        try!(backend.get_wm_mut().set_tile_weight(w, weight + step));
+    }
+    Ok(())
+}
+
+/// Focus the next or previous window in the focus history, like Alt-Tab.
 ///
-/// Do nothing when no window is focused or when the index is that of the
-/// currently active workspace.
+/// While Alt is held, the focus history is frozen, so every press of Tab
+/// focuses a less recently focused window. When Alt is released, the focused
+/// window becomes the most recently focused one.
+fn cycle_focus_history(backend: &mut X11Backend<WM>, dir: PrevOrNext) -> X11Result<()> {
+    let holding = backend.is_holding_modifier();
+    if !holding {
+        backend.get_wm_mut().set_focus_history_frozen(true);
+    }
+    backend.get_wm_mut().cycle_focus_history(dir);
+    if !holding {
+        let unfreeze = |backend: &mut X11Backend<WM>| {
+            backend.get_wm_mut().set_focus_history_frozen(false);
+            Ok(())
+        };
+        try!(backend.hold_modifier(x11_dl::xlib::Mod1Mask, Box::new(unfreeze)));
+    }
+    Ok(())
+}
+
+/// Whether switching to the current workspace goes back to the previous one,
+/// like `workspace_auto_back_and_forth` in i3.
+const AUTO_BACK_AND_FORTH: bool = true;
+
+/// Switch to the workspace with the given name.
+///
+/// The workspace is created when it doesn't exist yet. When it is the current
+/// workspace, switch back to the previous one instead, see
+/// `AUTO_BACK_AND_FORTH`.
+fn switch_to_workspace(backend: &mut X11Backend<WM>, name: &str) -> X11Result<()> {
+    let wm = backend.get_wm_mut();
+    let index = try!(wm.get_or_create_workspace(name));
+    if AUTO_BACK_AND_FORTH && index == wm.get_current_workspace_index() {
+        return run(wm.switch_to_previous_workspace());
+    }
+    run(wm.switch_workspace(index))
+}
+
+/// Move the focused window to the workspace with the given name.
+///
+/// The workspace is created when it doesn't exist yet, it isn't deleted
+/// before the window is on it, see `set_delete_empty_workspaces`. When
+/// `follow` is `true`, switch to that workspace as well.
 fn move_focused_window_to_workspace(backend: &mut X11Backend<WM>,
-                                   index: WorkspaceIndex)
-                                   -> X11Result<()> {
-   if let Some(w) = backend.get_wm().get_focused_window() {
-       if index != backend.get_wm().get_current_workspace_index() {
-           let wm = backend.get_wm_mut();
-           let window_with_info = try!(wm.get_window_info(w));
-           try!(wm.remove_window(w));
-           try!(wm.switch_workspace(index));
-           try!(wm.add_window(window_with_info));
-       }
-   }
-   Ok(())
+                                    name: &str,
+                                    follow: bool)
+                                    -> X11Result<()> {
+    if let Some(w) = backend.get_wm().get_focused_window() {
+        let wm = backend.get_wm_mut();
+        let index = try!(wm.get_or_create_workspace(name));
+        try!(wm.move_window_to_workspace(w, index, follow));
+    }
+    Ok(())
+}
+
+/// Make the screen (monitor) with the given index the current one.
+///
+/// Does nothing when there is no such screen, e.g. when there is only one
+/// monitor.
+fn focus_screen(backend: &mut X11Backend<WM>, screen: ScreenIndex) -> X11Result<()> {
+    if screen < backend.get_wm().get_screens().len() {
+        try!(backend.get_wm_mut().focus_screen(screen));
+    }
+    Ok(())
+}
+
+/// Move the focused window to the screen (monitor) with the given index.
+///
+/// When `follow` is `true`, that screen becomes the current one. Does nothing
+/// when there is no such screen.
+fn move_focused_window_to_screen(backend: &mut X11Backend<WM>,
+                                 screen: ScreenIndex,
+                                 follow: bool)
+                                 -> X11Result<()> {
+    if screen >= backend.get_wm().get_screens().len() {
+        return Ok(());
+    }
+    if let Some(w) = backend.get_wm().get_focused_window() {
+        try!(backend.get_wm_mut().move_window_to_screen(w, screen, follow));
+    }
+    Ok(())
 }
 
 
@@ -285,13 +440,27 @@ pub fn main() {
             }
             Ok(())
         },
+        // Focus the previously focused window, keep Alt pressed to go further back
+        (Alt - XK_Tab) => |backend| cycle_focus_history(backend, PrevOrNext::Next),
+        (Alt - Shift - XK_Tab) => |backend| cycle_focus_history(backend, PrevOrNext::Prev),
+        // Show or hide the scratchpad, start its terminal the first time
+        (Super - XK_s) => |backend| {
+            if backend.get_wm().get_scratchpad_window().is_some() {
+                backend.get_wm_mut().toggle_scratchpad();
+                Ok(())
+            } else {
+                spawn("xterm -name scratchpad")
+            }
+        },
+        // Focus the window that demands attention, also on another workspace
+        (Super - XK_a) => |backend| run(backend.get_wm_mut().focus_urgent_window()),
         // Focus the next window
-        (Alt - XK_Tab) => |backend| {
+        (Super - XK_Tab) => |backend| {
             backend.get_wm_mut().cycle_focus(PrevOrNext::Next);
             Ok(())
         },
         // Focus the previous window
-        (Alt - Shift - XK_Tab) => |backend| {
+        (Super - Shift - XK_Tab) => |backend| {
             backend.get_wm_mut().cycle_focus(PrevOrNext::Prev);
             Ok(())
         },
@@ -312,6 +481,58 @@ pub fn main() {
             }
             Ok(())
         },
+        // Switch to the next layout
+        (Super - XK_space) => |backend| {
+            backend.get_wm_mut().cycle_layout(PrevOrNext::Next);
+            Ok(())
+        },
+        // Switch to the previous layout
+        (Super - Shift - XK_space) => |backend| {
+            backend.get_wm_mut().cycle_layout(PrevOrNext::Prev);
+            Ok(())
+        },
+        // Grow the master tile
+        (Super - XK_l) => |backend| change_master_ratio(backend, MASTER_RATIO_STEP),
+        // Shrink the master tile
+        (Super - XK_h) => |backend| change_master_ratio(backend, -MASTER_RATIO_STEP),
+        // Grow the focused tile in its column
+        (Super - Shift - XK_l) => |backend| change_tile_weight(backend, TILE_WEIGHT_STEP),
+        // Shrink the focused tile in its column
+        (Super - Shift - XK_h) => |backend| change_tile_weight(backend, -TILE_WEIGHT_STEP),
+        // Give all tiles the same size again
+        (Super - Shift - XK_o) => |backend| {
+            backend.get_wm_mut().reset_tile_weights();
+            Ok(())
+        },
+        // Rotate the layout clockwise: master on top, stack below
+        (Super - XK_o) => |backend| {
+            backend.get_wm_mut().toggle_layout_transform(LayoutTransform::Rotate);
+            Ok(())
+        },
+        // Mirror the layout horizontally
+        (Super - XK_x) => |backend| {
+            backend.get_wm_mut().toggle_layout_transform(LayoutTransform::MirrorHorizontally);
+            Ok(())
+        },
+        // Mirror the layout vertically
+        (Super - Shift - XK_x) => |backend| {
+            backend.get_wm_mut().toggle_layout_transform(LayoutTransform::MirrorVertically);
+            Ok(())
+        },
+        // Add a window to the master column
+        (Super - XK_comma) => |backend| {
+            let nmaster = backend.get_wm().get_nmaster();
+            backend.get_wm_mut().set_nmaster(nmaster + 1);
+            Ok(())
+        },
+        // Remove a window from the master column
+        (Super - XK_period) => |backend| {
+            let nmaster = backend.get_wm().get_nmaster();
+            if nmaster > 0 {
+                backend.get_wm_mut().set_nmaster(nmaster - 1);
+            }
+            Ok(())
+        },
         // Minimise the focused window
         (Super - XK_u) => |backend| {
             if let Some(w) = backend.get_wm().get_focused_window() {
@@ -333,29 +554,166 @@ pub fn main() {
             }
             Ok(())
         },
-        //// Increase the gap
-        //(Super - XK_g) => |backend| {
-        //    let current_gap = backend.get_wm().get_gap();
-        //    backend.get_wm_mut().set_gap(current_gap + 1);
+        // Increase the gap
+        (Super - XK_g) => |backend| {
+            let current_gap = backend.get_wm().get_gap();
+            backend.get_wm_mut().set_gap(current_gap + 1);
+            Ok(())
+        },
+        // Decrease the gap
+        (Super - Shift - XK_g) => |backend| {
+            let current_gap = backend.get_wm().get_gap();
+            if current_gap > 0 {
+                backend.get_wm_mut().set_gap(current_gap - 1);
+            }
+            Ok(())
+        },
+        // Focus the window on the left, right, above or below the focused one
+        (Super - XK_Left) => |backend| {
+            backend.get_wm_mut().focus_direction(Direction::Left);
+            Ok(())
+        },
+        (Super - XK_Right) => |backend| {
+            backend.get_wm_mut().focus_direction(Direction::Right);
+            Ok(())
+        },
+        (Super - XK_Up) => |backend| {
+            backend.get_wm_mut().focus_direction(Direction::Up);
+            Ok(())
+        },
+        (Super - XK_Down) => |backend| {
+            backend.get_wm_mut().focus_direction(Direction::Down);
+            Ok(())
+        },
+        // Swap the focused window with the one on the left, right, above or below it
+        (Super - Shift - XK_Left) => |backend| {
+            backend.get_wm_mut().swap_direction(Direction::Left);
+            Ok(())
+        },
+        (Super - Shift - XK_Right) => |backend| {
+            backend.get_wm_mut().swap_direction(Direction::Right);
+            Ok(())
+        },
+        (Super - Shift - XK_Up) => |backend| {
+            backend.get_wm_mut().swap_direction(Direction::Up);
+            Ok(())
+        },
+        (Super - Shift - XK_Down) => |backend| {
+            backend.get_wm_mut().swap_direction(Direction::Down);
+            Ok(())
+        },
+        // Toggle smart gaps
+        (Super - Control - XK_g) => |backend| {
+            let smart_gaps = backend.get_wm().get_smart_gaps();
+            backend.get_wm_mut().set_smart_gaps(!smart_gaps);
+            Ok(())
+        },
+        // Toggle smart borders
+        (Super - Control - XK_b) => |backend| {
+            let smart_borders = backend.get_wm().get_smart_borders();
+            backend.get_wm_mut().set_smart_borders(!smart_borders);
+            Ok(())
+        },
+        // The bindings below need `WM` to be the `BspWM` of `h_bsp_wm` and
+        // `Orientation` to be imported from it. The backend can run it, but
+        // the bindings and extensions of the traits it doesn't implement,
+        // e.g. `MultiWorkspaceSupport`, must be commented out.
+        //// Place the next window on the left of the focused one
+        //(Super - Control - XK_h) => |backend| {
+        //    backend.get_wm_mut().preselect(Direction::Left);
         //    Ok(())
         //},
-        // Decrease the gap
-        //(Super - Shift - XK_g) => |backend| {
-        //    let current_gap = backend.get_wm().get_gap();
-        //    if current_gap > 0 {
-        //        backend.get_wm_mut().set_gap(current_gap - 1);
+        //// Place the next window below the focused one
+        //(Super - Control - XK_j) => |backend| {
+        //    backend.get_wm_mut().preselect(Direction::Down);
+        //    Ok(())
+        //},
+        //// Place the next window above the focused one
+        //(Super - Control - XK_k) => |backend| {
+        //    backend.get_wm_mut().preselect(Direction::Up);
+        //    Ok(())
+        //},
+        //// Place the next window on the right of the focused one
+        //(Super - Control - XK_l) => |backend| {
+        //    backend.get_wm_mut().preselect(Direction::Right);
+        //    Ok(())
+        //},
+        //// Rotate the split of the focused window
+        //(Super - XK_r) => |backend| {
+        //    backend.get_wm_mut().rotate_split();
+        //    Ok(())
+        //},
+        //// Flip the split of the focused window
+        //(Super - XK_f) => |backend| {
+        //    backend.get_wm_mut().flip_split(Orientation::Horizontal);
+        //    Ok(())
+        //},
+        //(Super - Shift - XK_f) => |backend| {
+        //    backend.get_wm_mut().flip_split(Orientation::Vertical);
+        //    Ok(())
+        //},
+        //// Grow or shrink the first part of the split of the focused window
+        //(Super - XK_bracketright) => |backend| {
+        //    if let Some(ratio) = backend.get_wm().get_split_ratio() {
+        //        backend.get_wm_mut().set_split_ratio(ratio + MASTER_RATIO_STEP);
+        //    }
+        //    Ok(())
+        //},
+        //(Super - XK_bracketleft) => |backend| {
+        //    if let Some(ratio) = backend.get_wm().get_split_ratio() {
+        //        backend.get_wm_mut().set_split_ratio(ratio - MASTER_RATIO_STEP);
         //    }
         //    Ok(())
         //},
-        // We could use a loop for this
-       (Super - XK_1) => |backend| run(backend.get_wm_mut().switch_workspace(0)),
-       (Super - XK_2) => |backend| run(backend.get_wm_mut().switch_workspace(1)),
-       (Super - XK_3) => |backend| run(backend.get_wm_mut().switch_workspace(2)),
-       (Super - XK_4) => |backend| run(backend.get_wm_mut().switch_workspace(3)),
-       (Super - Shift - XK_1) => |backend| move_focused_window_to_workspace(backend, 0),
-       (Super - Shift - XK_2) => |backend| move_focused_window_to_workspace(backend, 1),
-       (Super - Shift - XK_3) => |backend| move_focused_window_to_workspace(backend, 2),
-       (Super - Shift - XK_4) => |backend| move_focused_window_to_workspace(backend, 3)
+        // Workspaces are bound by name, they are created when they are first
+        // used, and deleted again when they are left empty. The key of the
+        // current workspace goes back to the previous one.
+        (Super - XK_1) => |backend| switch_to_workspace(backend, "1"),
+        (Super - XK_2) => |backend| switch_to_workspace(backend, "2"),
+        (Super - XK_3) => |backend| switch_to_workspace(backend, "3"),
+        (Super - XK_4) => |backend| switch_to_workspace(backend, "4"),
+        (Super - XK_0) => |backend| switch_to_workspace(backend, "chat"),
+        // Go to the next or previous workspace
+        (Super - XK_Next) => |backend| {
+            run(backend.get_wm_mut().cycle_workspace(PrevOrNext::Next, false))
+        },
+        (Super - XK_Prior) => |backend| {
+            run(backend.get_wm_mut().cycle_workspace(PrevOrNext::Prev, false))
+        },
+        // Go to the next or previous workspace that has windows
+        (Super - Shift - XK_Next) => |backend| {
+            run(backend.get_wm_mut().cycle_workspace(PrevOrNext::Next, true))
+        },
+        (Super - Shift - XK_Prior) => |backend| {
+            run(backend.get_wm_mut().cycle_workspace(PrevOrNext::Prev, true))
+        },
+        // Go back to the previous workspace
+        (Super - XK_grave) => |backend| run(backend.get_wm_mut().switch_to_previous_workspace()),
+        // Move the focused window, staying on the current workspace
+        (Super - Shift - XK_1) => |backend| move_focused_window_to_workspace(backend, "1", false),
+        (Super - Shift - XK_2) => |backend| move_focused_window_to_workspace(backend, "2", false),
+        (Super - Shift - XK_3) => |backend| move_focused_window_to_workspace(backend, "3", false),
+        (Super - Shift - XK_4) => |backend| move_focused_window_to_workspace(backend, "4", false),
+        (Super - Shift - XK_0) => |backend| {
+            move_focused_window_to_workspace(backend, "chat", false)
+        },
+        // Move the focused window and follow it
+        (Super - Control - XK_1) => |backend| move_focused_window_to_workspace(backend, "1", true),
+        (Super - Control - XK_2) => |backend| move_focused_window_to_workspace(backend, "2", true),
+        (Super - Control - XK_3) => |backend| move_focused_window_to_workspace(backend, "3", true),
+        (Super - Control - XK_4) => |backend| move_focused_window_to_workspace(backend, "4", true),
+        (Super - Control - XK_0) => |backend| {
+            move_focused_window_to_workspace(backend, "chat", true)
+        },
+        // Go to the first or second screen (monitor)
+        (Super - XK_w) => |backend| focus_screen(backend, 0),
+        (Super - XK_e) => |backend| focus_screen(backend, 1),
+        // Move the focused window to another screen, with or without
+        // following it
+        (Super - Shift - XK_w) => |backend| move_focused_window_to_screen(backend, 0, false),
+        (Super - Shift - XK_e) => |backend| move_focused_window_to_screen(backend, 1, false),
+        (Super - Control - XK_w) => |backend| move_focused_window_to_screen(backend, 0, true),
+        (Super - Control - XK_e) => |backend| move_focused_window_to_screen(backend, 1, true)
     };
 
     config.button_bindings = button_bindings! { WM =>
@@ -365,7 +723,45 @@ pub fn main() {
         /* (Super - RMB) => |backend, ev| backend.mouse_resize_window(ev.subwindow) */
     };
 
-    X11Backend::start(WM::new, config).unwrap();
+    // Decide how some windows are placed when they appear. The last matching
+    // rule wins.
+    config.rules = vec![
+        // The clock floats in the top right corner
+        Rule {
+            class: Some("XClock"),
+            float_or_tile: Some(FloatOrTile::Float),
+            geometry: Some(Geometry {
+                x: 1000,
+                y: 20,
+                width: 164,
+                height: 164,
+            }),
+            ..Default::default()
+        },
+        // Chat windows open on the chat workspace without stealing the focus
+        Rule {
+            class: Some("Pidgin"),
+            workspace: Some("chat"),
+            skip_focus: Some(true),
+            ..Default::default()
+        },
+    ];
+
+    // The functionality that needs more than the basic traits. Remove the
+    // extensions the window manager can't support, e.g. `Screens` without
+    // `MultiScreenSupport`.
+    config.extensions = vec![Box::new(TabStrip),
+                             Box::new(Urgency),
+                             Box::new(Scratchpad::default()),
+                             Box::new(Workspaces::new()),
+                             Box::new(Screens::new())];
+
+    let make_wm = |screen| {
+        let mut wm = WM::new(screen);
+        wm.set_delete_empty_workspaces(true);
+        wm
+    };
+    X11Backend::start(make_wm, config).unwrap();
 
     info!("The window manager has stopped");
 }
@@ -0,0 +1,173 @@
+#!/bin/bash
+#
+# Check that the window manager follows monitors that are added and removed
+# with xrandr, without losing workspaces or windows.
+#
+# The window manager runs in a virtual framebuffer X server (Xvfb) of
+# 1600x600 that is split into two virtual monitors of 800x600, see
+# "Multiple monitors" in src/main.rs. The X server must support RandR 1.5
+# monitors (X.Org server 1.18 or later). Needed: Xvfb, xrandr, xprop,
+# xwininfo, xdotool and xterm.
+#
+# Usage, in the runner folder:
+#
+#     $ ./test_screen_changes.sh [DISPLAY]
+#
+# The display defaults to :99. The log of the window manager is written to
+# cplwm.log in the runner folder.
+
+set -eu
+
+DISPLAY=${1:-:99}
+export DISPLAY
+
+cd "$(dirname "$0")"
+
+fail() {
+    echo "FAIL: $*" >&2
+    exit 1
+}
+
+for tool in Xvfb xrandr xprop xwininfo xdotool xterm; do
+    command -v $tool > /dev/null || fail "$tool is not installed"
+done
+
+cargo build
+WM=../target/debug/cplwm-runner
+
+PIDS=()
+cleanup() {
+    for pid in "${PIDS[@]}"; do
+        kill "$pid" 2> /dev/null || true
+    done
+}
+trap cleanup EXIT
+
+# Run the given command until it succeeds, for at most 5 seconds
+wait_for() {
+    for _ in $(seq 50); do
+        if "$@" 2> /dev/null; then
+            return 0
+        fi
+        sleep 0.1
+    done
+    fail "timed out waiting for: $*"
+}
+
+# Print the managed windows
+client_list() {
+    xprop -root _NET_CLIENT_LIST | sed -e 's/^[^#]*# //' -e 's/, /\n/g'
+}
+
+# Print the name of the workspace of the given window
+workspace_of() {
+    local index
+    index=$(xprop -id "$1" _NET_WM_DESKTOP | awk '{print $3}')
+    xprop -root _NET_DESKTOP_NAMES | sed -e 's/^[^=]*= //' -e 's/"//g' -e 's/, /\n/g' |
+        sed -n "$((index + 1))p"
+}
+
+# Print the number of workspaces
+workspace_count() {
+    xprop -root _NET_NUMBER_OF_DESKTOPS | awk '{print $3}'
+}
+
+# Print the geometry of the given window as X Y WIDTH HEIGHT
+geometry_of() {
+    xwininfo -id "$1" | awk '/Absolute upper-left X/ {x = $NF}
+                            /Absolute upper-left Y/ {y = $NF}
+                            /Width/ {w = $NF}
+                            /Height/ {h = $NF}
+                            END {print x, y, w, h}'
+}
+
+is_viewable() {
+    xwininfo -id "$1" | grep -q IsViewable
+}
+
+is_active() {
+    xprop -root _NET_ACTIVE_WINDOW | grep -qw "$(printf '0x%x' "$1")"
+}
+
+is_managed() {
+    client_list | grep -qw "$(printf '0x%x' "$1")"
+}
+
+workspace_is() {
+    [ "$(workspace_of "$1")" = "$2" ]
+}
+
+count_is() {
+    [ "$(workspace_count)" = "$1" ]
+}
+
+# Start an xterm with the given instance name and print its window. It exits
+# together with the X server.
+start_xterm() {
+    xterm -name "$1" -geometry 40x10+100+100 > /dev/null 2>&1 &
+    local window
+    window=$(xdotool search --sync --classname "$1" | head -n 1)
+    wait_for is_managed "$window"
+    echo "$window"
+}
+
+Xvfb "$DISPLAY" -screen 0 1600x600x24 &
+PIDS+=($!)
+wait_for xprop -root > /dev/null
+
+OUTPUT=$(xrandr --listmonitors | awk 'NR == 2 {print $NF}')
+xrandr --setmonitor left 800/0x600/0+0+0 "$OUTPUT"
+xrandr --setmonitor right 800/0x600/0+800+0 none
+
+"$WM" > /dev/null &
+PIDS+=($!)
+wait_for xprop -root _NET_NUMBER_OF_DESKTOPS
+# Every monitor shows a workspace
+wait_for count_is 2
+
+# A tile on workspace "1", on the left monitor
+TILE=$(start_xterm tile)
+
+# A floating window on workspace "2", on the right monitor
+FLOAT=$(start_xterm float)
+wait_for is_active "$FLOAT"
+xdotool key super+t
+xdotool key super+shift+e
+wait_for workspace_is "$FLOAT" 2
+read -r X Y WIDTH HEIGHT <<< "$(geometry_of "$FLOAT")"
+[ "$X" -ge 800 ] || fail "the floating window is not on the right monitor: $X"
+
+# A window on the hidden workspace "3"
+HIDDEN=$(start_xterm hidden)
+wait_for is_active "$HIDDEN"
+xdotool key super+shift+3
+wait_for workspace_is "$HIDDEN" 3
+wait_for count_is 3
+
+# Unplug the right monitor
+xrandr --delmonitor right
+wait_for eval '! is_viewable "$FLOAT"'
+for window in "$TILE" "$FLOAT" "$HIDDEN"; do
+    is_managed "$window" || fail "window $window is lost"
+done
+count_is 3 || fail "a workspace is lost: $(workspace_count) workspaces"
+workspace_is "$TILE" 1 || fail "the tile moved to $(workspace_of "$TILE")"
+workspace_is "$FLOAT" 2 || fail "the floating window moved to $(workspace_of "$FLOAT")"
+workspace_is "$HIDDEN" 3 || fail "the hidden window moved to $(workspace_of "$HIDDEN")"
+
+# The floating window keeps its place and size on the remaining monitor
+xdotool set_desktop "$(xprop -id "$FLOAT" _NET_WM_DESKTOP | awk '{print $3}')"
+wait_for is_viewable "$FLOAT"
+read -r X2 Y2 WIDTH2 HEIGHT2 <<< "$(geometry_of "$FLOAT")"
+[ "$X2 $Y2 $WIDTH2 $HEIGHT2" = "$((X - 800)) $Y $WIDTH $HEIGHT" ] ||
+    fail "the floating window changed from $X $Y $WIDTH $HEIGHT to $X2 $Y2 $WIDTH2 $HEIGHT2"
+
+# Plug it back in, it shows the first hidden workspace
+xrandr --setmonitor right 800/0x600/0+800+0 none
+wait_for is_viewable "$TILE"
+read -r X Y _ _ <<< "$(geometry_of "$TILE")"
+[ "$X" -ge 800 ] || fail "the tile is not on the right monitor: $X"
+is_viewable "$FLOAT" || fail "the floating window is no longer shown"
+count_is 3 || fail "a workspace is lost: $(workspace_count) workspaces"
+
+echo "OK"
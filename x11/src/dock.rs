@@ -0,0 +1,154 @@
+//! Dock methods.
+//!
+//! A dock is a panel or task bar, e.g. polybar or tint2, a window with the
+//! window type `_NET_WM_WINDOW_TYPE_DOCK`. Docks are not managed by the
+//! window manager: they keep the place they chose themselves and are kept on
+//! top of the other windows, except the fullscreen ones. A dock can reserve
+//! space along an edge of the screen with a strut, the screens the window
+//! manager gets are made smaller so the windows stay out of that space.
+//! Fullscreen windows still cover the whole screen.
+
+use std::os::raw::{c_int, c_uint};
+
+use cplwm_api::types::{Geometry, Window};
+use cplwm_api::wm::WindowManager;
+
+use super::*;
+
+use x11_dl::xlib;
+
+/// The event mask for docks.
+///
+/// We only need to know when a dock disappears or changes its strut.
+const DOCK_MASK: XEventMask = xlib::StructureNotifyMask | xlib::PropertyChangeMask;
+
+/// Dock methods.
+impl<WM> X11Backend<WM>
+    where WM: WindowManager
+{
+    /// Check whether the given window is a dock.
+    ///
+    /// This is done by checking whether `_NET_WM_WINDOW_TYPE_DOCK` is in the
+    /// [`_NET_WM_WINDOW_TYPE`] property of the window.
+    ///
+    /// [`_NET_WM_WINDOW_TYPE`]: https://developer.gnome.org/wm-spec/#idm140200472629520
+    pub fn is_dock(&self, window: Window) -> bool {
+        let net_wm_window_type_dock = self.get_atom("_NET_WM_WINDOW_TYPE_DOCK");
+        let net_wm_window_type = self.get_atom("_NET_WM_WINDOW_TYPE");
+        let window_type_props = self.get_window_property32(window, net_wm_window_type)
+            .unwrap_or_default();
+        window_type_props.contains(&(net_wm_window_type_dock as c_int))
+    }
+
+    /// Show the given dock on top of the other windows and keep the windows
+    /// out of the space it reserves.
+    pub fn add_dock(&mut self, window: Window) -> X11Result<()> {
+        trace!("add_dock: {}", window);
+        unsafe {
+            (self.xlib.XSelectInput)(self.display, window, DOCK_MASK);
+            (self.xlib.XMapWindow)(self.display, window);
+        }
+        if !self.docks.contains(&window) {
+            self.docks.push(window);
+        }
+        self.raise_docks();
+        self.update_strut(window)
+    }
+
+    /// Forget the given dock when it was unmapped or destroyed, the space it
+    /// reserved is given back to the windows.
+    pub fn remove_dock(&mut self, window: Window) -> X11Result<()> {
+        trace!("remove_dock: {}", window);
+        self.docks.retain(|dock| *dock != window);
+        self.struts.remove(&window);
+        self.update_screens()
+    }
+
+    /// Read the strut of the given dock again, e.g. when it was added or
+    /// changed its strut, and keep the windows out of the space it reserves.
+    pub fn update_strut(&mut self, window: Window) -> X11Result<()> {
+        match self.get_strut(window) {
+            Some(strut) => self.struts.insert(window, strut),
+            None => self.struts.remove(&window),
+        };
+        self.update_screens()
+    }
+
+    /// Raise the docks above the other windows, but below the fullscreen
+    /// windows of the last applied window layout.
+    pub fn raise_docks(&self) {
+        for window in self.docks.iter().chain(&self.fullscreen) {
+            unsafe {
+                (self.xlib.XRaiseWindow)(self.display, *window);
+            }
+        }
+    }
+
+    /// Move and/or resize a dock as it requested.
+    ///
+    /// Docks aren't managed, so the request is simply granted.
+    pub fn configure_dock(&self, xev: &xlib::XConfigureRequestEvent) {
+        let mut changes = xlib::XWindowChanges {
+            x: xev.x,
+            y: xev.y,
+            width: xev.width,
+            height: xev.height,
+            border_width: xev.border_width,
+            sibling: xev.above,
+            stack_mode: xev.detail,
+        };
+        unsafe {
+            (self.xlib.XConfigureWindow)(self.display,
+                                         xev.window,
+                                         xev.value_mask as c_uint,
+                                         &mut changes);
+        }
+    }
+
+    /// Return the strut of the given dock as the 12 values of
+    /// [`_NET_WM_STRUT_PARTIAL`], see `reserve_strut`, or `None` when it
+    /// doesn't reserve any space.
+    ///
+    /// A dock that only sets the older [`_NET_WM_STRUT`] reserves the whole
+    /// length of the edges.
+    ///
+    /// [`_NET_WM_STRUT_PARTIAL`]: https://developer.gnome.org/wm-spec/
+    /// [`_NET_WM_STRUT`]: https://developer.gnome.org/wm-spec/
+    pub fn get_strut(&self, window: Window) -> Option<Vec<c_int>> {
+        let net_wm_strut_partial = self.get_atom("_NET_WM_STRUT_PARTIAL");
+        match self.get_window_property32(window, net_wm_strut_partial) {
+            Some(ref strut) if strut.len() == 12 => return Some(strut.clone()),
+            _ => (),
+        }
+        let net_wm_strut = self.get_atom("_NET_WM_STRUT");
+        match self.get_window_property32(window, net_wm_strut) {
+            Some(mut strut) => {
+                if strut.len() != 4 {
+                    return None;
+                }
+                let screen = self.get_screen();
+                let width = screen.width as c_int;
+                let height = screen.height as c_int;
+                strut.extend(&[0, height - 1, 0, height - 1, 0, width - 1, 0, width - 1]);
+                Some(strut)
+            }
+            None => None,
+        }
+    }
+
+    /// Return the given screen geometry without the space reserved by the
+    /// docks.
+    pub fn get_work_area(&self, screen: Geometry) -> Geometry {
+        let root = self.get_screen();
+        self.struts
+            .values()
+            .fold(screen, |area, strut| reserve_strut(area, strut, root))
+    }
+
+    /// Check whether the given property is one with which a dock reserves
+    /// space.
+    pub fn is_strut_property(&self, property: xlib::Atom) -> bool {
+        property == self.get_atom("_NET_WM_STRUT_PARTIAL") ||
+        property == self.get_atom("_NET_WM_STRUT")
+    }
+}
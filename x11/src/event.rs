@@ -17,8 +17,9 @@ impl<WM> X11Backend<WM>
 {
     /// Run the main event loop.
     ///
-    /// Calls [`handler`] for each event. When the handler modified the window
-    /// manager, [`apply_window_layout`] is called to apply the changes.
+    /// Calls [`handler`] and the `handle_event` method of the extensions for
+    /// each event. When they modified the window manager,
+    /// [`apply_window_layout`] is called to apply the changes.
     ///
     /// [`handler`]: struct.X11Backend.html#method.handler
     /// [`apply_window_layout`]: struct.X11Backend.html#method.apply_window_layout
@@ -32,7 +33,7 @@ impl<WM> X11Backend<WM>
             // focus_window.
             self.current_event = Some(event);
             // Remember the previous window layout
-            let prev_window_layout = self.get_wm().get_window_layout();
+            let prev_window_layout = self.get_root_window_layout();
             self.wm_modified = false;
             // Run the handler. When it returns an error, the window manager
             // stops. In general this is very undesirable for a window
@@ -41,10 +42,15 @@ impl<WM> X11Backend<WM>
             // mostly do. So instead of swallowing errors, we crash, which
             // leads to quicker discovery of bugs.
             try!(self.handler(&event, &config));
+            // Let the extensions handle it as well, e.g. to redraw the tab
+            // strip.
+            for extension in self.get_extensions().iter() {
+                try!(extension.handle_event(self, &event));
+            }
             // Only if the window manager was modified, i.e. a mutable borrow
             // occurred using `get_wm_mut`, do we have to apply the changes.
             if self.wm_modified {
-                let new_window_layout = self.get_wm().get_window_layout();
+                let new_window_layout = self.get_root_window_layout();
                 self.apply_window_layout(&prev_window_layout, &new_window_layout);
             }
         }
@@ -74,6 +80,18 @@ impl<WM> X11Backend<WM>
                     try!(command(self));
                 }
             }
+            // A key was released. When the user holds a modifier, see
+            // `hold_modifier`, and releases it, stop holding it.
+            xlib::KeyRelease => {
+                let xev: xlib::XKeyEvent = From::from(event);
+                let released = match self.holding {
+                    Some((_, ref keycodes, _)) => keycodes.contains(&(xev.keycode as u8)),
+                    None => false,
+                };
+                if released {
+                    try!(self.release_modifier());
+                }
+            }
             // A mouse button was clicked. If the root window was clicked and
             // a command is bound to the mouse button, execute it. Otherwise,
             // it is a click to focus another window.
@@ -103,7 +121,9 @@ impl<WM> X11Backend<WM>
                         } else {
                             Some(xev.window)
                         };
-                        if self.get_wm().get_focused_window() != to_focus {
+                        // Docks can't be focused
+                        let is_dock = to_focus.map_or(false, |w| self.docks.contains(&w));
+                        if !is_dock && self.get_wm().get_focused_window() != to_focus {
                             try!(self.get_wm_mut().focus_window(to_focus));
                         }
                     }
@@ -162,8 +182,12 @@ impl<WM> X11Backend<WM>
                 };
                 // Only add the window if it isn't already and if it
                 // didn't indicate that it should not be managed (e.g.
-                // popups or fullscreen windows).
-                if !self.get_wm().is_managed(xev.window) && window_attrs.override_redirect == 0 {
+                // popups or fullscreen windows). Docks, e.g. panels, aren't
+                // managed either, they are only shown and kept on top.
+                if window_attrs.override_redirect == 0 && self.is_dock(xev.window) {
+                    try!(self.add_dock(xev.window));
+                } else if !self.get_wm().is_managed(xev.window) &&
+                          window_attrs.override_redirect == 0 {
                     let mut geometry = Geometry {
                         x: window_attrs.x,
                         y: window_attrs.y,
@@ -173,16 +197,58 @@ impl<WM> X11Backend<WM>
                     if let Some(hints) = self.get_wm_normal_hints(xev.window) {
                         respect_hints(&mut geometry, &hints);
                     }
+                    // The window manager wants a position relative to the
+                    // current screen, or to the work area it got. A window
+                    // positioned on another screen is centred on the current
+                    // one instead.
+                    let extensions = self.get_extensions();
+                    let (x, y) = extensions.iter()
+                        .filter_map(|extension| extension.get_current_screen(self))
+                        .next()
+                        .map_or(self.origin, |screen| (screen.x, screen.y));
+                    geometry.x -= x;
+                    geometry.y -= y;
                     let screen = self.get_wm().get_screen();
+                    if geometry.x < 0 || geometry.y < 0 || geometry.x >= screen.width as c_int ||
+                       geometry.y >= screen.height as c_int {
+                        geometry.x = 0;
+                        geometry.y = 0;
+                    }
                     center_geometry(&mut geometry, &screen);
-                    let float_or_tile = self.wants_to_float_or_tile(xev.window);
-                    let fullscreen = self.wants_to_be_fullscreen(xev.window);
+                    // The rules matching the window override what the
+                    // window itself wants.
+                    let rules = self.matching_rules(xev.window, &config.rules);
+                    if let Some(rule_geometry) = rule_action(&rules, |rule| rule.geometry) {
+                        geometry = rule_geometry;
+                    }
+                    let float_or_tile = rule_action(&rules, |rule| rule.float_or_tile)
+                        .unwrap_or_else(|| self.wants_to_float_or_tile(xev.window));
+                    let fullscreen = rule_action(&rules, |rule| rule.fullscreen)
+                        .unwrap_or_else(|| self.wants_to_be_fullscreen(xev.window));
+                    let window_with_info =
+                        WindowWithInfo::new(xev.window, geometry, float_or_tile, fullscreen);
+                    let focused_window = self.get_wm().get_focused_window();
                     self.add_window(xev.window);
-                    try!(self.get_wm_mut()
-                        .add_window(WindowWithInfo::new(xev.window,
-                                                        geometry,
-                                                        float_or_tile,
-                                                        fullscreen)));
+                    // An extension can add the window elsewhere, e.g. to the
+                    // workspace a rule names.
+                    let mut placed = false;
+                    for extension in extensions.iter() {
+                        if try!(extension.place_window(self, &window_with_info, &rules)) {
+                            placed = true;
+                            break;
+                        }
+                    }
+                    if !placed {
+                        try!(self.get_wm_mut().add_window(window_with_info));
+                    }
+                    // Give the focus back to the previously focused window
+                    if rule_action(&rules, |rule| rule.skip_focus) == Some(true) &&
+                       self.get_wm().get_focused_window() == Some(xev.window) {
+                        try!(self.get_wm_mut().focus_window(focused_window));
+                    }
+                    for extension in extensions.iter() {
+                        try!(extension.window_mapped(self, xev.window));
+                    }
                 }
             }
             // The keyboard mapping was changed, regrab the keys.
@@ -201,6 +267,8 @@ impl<WM> X11Backend<WM>
                 if self.get_wm().is_managed(xev.window) {
                     try!(self.get_wm_mut().remove_window(xev.window));
                     self.remove_window(xev.window);
+                } else if self.docks.contains(&xev.window) {
+                    try!(self.remove_dock(xev.window));
                 }
             }
             // A window is unmapped, i.e. removed from the window manager.
@@ -215,6 +283,8 @@ impl<WM> X11Backend<WM>
                         try!(self.get_wm_mut().remove_window(xev.window));
                         self.remove_window(xev.window);
                     }
+                } else if self.docks.contains(&xev.window) {
+                    try!(self.remove_dock(xev.window));
                 }
                 // Be a good parent and reap your zombie children. Children,
                 // i.e. processes of windows spawned by the window manager
@@ -235,11 +305,20 @@ impl<WM> X11Backend<WM>
             // configuration.
             xlib::ConfigureRequest => {
                 let xev: xlib::XConfigureRequestEvent = From::from(event);
+                // Docks place themselves.
+                if self.docks.contains(&xev.window) {
+                    self.configure_dock(&xev);
+                    return Ok(());
+                }
                 // We can't configure a window we don't manage.
                 if !self.get_wm().is_managed(xev.window) {
                     return Ok(());
                 }
+                // The width and height of this geometry exclude the border.
                 let geometry = try!(self.get_window_geometry(xev.window));
+                // The border is ours to choose, not the client's.
+                let window_layout = self.get_wm().get_window_layout();
+                let border_width = self.get_layout_border_width(&window_layout, xev.window);
                 if self.get_wm().is_floating(xev.window) {
                     let mask = xev.value_mask as c_ushort;
                     let new_geometry = Geometry {
@@ -257,17 +336,21 @@ impl<WM> X11Backend<WM>
                         // gets subtracted in set_window_geometry. If we don't
                         // do this, some windows will keep sending these
                         // requests and slowly shrink.
-                        width: if mask & xlib::CWWidth != 0 {
-                            xev.width as c_uint + 2 * WINDOW_BORDER_WIDTH
-                        } else {
-                            geometry.width
-                        },
-                        height: if mask & xlib::CWHeight != 0 {
-                            xev.height as c_uint + 2 * WINDOW_BORDER_WIDTH
-                        } else {
-                            geometry.height
-                        },
+                        width: 2 * border_width +
+                               if mask & xlib::CWWidth != 0 {
+                                   xev.width as c_uint
+                               } else {
+                                   geometry.width
+                               },
+                        height: 2 * border_width +
+                                if mask & xlib::CWHeight != 0 {
+                                    xev.height as c_uint
+                                } else {
+                                    geometry.height
+                                },
                     };
+                    // The request uses the coordinates of the root window
+                    let new_geometry = self.to_screen_coordinates(xev.window, new_geometry);
                     try!(self.get_wm_mut().set_window_geometry(xev.window, new_geometry));
                 } else {
                     // Just send the event
@@ -280,9 +363,9 @@ impl<WM> X11Backend<WM>
                             window: xev.window,
                             x: geometry.x,
                             y: geometry.y,
-                            width: (geometry.width - 2 * WINDOW_BORDER_WIDTH) as c_int,
-                            height: (geometry.height - 2 * WINDOW_BORDER_WIDTH) as c_int,
-                            border_width: WINDOW_BORDER_WIDTH as c_int,
+                            width: geometry.width as c_int,
+                            height: geometry.height as c_int,
+                            border_width: border_width as c_int,
                             above: 0,
                             override_redirect: xlib::False,
                         }
@@ -298,13 +381,20 @@ impl<WM> X11Backend<WM>
                 unsafe { (self.xlib.XSync)(self.display, xlib::False) };
             }
             // When the root window is 'reconfigured', the display settings
-            // have changed.
+            // have changed, e.g. a monitor was added.
             xlib::ConfigureNotify => {
                 let xev: xlib::XConfigureEvent = From::from(event);
                 if xev.window == self.root_window {
-                    let screen = self.get_screen();
-                    // Update the window manager with the changed screen.
-                    self.get_wm_mut().resize_screen(screen);
+                    // Update the window manager with the changed screens.
+                    try!(self.update_screens());
+                }
+            }
+            // A property of a window changed. When a dock changes its strut,
+            // the screens change.
+            xlib::PropertyNotify => {
+                let xev: xlib::XPropertyEvent = From::from(event);
+                if self.docks.contains(&xev.window) && self.is_strut_property(xev.atom) {
+                    try!(self.update_strut(xev.window));
                 }
             }
             // Messages sent by client, i.e. applications
@@ -313,6 +403,18 @@ impl<WM> X11Backend<WM>
                 // Delegate to the EWMH handler
                 try!(self.handle_ewmh_client_message(xev));
             }
+            // A monitor was plugged in, unplugged, rotated, ... Let Xlib
+            // update the size of the screen it remembers before asking for
+            // the new screens.
+            _ if self.is_screen_change_event(event) => {
+                if let Some(ref xrandr) = self.xrandr {
+                    let mut event = *event;
+                    unsafe {
+                        (xrandr.XRRUpdateConfiguration)(&mut event);
+                    }
+                }
+                try!(self.update_screens());
+            }
             _ => (),
         }
         Ok(())
@@ -4,7 +4,8 @@
 //! communicates with the running applications. For example, it provides ways
 //! for applications to indicate that they want to be hidden or displayed
 //! fullscreen. It also lets panels or task bars know which windows are
-//! visible on the current workspace and which window is focused.
+//! visible on the current workspace and which window is focused, and lets
+//! pagers show and switch the workspaces.
 //!
 //! The protocol is extensive, but we only implement the necessary bits to get
 //! the mentioned functionality working.
@@ -17,9 +18,11 @@
 //! * https://hackage.haskell.org/package/xmonad-contrib/docs/XMonad-Util-WindowProperties.html
 //! * https://hackage.haskell.org/package/xmonad-contrib/docs/XMonad-Hooks-SetWMName.htm
 
-use cplwm_api::types::Window;
-use cplwm_api::wm::{FloatSupport, FullscreenSupport, MinimiseSupport, WindowManager};
+use cplwm_api::types::{Geometry, Window, WorkspaceIndex};
+use cplwm_api::wm::{FloatSupport, FullscreenSupport, MinimiseSupport, MultiWorkspaceSupport,
+                    WindowManager};
 
+use std::collections::HashMap;
 use std::os::raw::{c_int, c_long};
 use std::error;
 
@@ -27,13 +30,20 @@ use super::*;
 
 use x11_dl::xlib;
 
-/// The hints this window manager supports.
+/// The hints this window manager supports, the extensions can support more,
+/// see [`supported_atom_names`].
+///
+/// [`supported_atom_names`]: trait.Extension.html#method.supported_atom_names
 pub const SUPPORTED_ATOM_NAMES: &'static [&'static str] = &["_NET_ACTIVE_WINDOW",
                                                             "_NET_CLIENT_LIST",
                                                             "_NET_CLIENT_LIST_STACKING",
                                                             "_NET_WM_STATE",
                                                             "_NET_WM_STATE_FULLSCREEN",
-                                                            "_NET_WM_STATE_HIDDEN"];
+                                                            "_NET_WM_STATE_HIDDEN",
+                                                            "_NET_WM_WINDOW_TYPE_DOCK",
+                                                            "_NET_WM_STRUT",
+                                                            "_NET_WM_STRUT_PARTIAL",
+                                                            "_NET_WORKAREA"];
 
 /// The actions windows are allowed to perform.
 pub const ALLOWED_ACTIONS_ATOM_NAMES: &'static [&'static str] = &["_NET_WM_ACTION_MOVE",
@@ -42,9 +52,13 @@ pub const ALLOWED_ACTIONS_ATOM_NAMES: &'static [&'static str] = &["_NET_WM_ACTIO
                                                                   "_NET_WM_ACTION_FULLSCREEN",
                                                                   "_NET_WM_ACTION_CLOSE"];
 
+/// The `_NET_WM_DESKTOP` of a window on all desktops, 0xFFFFFFFF as a 32-bit
+/// item.
+const ALL_DESKTOPS: c_int = -1;
+
 /// EWMH Support.
 impl<WM> X11Backend<WM>
-    where WM: WindowManager + FloatSupport + FullscreenSupport + MinimiseSupport
+    where WM: WindowManager
 {
     /// Advertise which hints are supported.
     ///
@@ -92,6 +106,40 @@ impl<WM> X11Backend<WM>
                                       allowed_actions_atoms.map(|atom| atom as c_int));
     }
 
+    /// Advertise the work area.
+    ///
+    /// Sets the [`_NET_WORKAREA`] property of the root window: the root
+    /// window without the space reserved by docks, see `get_work_area`. There
+    /// is a work area per desktop, they are all the same. Without desktops,
+    /// i.e. without the `Workspaces` extension, there is one. Does nothing
+    /// when neither the work area nor the number of desktops changed.
+    ///
+    /// [`_NET_WORKAREA`]: https://developer.gnome.org/wm-spec/
+    pub fn update_work_area(&mut self) {
+        let root = self.get_screen();
+        let work_area = self.get_work_area(Geometry {
+            x: 0,
+            y: 0,
+            width: root.width,
+            height: root.height,
+        });
+        let count = self.desktop_count.unwrap_or(1);
+        if self.work_area != Some((work_area, count)) {
+            let work_areas = (0..count).flat_map(|_| {
+                vec![work_area.x,
+                     work_area.y,
+                     work_area.width as c_int,
+                     work_area.height as c_int]
+            });
+            self.change_window_property32(self.root_window,
+                                          self.get_atom("_NET_WORKAREA"),
+                                          xlib::XA_CARDINAL,
+                                          xlib::PropModeReplace,
+                                          work_areas);
+            self.work_area = Some((work_area, count));
+        }
+    }
+
     /// Advertise the managed windows.
     ///
     /// Sets the [`_NET_CLIENT_LIST`] property of the root window. The list of
@@ -140,6 +188,38 @@ impl<WM> X11Backend<WM>
                                       Some(focused_window.unwrap_or(0) as c_int).into_iter());
     }
 
+    /// Advertise whether the given window demands attention.
+    ///
+    /// Adds `_NET_WM_STATE_DEMANDS_ATTENTION` to or removes it from the
+    /// [`_NET_WM_STATE`] property of the given window, so task bars can
+    /// highlight it.
+    ///
+    /// [`_NET_WM_STATE`]: https://developer.gnome.org/wm-spec/#idm140200472615568
+    pub fn set_demands_attention(&self, window: Window, demands_attention: bool) {
+        let net_wm_state_atom = self.get_atom("_NET_WM_STATE");
+        let demands_attention_atom = self.get_atom("_NET_WM_STATE_DEMANDS_ATTENTION") as c_int;
+        let mut states = self.get_window_property32(window, net_wm_state_atom)
+            .unwrap_or_default();
+        if states.contains(&demands_attention_atom) == demands_attention {
+            return;
+        }
+        if demands_attention {
+            states.push(demands_attention_atom);
+        } else {
+            states.retain(|state| *state != demands_attention_atom);
+        }
+        self.change_window_property32(window,
+                                      net_wm_state_atom,
+                                      xlib::XA_ATOM,
+                                      xlib::PropModeReplace,
+                                      states.into_iter());
+    }
+}
+
+/// EWMH client messages.
+impl<WM> X11Backend<WM>
+    where WM: WindowManager + FloatSupport + FullscreenSupport + MinimiseSupport
+{
     /// Private helper function for `handle_ewmh_client_message`.
     fn net_wm_state_toggler<F, E>(&mut self,
                                   window: Window,
@@ -191,6 +271,8 @@ impl<WM> X11Backend<WM>
     /// * [`_NET_CLOSE_WINDOW`]
     /// * [`_NET_WM_STATE`]: only `_NET_WM_STATE_FULLSCREEN` and `_NET_WM_STATE_HIDDEN`.
     ///
+    /// The extensions handle the other actions, e.g. switching workspaces.
+    ///
     /// [`XClientMessageEvent`]: ../x11_dl/xlib/struct.XClientMessageEvent.html
     /// [`_NET_ACTIVE_WINDOW`]: https://developer.gnome.org/wm-spec/#idm140200472702304
     /// [`_NET_CLOSE_WINDOW`]: https://developer.gnome.org/wm-spec/#idm140200472668896
@@ -257,6 +339,128 @@ impl<WM> X11Backend<WM>
 }
 
 
+/// EWMH desktops.
+impl<WM> X11Backend<WM>
+    where WM: WindowManager
+{
+    /// Advertise the workspaces.
+    ///
+    /// Sets the [`_NET_NUMBER_OF_DESKTOPS`], [`_NET_CURRENT_DESKTOP`] and
+    /// [`_NET_DESKTOP_NAMES`] properties of the root window, and the
+    /// [`_NET_WM_DESKTOP`] property of every managed window. Windows that are
+    /// on no workspace, e.g. the hidden scratchpad, are on all desktops. The
+    /// work area is published for every desktop, see `update_work_area`.
+    ///
+    /// Workspaces come and go, so this should be done whenever the window
+    /// manager changed. Every change notifies the pagers and windows, so
+    /// only the properties that differ from the ones published last time are
+    /// changed.
+    ///
+    /// [`_NET_NUMBER_OF_DESKTOPS`]: https://developer.gnome.org/wm-spec/#idm140200472728112
+    /// [`_NET_CURRENT_DESKTOP`]: https://developer.gnome.org/wm-spec/#idm140200472711712
+    /// [`_NET_DESKTOP_NAMES`]: https://developer.gnome.org/wm-spec/#idm140200472708656
+    /// [`_NET_WM_DESKTOP`]: https://developer.gnome.org/wm-spec/#idm140200472635456
+    pub fn update_desktops<W>(&mut self)
+        where WM: MultiWorkspaceSupport<W>,
+              W: WindowManager
+    {
+        let count = self.get_wm().get_workspace_count();
+        if self.desktop_count != Some(count) {
+            self.change_window_property32(self.root_window,
+                                          self.get_atom("_NET_NUMBER_OF_DESKTOPS"),
+                                          xlib::XA_CARDINAL,
+                                          xlib::PropModeReplace,
+                                          Some(count as c_int).into_iter());
+        }
+        let current = self.get_wm().get_current_workspace_index();
+        if self.current_desktop != Some(current) {
+            self.change_window_property32(self.root_window,
+                                          self.get_atom("_NET_CURRENT_DESKTOP"),
+                                          xlib::XA_CARDINAL,
+                                          xlib::PropModeReplace,
+                                          Some(current as c_int).into_iter());
+            self.current_desktop = Some(current);
+        }
+        // The names are NUL-terminated UTF-8 strings
+        let mut names: Vec<u8> = Vec::new();
+        for index in 0..count {
+            if let Ok(name) = self.get_wm().get_workspace_name(index) {
+                names.extend(name.bytes());
+            }
+            names.push(0);
+        }
+        if self.desktop_names.as_ref() != Some(&names) {
+            self.change_window_property8(self.root_window,
+                                         self.get_atom("_NET_DESKTOP_NAMES"),
+                                         self.get_atom("UTF8_STRING"),
+                                         xlib::PropModeReplace,
+                                         &names);
+            self.desktop_names = Some(names);
+        }
+        self.desktop_count = Some(count);
+        self.update_work_area();
+        // Windows that are no longer managed are forgotten
+        let net_wm_desktop_atom = self.get_atom("_NET_WM_DESKTOP");
+        let mut window_desktops = HashMap::new();
+        for &window in &self.managed {
+            let desktop = self.get_wm()
+                .get_window_workspace(window)
+                .map_or(ALL_DESKTOPS, |index| index as c_int);
+            if self.window_desktops.get(&window) != Some(&desktop) {
+                self.change_window_property32(window,
+                                              net_wm_desktop_atom,
+                                              xlib::XA_CARDINAL,
+                                              xlib::PropModeReplace,
+                                              Some(desktop).into_iter());
+            }
+            window_desktops.insert(window, desktop);
+        }
+        self.window_desktops = window_desktops;
+    }
+
+    /// Handle an [`XClientMessageEvent`] that concerns the workspaces.
+    ///
+    /// The following actions are supported:
+    ///
+    /// * [`_NET_CURRENT_DESKTOP`]: switch to another workspace, e.g. `wmctrl -s`.
+    /// * [`_NET_WM_DESKTOP`]: move a window to another workspace, e.g. `xdotool
+    ///   set_desktop_for_window`. Moving it to all desktops is not supported.
+    ///
+    /// [`XClientMessageEvent`]: ../x11_dl/xlib/struct.XClientMessageEvent.html
+    /// [`_NET_CURRENT_DESKTOP`]: https://developer.gnome.org/wm-spec/#idm140200472711712
+    /// [`_NET_WM_DESKTOP`]: https://developer.gnome.org/wm-spec/#idm140200472635456
+    pub fn handle_desktop_client_message<W>(&mut self,
+                                            xev: xlib::XClientMessageEvent)
+                                            -> X11Result<()>
+        where WM: MultiWorkspaceSupport<W>,
+              W: WindowManager
+    {
+        let net_current_desktop_atom = self.get_atom("_NET_CURRENT_DESKTOP");
+        let net_wm_desktop_atom = self.get_atom("_NET_WM_DESKTOP");
+
+        if xev.message_type == net_current_desktop_atom {
+
+            // Negative indices become too large
+            let index = xev.data.get_long(0) as WorkspaceIndex;
+            if index < self.get_wm().get_workspace_count() {
+                try!(self.get_wm_mut().switch_workspace(index));
+            }
+
+        } else if xev.message_type == net_wm_desktop_atom {
+
+            let index = xev.data.get_long(0) as WorkspaceIndex;
+            if self.get_wm().is_managed(xev.window) &&
+               index < self.get_wm().get_workspace_count() &&
+               self.get_wm().get_window_workspace(xev.window) != Some(index) {
+                try!(self.get_wm_mut().move_window_to_workspace(xev.window, index, false));
+            }
+
+        }
+        Ok(())
+    }
+}
+
+
 // Private helpers for `net_wm_state_toggler`
 
 /// Remove/unset a `_NET_WM_STATE_*` property
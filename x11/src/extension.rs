@@ -0,0 +1,388 @@
+//! Extensions of the backend.
+//!
+//! The backend itself only needs a window manager that implements
+//! `WindowManager`, `FloatSupport`, `FullscreenSupport` and
+//! `MinimiseSupport`. The functionality that needs more of the window
+//! manager, e.g. the tab strip or the workspaces, is provided by an
+//! [`Extension`]. An extension is only available for the window managers
+//! that implement the traits it needs, so the runner chooses the extensions
+//! that fit its window manager and lists them in the `extensions` of the
+//! [`X11Config`].
+//!
+//! [`Extension`]: trait.Extension.html
+//! [`X11Config`]: struct.X11Config.html
+
+use std::marker::PhantomData;
+use std::os::raw::c_long;
+
+use cplwm_api::types::{Geometry, Window, WindowLayout, WindowWithInfo};
+use cplwm_api::wm::{FloatSupport, FullscreenSupport, MinimiseSupport, MultiScreenSupport,
+                    MultiWorkspaceSupport, ScratchpadSupport, TilingSupport, UrgencySupport,
+                    WindowManager};
+
+use super::*;
+
+use x11_dl::xlib;
+
+/// Functionality the backend calls at certain points, e.g. when the window
+/// layout was applied or when an event arrived.
+///
+/// Every method does nothing by default, so an extension only implements
+/// the ones it needs.
+pub trait Extension<WM> {
+    /// Return the names of the EWMH hints this extension supports, they are
+    /// added to the ones the backend supports, see `set_net_supported`.
+    fn supported_atom_names(&self) -> &'static [&'static str] {
+        &[]
+    }
+
+    /// Called when the backend is initialised, after the windows that were
+    /// already present were added.
+    fn init(&self, _backend: &mut X11Backend<WM>) -> X11Result<()> {
+        Ok(())
+    }
+
+    /// Give the window manager the current screens (monitors) of the
+    /// display.
+    ///
+    /// Return `true` when the extension took care of it. Otherwise, the
+    /// window manager gets the whole screen of the display, see
+    /// `update_screens`.
+    fn update_screens(&self, _backend: &mut X11Backend<WM>) -> X11Result<bool> {
+        Ok(false)
+    }
+
+    /// Return the geometry of the current screen in the coordinates of the
+    /// root window, `None` when the extension doesn't know it.
+    ///
+    /// New windows are placed relative to the current screen.
+    fn get_current_screen(&self, _backend: &X11Backend<WM>) -> Option<Geometry> {
+        None
+    }
+
+    /// Return the geometry of the screen that shows the given window, in the
+    /// coordinates of the root window, `None` when the extension doesn't
+    /// know it.
+    ///
+    /// The geometries the window manager uses for the window are relative to
+    /// this screen, see `to_screen_coordinates`.
+    fn get_window_screen(&self, _backend: &X11Backend<WM>, _window: Window) -> Option<Geometry> {
+        None
+    }
+
+    /// Return the fullscreen windows of the window manager, `None` when the
+    /// extension doesn't know them, see `get_fullscreen_windows`.
+    fn get_fullscreen_windows(&self, _backend: &X11Backend<WM>) -> Option<Vec<Window>> {
+        None
+    }
+
+    /// Return the tabs of the window manager, one `Vec` for every group of
+    /// tabs that is shown, `None` when the extension doesn't know them, see
+    /// `get_tab_groups`.
+    fn get_tabs(&self, _backend: &X11Backend<WM>) -> Option<Vec<Vec<Window>>> {
+        None
+    }
+
+    /// Add a new window matching the given rules to the window manager,
+    /// e.g. to another workspace.
+    ///
+    /// Return `true` when the extension added the window. Otherwise the
+    /// window is added with the `add_window` method of the window manager.
+    fn place_window(&self,
+                    _backend: &mut X11Backend<WM>,
+                    _window_with_info: &WindowWithInfo,
+                    _rules: &[&Rule])
+                    -> X11Result<bool> {
+        Ok(false)
+    }
+
+    /// Called when a new window was added to the window manager.
+    fn window_mapped(&self, _backend: &mut X11Backend<WM>, _window: Window) -> X11Result<()> {
+        Ok(())
+    }
+
+    /// Called when the given window layout was applied, see
+    /// `apply_window_layout`.
+    fn window_layout_applied(&self,
+                             _backend: &mut X11Backend<WM>,
+                             _window_layout: &WindowLayout) {
+    }
+
+    /// Called for every event, after the backend handled it.
+    fn handle_event(&self,
+                    _backend: &mut X11Backend<WM>,
+                    _event: &xlib::XEvent)
+                    -> X11Result<()> {
+        Ok(())
+    }
+}
+
+/// Show a tab strip above the visible tab of every group of tabs, see
+/// `update_tab_strips`.
+pub struct TabStrip;
+
+impl<WM> Extension<WM> for TabStrip
+    where WM: WindowManager + FloatSupport + FullscreenSupport + MinimiseSupport + TilingSupport
+{
+    fn window_layout_applied(&self, backend: &mut X11Backend<WM>, window_layout: &WindowLayout) {
+        backend.update_tab_strips(window_layout);
+    }
+
+    fn handle_event(&self, backend: &mut X11Backend<WM>, event: &xlib::XEvent) -> X11Result<()> {
+        match event.get_type() {
+            // Wait for the last event of a series before redrawing the strip.
+            xlib::Expose => {
+                let xev: xlib::XExposeEvent = From::from(event);
+                if xev.count == 0 {
+                    backend.draw_tab_strip(xev.window);
+                }
+            }
+            // The title of one of the tabs changed.
+            xlib::PropertyNotify => {
+                let xev: xlib::XPropertyEvent = From::from(event);
+                let is_title = xev.atom == xlib::XA_WM_NAME ||
+                               xev.atom == backend.get_atom("_NET_WM_NAME");
+                if is_title {
+                    if let Some(strip) = backend.get_tab_strip_of(xev.window) {
+                        backend.draw_tab_strip(strip);
+                    }
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
+/// Mark windows as urgent when they set the urgency hint or demand
+/// attention, and show the urgent windows, see `update_urgency`.
+pub struct Urgency;
+
+impl<WM> Extension<WM> for Urgency
+    where WM: WindowManager + UrgencySupport
+{
+    fn supported_atom_names(&self) -> &'static [&'static str] {
+        &["_NET_WM_STATE_DEMANDS_ATTENTION"]
+    }
+
+    fn window_mapped(&self, backend: &mut X11Backend<WM>, window: Window) -> X11Result<()> {
+        // A window can be urgent from the start, e.g. when it opens on
+        // another workspace. The focused window is seen by the user, so it
+        // isn't urgent.
+        if backend.get_wm().get_focused_window() != Some(window) &&
+           backend.has_urgency_hint(window) {
+            try!(backend.get_wm_mut().set_urgent(window, true));
+        }
+        Ok(())
+    }
+
+    fn window_layout_applied(&self, backend: &mut X11Backend<WM>, window_layout: &WindowLayout) {
+        backend.update_urgency(window_layout);
+    }
+
+    fn handle_event(&self, backend: &mut X11Backend<WM>, event: &xlib::XEvent) -> X11Result<()> {
+        let (window, urgent) = match event.get_type() {
+            // An application sets or clears the urgency hint in the WM_HINTS
+            // of its window.
+            xlib::PropertyNotify => {
+                let xev: xlib::XPropertyEvent = From::from(event);
+                if xev.atom != xlib::XA_WM_HINTS || !backend.get_wm().is_managed(xev.window) {
+                    return Ok(());
+                }
+                (xev.window, backend.has_urgency_hint(xev.window))
+            }
+            // A pager or the application itself adds, removes or toggles
+            // `_NET_WM_STATE_DEMANDS_ATTENTION`, `update_urgency` updates the
+            // property.
+            xlib::ClientMessage => {
+                let xev: xlib::XClientMessageEvent = From::from(event);
+                let demands_attention = backend.get_atom("_NET_WM_STATE_DEMANDS_ATTENTION");
+                if xev.message_type != backend.get_atom("_NET_WM_STATE") ||
+                   (xev.data.get_long(1) != demands_attention as c_long &&
+                    xev.data.get_long(2) != demands_attention as c_long) ||
+                   !backend.get_wm().is_managed(xev.window) {
+                    return Ok(());
+                }
+                // Remove = 0, Add = 1, Toggle = 2
+                match xev.data.get_long(0) {
+                    0 => (xev.window, false),
+                    1 => (xev.window, true),
+                    2 => (xev.window, !backend.get_wm().is_urgent(xev.window)),
+                    _ => return Ok(()),
+                }
+            }
+            _ => return Ok(()),
+        };
+        // The focused window is already noticed.
+        if urgent != backend.get_wm().is_urgent(window) &&
+           backend.get_wm().get_focused_window() != Some(window) {
+            try!(backend.get_wm_mut().set_urgent(window, urgent));
+        }
+        Ok(())
+    }
+}
+
+/// Make a window the scratchpad when it is mapped and its instance name, the
+/// first part of its `WM_CLASS`, is `name`.
+///
+/// For example, start the scratchpad terminal with `xterm -name scratchpad`.
+pub struct Scratchpad {
+    /// The instance name of the scratchpad window.
+    pub name: &'static str,
+}
+
+impl Default for Scratchpad {
+    /// A scratchpad with the instance name `"scratchpad"`.
+    fn default() -> Scratchpad {
+        Scratchpad { name: "scratchpad" }
+    }
+}
+
+impl<WM> Extension<WM> for Scratchpad
+    where WM: WindowManager + ScratchpadSupport
+{
+    fn window_mapped(&self, backend: &mut X11Backend<WM>, window: Window) -> X11Result<()> {
+        let is_scratchpad = match backend.get_window_class(window) {
+            Some((instance, _)) => instance == self.name,
+            None => false,
+        };
+        if is_scratchpad {
+            try!(backend.get_wm_mut().set_scratchpad_window(window));
+        }
+        Ok(())
+    }
+}
+
+/// Publish the workspaces as EWMH desktops, let pagers switch them, and add
+/// new windows to the workspace their rules name.
+///
+/// `W` is the type of the window manager of each workspace.
+pub struct Workspaces<W> {
+    /// The type of the window manager of each workspace.
+    workspace: PhantomData<W>,
+}
+
+impl<W> Workspaces<W> {
+    /// Create a `Workspaces` extension.
+    pub fn new() -> Workspaces<W> {
+        Workspaces { workspace: PhantomData }
+    }
+}
+
+impl<WM, W> Extension<WM> for Workspaces<W>
+    where WM: WindowManager + MultiWorkspaceSupport<W>,
+          W: WindowManager
+{
+    fn supported_atom_names(&self) -> &'static [&'static str] {
+        &["_NET_NUMBER_OF_DESKTOPS",
+          "_NET_CURRENT_DESKTOP",
+          "_NET_DESKTOP_NAMES",
+          "_NET_WM_DESKTOP"]
+    }
+
+    fn init(&self, backend: &mut X11Backend<WM>) -> X11Result<()> {
+        backend.update_desktops();
+        Ok(())
+    }
+
+    fn place_window(&self,
+                    backend: &mut X11Backend<WM>,
+                    window_with_info: &WindowWithInfo,
+                    rules: &[&Rule])
+                    -> X11Result<bool> {
+        let name = match rule_action(rules, |rule| rule.workspace) {
+            Some(name) => name,
+            None => return Ok(false),
+        };
+        let index = try!(backend.get_wm_mut().get_or_create_workspace(name));
+        if index == backend.get_wm().get_current_workspace_index() {
+            return Ok(false);
+        }
+        let window = window_with_info.window;
+        try!(backend.get_wm_mut().add_window_to_workspace(index, window_with_info.clone()));
+        // The window stays unmapped while its workspace is hidden, mark it
+        // as hidden so it is kept after a restart, see `find_hidden_windows`.
+        let shown = backend.get_wm()
+            .get_window_layout()
+            .windows
+            .iter()
+            .any(|&(w, _)| w == window);
+        if !shown {
+            backend.set_wm_state(window, WindowState::Iconic);
+        }
+        Ok(true)
+    }
+
+    fn window_layout_applied(&self, backend: &mut X11Backend<WM>, _window_layout: &WindowLayout) {
+        // Let pagers know about new, renamed, deleted or switched workspaces
+        backend.update_desktops();
+    }
+
+    fn handle_event(&self, backend: &mut X11Backend<WM>, event: &xlib::XEvent) -> X11Result<()> {
+        if event.get_type() == xlib::ClientMessage {
+            try!(backend.handle_desktop_client_message(From::from(event)));
+        }
+        Ok(())
+    }
+}
+
+/// Show a workspace on every screen (monitor) of the display, see
+/// `update_screen_geometries`, each with its own fullscreen window and tabs.
+///
+/// `W` is the type of the window manager of each workspace.
+pub struct Screens<W> {
+    /// The type of the window manager of each workspace.
+    workspace: PhantomData<W>,
+}
+
+impl<W> Screens<W> {
+    /// Create a `Screens` extension.
+    pub fn new() -> Screens<W> {
+        Screens { workspace: PhantomData }
+    }
+}
+
+impl<WM, W> Extension<WM> for Screens<W>
+    where WM: WindowManager + MultiScreenSupport<W>,
+          W: WindowManager + FullscreenSupport + TilingSupport
+{
+    fn update_screens(&self, backend: &mut X11Backend<WM>) -> X11Result<bool> {
+        try!(backend.update_screen_geometries());
+        Ok(true)
+    }
+
+    fn get_current_screen(&self, backend: &X11Backend<WM>) -> Option<Geometry> {
+        let screens = backend.get_wm().get_screens();
+        screens.get(backend.get_wm().get_current_screen_index()).cloned()
+    }
+
+    fn get_window_screen(&self, backend: &X11Backend<WM>, window: Window) -> Option<Geometry> {
+        let wm = backend.get_wm();
+        wm.get_window_workspace(window)
+            .and_then(|index| wm.get_workspace_screen(index))
+            .and_then(|screen| wm.get_screens().get(screen).cloned())
+    }
+
+    fn get_fullscreen_windows(&self, backend: &X11Backend<WM>) -> Option<Vec<Window>> {
+        // The workspace of every screen can have a fullscreen window
+        let wm = backend.get_wm();
+        let windows = (0..wm.get_screens().len())
+            .filter_map(|screen| wm.get_screen_workspace(screen).ok())
+            .filter_map(|index| wm.get_workspace(index).ok())
+            .filter_map(|workspace| workspace.get_fullscreen_window())
+            .collect();
+        Some(windows)
+    }
+
+    fn get_tabs(&self, backend: &X11Backend<WM>) -> Option<Vec<Vec<Window>>> {
+        // The workspace of every screen can have tabs
+        let wm = backend.get_wm();
+        let groups = (0..wm.get_screens().len())
+            .filter_map(|screen| wm.get_screen_workspace(screen).ok())
+            .filter_map(|index| wm.get_workspace(index).ok())
+            .map(|workspace| workspace.get_tabs())
+            .filter(|tabs| !tabs.is_empty())
+            .collect();
+        Some(groups)
+    }
+}
@@ -143,6 +143,28 @@ impl<WM> X11Backend<WM>
         }
     }
 
+    /// Return the key codes of the keys of the modifiers in the given key
+    /// mask, e.g. both Alt keys for `xlib::Mod1Mask`.
+    pub fn get_modifier_keycodes(&self, mask: XKeyMask) -> Vec<xlib::KeyCode> {
+        let mut modifier_keycodes = Vec::new();
+        let modifier_keymap_ptr = unsafe { (self.xlib.XGetModifierMapping)(self.display) };
+        let modifier_keymap = unsafe { *modifier_keymap_ptr };
+        let keycodes = unsafe {
+            slice::from_raw_parts(modifier_keymap.modifiermap,
+                                  8 * modifier_keymap.max_keypermod as usize)
+        };
+        for (modifier, keycodes_per_modifier) in (xlib::ShiftMapIndex..(xlib::Mod5MapIndex + 1))
+            .zip(keycodes.chunks(modifier_keymap.max_keypermod as usize)) {
+            if mask & (1 << modifier) != 0 {
+                modifier_keycodes.extend(keycodes_per_modifier.iter().filter(|k| **k != 0));
+            }
+        }
+        unsafe {
+            (self.xlib.XFreeModifiermap)(modifier_keymap_ptr);
+        }
+        modifier_keycodes
+    }
+
     /// Remove numlock and capslock from the [`XKeyMask`](type.XKeyMask.html).
     pub fn clean_mask(&self, mask: XKeyMask) -> XKeyMask {
         let nlm = self.numlock_mask;
@@ -186,6 +208,80 @@ impl<WM> X11Backend<WM>
         }
     }
 
+    /// Return true when the user holds a modifier, see
+    /// [`hold_modifier`](#method.hold_modifier).
+    pub fn is_holding_modifier(&self) -> bool {
+        self.holding.is_some()
+    }
+
+    /// Wait until the user releases the given modifier, e.g.
+    /// `xlib::Mod1Mask` for Alt, and then execute `on_release`.
+    ///
+    /// Use this in a key binding that includes the modifier, e.g. to cycle
+    /// through the windows with Alt-Tab while Alt is held. Meanwhile the
+    /// keyboard is grabbed, so the key bindings keep working and the release
+    /// of the modifier can't go unnoticed. When the modifier was already
+    /// released, `on_release` is executed right away.
+    ///
+    /// Does nothing when the user already holds a modifier.
+    pub fn hold_modifier(&mut self, mask: XKeyMask, on_release: KeyCommand<WM>) -> X11Result<()> {
+        if self.holding.is_some() {
+            return Ok(());
+        }
+        trace!("hold_modifier: {}", mask);
+        let mut root_return = 0;
+        let mut child_return = 0;
+        let mut root_x_return = 0;
+        let mut root_y_return = 0;
+        let mut win_x_return = 0;
+        let mut win_y_return = 0;
+        let mut mask_return = 0;
+        unsafe {
+            (self.xlib.XGrabKeyboard)(self.display,
+                                      self.root_window,
+                                      xlib::True,
+                                      xlib::GrabModeAsync,
+                                      xlib::GrabModeAsync,
+                                      xlib::CurrentTime);
+            // The modifier could have been released before we grabbed the
+            // keyboard, in which case we will never see it.
+            (self.xlib.XQueryPointer)(self.display,
+                                      self.root_window,
+                                      &mut root_return,
+                                      &mut child_return,
+                                      &mut root_x_return,
+                                      &mut root_y_return,
+                                      &mut win_x_return,
+                                      &mut win_y_return,
+                                      &mut mask_return);
+        }
+        // Look up the keys of the modifier now, not on every key release.
+        let keycodes = self.get_modifier_keycodes(mask);
+        self.holding = Some((mask, keycodes, on_release));
+        if mask_return & mask == 0 {
+            self.release_modifier()
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Stop holding the modifier: ungrab the keyboard and execute the command
+    /// passed to [`hold_modifier`](#method.hold_modifier).
+    ///
+    /// Does nothing when the user doesn't hold a modifier.
+    pub fn release_modifier(&mut self) -> X11Result<()> {
+        match self.holding.take() {
+            Some((mask, _, on_release)) => {
+                trace!("release_modifier: {}", mask);
+                unsafe {
+                    (self.xlib.XUngrabKeyboard)(self.display, xlib::CurrentTime);
+                }
+                on_release(self)
+            }
+            None => Ok(()),
+        }
+    }
+
     /// Grab or ungrab the given button and keymask on the given window.
     ///
     /// Grab when `grab` is `true`, ungrab when `false`.
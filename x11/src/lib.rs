@@ -61,35 +61,42 @@ extern crate rustc_serialize;
 extern crate x11_dl;
 extern crate zombie;
 
+mod dock;
 mod event;
 mod ewmh;
+mod extension;
 mod input;
 mod macros;
 mod methods;
 mod mouse;
+mod tabs;
 mod types;
 mod util;
 
+pub use self::dock::*;
 pub use self::event::*;
 pub use self::ewmh::*;
+pub use self::extension::*;
 pub use self::input::*;
 pub use self::macros::*;
 pub use self::methods::*;
 pub use self::mouse::*;
+pub use self::tabs::*;
 pub use self::types::*;
 pub use self::util::*;
 
-use std::collections::HashSet;
+use std::cmp::max;
+use std::collections::{HashMap, HashSet};
+use std::mem;
 use std::os::raw::{c_int, c_long, c_uint};
 use std::ptr::{null, null_mut};
+use std::rc::Rc;
 
-use cplwm_api::wm::{FloatSupport, FullscreenSupport, MinimiseSupport, WindowManager};
-use cplwm_api::types::{Geometry, Screen, Window, WindowLayout, WindowWithInfo};
+use cplwm_api::wm::{FloatSupport, FullscreenSupport, MinimiseSupport, MultiScreenSupport,
+                    UrgencySupport, WindowManager};
+use cplwm_api::types::{Geometry, Screen, Window, WindowLayout, WindowWithInfo, WorkspaceIndex};
 
-use x11_dl::xlib;
-
-/// The border width of windows.
-pub const WINDOW_BORDER_WIDTH: c_uint = 1;
+use x11_dl::{xinerama, xlib, xrandr};
 
 /// The event mask for the root window.
 ///
@@ -101,7 +108,8 @@ const ROOT_MASK: XEventMask =
 /// The event mask for client windows.
 ///
 /// This controls which client window events the event loop will receive.
-const CLIENT_MASK: XEventMask = xlib::StructureNotifyMask | xlib::EnterWindowMask;
+const CLIENT_MASK: XEventMask =
+    xlib::StructureNotifyMask | xlib::EnterWindowMask | xlib::PropertyChangeMask;
 
 /// The X11 Backend.
 ///
@@ -111,6 +119,19 @@ pub struct X11Backend<WM> {
     ///
     /// We need this to call Xlib functions.
     xlib: xlib::Xlib,
+    /// A reference to the Xinerama library, `None` when it is unavailable.
+    ///
+    /// We need this to find the screens (monitors), see
+    /// `get_screen_geometries`.
+    xinerama: Option<xinerama::Xlib>,
+    /// A reference to the Xrandr library, `None` when it is unavailable.
+    ///
+    /// We need this to be notified when monitors are added, removed or
+    /// rotated, see `select_screen_change_input`.
+    xrandr: Option<xrandr::Xrandr>,
+    /// The number of the first event of the RandR extension, `None` when
+    /// the X server doesn't support it.
+    randr_event_base: Option<c_int>,
     /// Cached display.
     ///
     /// We need this for many Xlib calls.
@@ -133,6 +154,12 @@ pub struct X11Backend<WM> {
     /// For example the function to execute while dragging could be a function
     /// that moves the window to the right position.
     dragging: Option<Box<WhileDragging<WM>>>,
+    /// The modifier held by the user, the key codes of its keys and the
+    /// command to execute when it is released, see `hold_modifier`.
+    ///
+    /// For example Alt, while the user presses Tab to cycle through the
+    /// windows.
+    holding: Option<(XKeyMask, Vec<xlib::KeyCode>, KeyCommand<WM>)>,
     /// The hidden windows. We need this to handle `UnmapNotify` events in
     /// `handler`.
     hidden: HashSet<Window>,
@@ -150,6 +177,53 @@ pub struct X11Backend<WM> {
     focused_border_color: xlib::XColor,
     /// Cached unfocused border color pixel.
     unfocused_border_color: xlib::XColor,
+    /// Cached urgent border color pixel.
+    urgent_border_color: xlib::XColor,
+    /// The windows shown as urgent, see `update_urgency`.
+    urgent: HashSet<Window>,
+    /// The border width of windows not listed in the `border_widths` of the
+    /// window layout.
+    border_width: c_uint,
+    /// The windows in which the tab strips are drawn, one for every group of
+    /// tabs that is shown at the same time, e.g. on every screen, see
+    /// `get_tab_groups`. Each with the tabs it lists and the shown tab. A
+    /// hidden strip lists no tabs.
+    tab_strips: Vec<(Window, Vec<Window>, Option<Window>)>,
+    /// The docks, e.g. panels, which aren't managed, see `add_dock`.
+    docks: Vec<Window>,
+    /// The struts of the docks that reserve space, see `get_strut`. They are
+    /// read when a dock is added or changes its strut.
+    struts: HashMap<Window, Vec<c_int>>,
+    /// The work area the window manager got for every screen (monitor),
+    /// together with the geometry of the whole screen, see
+    /// `update_screen_geometries`.
+    monitors: Vec<(Geometry, Geometry)>,
+    /// The fullscreen windows of the last applied window layout, see
+    /// `get_fullscreen_windows`.
+    fullscreen: Vec<Window>,
+    /// The origin of the work area the window manager got as its `Screen`,
+    /// in the coordinates of the root window, see `update_screens`. The
+    /// window layout of the window manager is relative to it.
+    origin: (c_int, c_int),
+    /// The number of desktops last published on the root window, `None`
+    /// before the first time, see `update_desktops`.
+    desktop_count: Option<usize>,
+    /// The current desktop last published on the root window.
+    current_desktop: Option<WorkspaceIndex>,
+    /// The desktop names last published on the root window.
+    desktop_names: Option<Vec<u8>>,
+    /// The work area and the number of desktops last published as
+    /// `_NET_WORKAREA`, see `update_work_area`.
+    work_area: Option<(Geometry, usize)>,
+    /// The desktop last published for every managed window.
+    window_desktops: HashMap<Window, c_int>,
+    /// The extensions chosen by the user, see [`Extension`].
+    ///
+    /// They are shared, so an extension can be called with a mutable borrow
+    /// of the backend, see `get_extensions`.
+    ///
+    /// [`Extension`]: trait.Extension.html
+    extensions: Rc<Vec<Box<Extension<WM>>>>,
 }
 
 /// Access to the window manager.
@@ -166,6 +240,11 @@ impl<WM> X11Backend<WM> {
         self.wm_modified = true;
         &mut self.wm
     }
+
+    /// Return the extensions chosen by the user.
+    pub fn get_extensions(&self) -> Rc<Vec<Box<Extension<WM>>>> {
+        self.extensions.clone()
+    }
 }
 
 
@@ -192,10 +271,11 @@ impl<WM> X11Backend<WM>
     /// when it returns an error, or when it is terminated by the user.
     ///
     /// [`new`]: ../cplwm_api/wm/trait.WindowManager.html#tymethod.new
-    pub fn start<F>(make_wm: F, config: X11Config<WM>) -> X11Result<()>
+    pub fn start<F>(make_wm: F, mut config: X11Config<WM>) -> X11Result<()>
         where F: Fn(Screen) -> WM
     {
-        let mut backend = Self::new(make_wm, &config);
+        let extensions = mem::replace(&mut config.extensions, Vec::new());
+        let mut backend = Self::new(make_wm, &config, extensions);
         try!(backend.init(&config));
         backend.run(config)
     }
@@ -204,7 +284,10 @@ impl<WM> X11Backend<WM>
     ///
     /// A connection to the X server is made, the window manager is created,
     /// and the initial state is created.
-    fn new<F>(make_wm: F, config: &X11Config<WM>) -> X11Backend<WM>
+    fn new<F>(make_wm: F,
+              config: &X11Config<WM>,
+              extensions: Vec<Box<Extension<WM>>>)
+              -> X11Backend<WM>
         where F: Fn(Screen) -> WM
     {
         // Open Xlib library
@@ -232,9 +315,14 @@ impl<WM> X11Backend<WM>
             allocate_color(display, &xlib, config.focused_border_color, colormap).unwrap();
         let unfocused_border_color =
             allocate_color(display, &xlib, config.unfocused_border_color, colormap).unwrap();
+        let urgent_border_color =
+            allocate_color(display, &xlib, config.urgent_border_color, colormap).unwrap();
 
         X11Backend {
             xlib: xlib,
+            xinerama: xinerama::Xlib::open().ok(),
+            xrandr: xrandr::Xrandr::open().ok(),
+            randr_event_base: None,
             display: display,
             root_window: root_window,
             wm_modified: false,
@@ -242,10 +330,26 @@ impl<WM> X11Backend<WM>
             current_event: None,
             numlock_mask: 0,
             dragging: None,
+            holding: None,
             hidden: HashSet::new(),
             managed: Vec::new(),
             focused_border_color: focused_border_color,
             unfocused_border_color: unfocused_border_color,
+            urgent_border_color: urgent_border_color,
+            urgent: HashSet::new(),
+            border_width: config.border_width,
+            tab_strips: Vec::new(),
+            docks: Vec::new(),
+            struts: HashMap::new(),
+            monitors: Vec::new(),
+            fullscreen: Vec::new(),
+            origin: (0, 0),
+            desktop_count: None,
+            current_desktop: None,
+            desktop_names: None,
+            work_area: None,
+            window_desktops: HashMap::new(),
+            extensions: Rc::new(extensions),
         }
     }
 
@@ -268,11 +372,20 @@ impl<WM> X11Backend<WM>
         // state of the WM.
         self.restore_state();
 
+        // Show the workspaces on the screens (monitors) of the display, they
+        // can differ from the ones of the previous state.
+        try!(self.update_screens());
+
         // In case the WM has been shut down and restarted, remove all
-        // windows managed by the WM that are no longer visible.
+        // windows managed by the WM that are no longer visible. Windows the
+        // WM hid, e.g. the hidden scratchpad, are kept.
         let visible_windows = self.find_visible_windows();
+        let hidden_windows = self.find_hidden_windows();
         for managed_window in self.get_wm().get_windows() {
-            if !visible_windows.contains(&managed_window) {
+            if hidden_windows.contains(&managed_window) {
+                self.add_window(managed_window);
+                self.hidden.insert(managed_window);
+            } else if !visible_windows.contains(&managed_window) {
                 try!(self.get_wm_mut().remove_window(managed_window));
             }
         }
@@ -281,6 +394,15 @@ impl<WM> X11Backend<WM>
         // the WM has been shut down and restarted, add all windows that
         // have been added since the shutdown.
         for visible_window in visible_windows {
+            // Docks aren't managed, but could have been by an older version
+            if self.is_dock(visible_window) {
+                if self.get_wm().is_managed(visible_window) {
+                    try!(self.get_wm_mut().remove_window(visible_window));
+                    self.remove_window(visible_window);
+                }
+                try!(self.add_dock(visible_window));
+                continue;
+            }
             // Make sure we grabbed the input and events
             self.add_window(visible_window);
 
@@ -310,14 +432,26 @@ impl<WM> X11Backend<WM>
         self.grab_keys(&config.key_bindings);
         self.grab_buttons(&config.button_bindings);
 
+        // Get notified when monitors are added, removed or rotated.
+        self.select_screen_change_input();
+
         // EWMH support
-        self.set_net_supported(SUPPORTED_ATOM_NAMES.iter().map(|name| *name));
+        let extensions = self.get_extensions();
+        let extension_atom_names = extensions.iter()
+            .flat_map(|extension| extension.supported_atom_names().iter());
+        self.set_net_supported(SUPPORTED_ATOM_NAMES.iter()
+            .chain(extension_atom_names)
+            .map(|name| *name));
+
+        for extension in extensions.iter() {
+            try!(extension.init(self));
+        }
 
         // Apply the layout when the state was restored. Windows could have
         // moved in the meantime.
         if self.wm_modified {
             let empty_layout = WindowLayout::new();
-            let restored_layout = self.get_wm().get_window_layout();
+            let restored_layout = self.get_root_window_layout();
             self.apply_window_layout(&empty_layout, &restored_layout);
         }
 
@@ -363,29 +497,68 @@ impl<WM> X11Backend<WM>
             _ => (),
         }
 
+        let fullscreen = self.get_fullscreen_windows();
+        let prev_fullscreen = mem::replace(&mut self.fullscreen, fullscreen);
+
         // Update the stack order. Dumb: also restacks when windows were only
         // added and/or removed.
-        if prev_windows != new_windows {
+        if prev_windows != new_windows || prev_fullscreen != self.fullscreen {
             self.restack(new_windows.iter().map(|w| *w));
+            self.raise_docks();
         }
 
         // Update the geometries: for every window in the new layout, look up
-        // its geometry in the old layout. When the lookup fails or when the
-        // geometry differs from the new one, update the geometry.
+        // its geometry and border width in the old layout. When the lookup
+        // fails, when either differs from the new one or when the window
+        // became fullscreen or stopped being fullscreen, update the geometry.
         for &(window, geometry) in &new_window_layout.windows {
+            let border_width = self.get_layout_border_width(new_window_layout, window);
+            let is_fullscreen = self.fullscreen.contains(&window);
             match prev_window_layout.windows.iter().find(|&&(w, _)| w == window) {
-                // Same geometry -> do nothing
-                Some(&(_, prev_geometry)) if prev_geometry == geometry => (),
+                // Same geometry, border width and fullscreen state -> do nothing
+                Some(&(_, prev_geometry)) if prev_geometry == geometry &&
+                                             self.get_layout_border_width(prev_window_layout,
+                                                                          window) ==
+                                             border_width &&
+                                             prev_fullscreen.contains(&window) ==
+                                             is_fullscreen => (),
+                // A fullscreen window also covers the space reserved by docks
+                _ if is_fullscreen => {
+                    let geometry = self.get_whole_screen(geometry);
+                    self.set_window_geometry(window, geometry, border_width)
+                }
                 // Different geometry or no geometry -> set it
-                _ => self.set_window_geometry(window, geometry),
+                _ => self.set_window_geometry(window, geometry, border_width),
             }
         }
 
+        // Let the extensions show the changes they care about, e.g. the tab
+        // strip
+        for extension in self.get_extensions().iter() {
+            extension.window_layout_applied(self, new_window_layout);
+        }
+
         // Ignore any enter/leave events we may have generated while applying
         // the window layout.
         self.clear_events(xlib::EnterWindowMask | xlib::LeaveWindowMask);
     }
 
+    /// Return the fullscreen windows of the window manager.
+    ///
+    /// An extension can know more of them, e.g. one for every screen
+    /// (monitor), see [`Extension`]. Otherwise it is the window returned by
+    /// `get_fullscreen_window`.
+    ///
+    /// [`Extension`]: trait.Extension.html
+    pub fn get_fullscreen_windows(&self) -> Vec<Window> {
+        for extension in self.get_extensions().iter() {
+            if let Some(windows) = extension.get_fullscreen_windows(self) {
+                return windows;
+            }
+        }
+        self.get_wm().get_fullscreen_window().into_iter().collect()
+    }
+
     /// Add a new window to the backend.
     ///
     /// Do not confuse this with the [`add_window`] method of the window
@@ -409,7 +582,7 @@ impl<WM> X11Backend<WM>
         }
         self.set_client_list(self.managed.iter());
         self.set_allowed_actions(window, ALLOWED_ACTIONS_ATOM_NAMES.iter().map(|name| *name));
-        self.set_window_border_width(window, WINDOW_BORDER_WIDTH);
+        self.set_window_border_width(window, self.border_width);
         self.set_window_border_color(window, self.unfocused_border_color);
     }
 
@@ -562,10 +735,41 @@ impl<WM> X11Backend<WM>
         }
     }
 
+    /// Return the border width the given window should have in the given
+    /// `WindowLayout`.
+    ///
+    /// Windows not listed in its `border_widths` get the configured border
+    /// width.
+    pub fn get_layout_border_width(&self, window_layout: &WindowLayout, window: Window) -> c_uint {
+        window_layout.border_widths
+            .iter()
+            .find(|&&(w, _)| w == window)
+            .map(|&(_, border_width)| border_width)
+            .unwrap_or(self.border_width)
+    }
+
     /// Get the actual `Geometry` of a window according to the X server.
     ///
+    /// Note that the width and height exclude the border, see
+    /// [`get_window_border_width`].
+    ///
     /// Return an `Err` when the X server doesn't know the window.
+    ///
+    /// [`get_window_border_width`]: struct.X11Backend.html#method.get_window_border_width
     pub fn get_window_geometry(&self, window: Window) -> X11Result<Geometry> {
+        self.query_geometry(window).map(|(geometry, _)| geometry)
+    }
+
+    /// Get the actual border width of a window according to the X server.
+    ///
+    /// Return an `Err` when the X server doesn't know the window.
+    pub fn get_window_border_width(&self, window: Window) -> X11Result<c_uint> {
+        self.query_geometry(window).map(|(_, border_width)| border_width)
+    }
+
+    /// Ask the X server for the geometry and the border width of a window
+    /// using `XGetGeometry`.
+    fn query_geometry(&self, window: Window) -> X11Result<(Geometry, c_uint)> {
         let mut root = 0;
         let mut x = 0;
         let mut y = 0;
@@ -591,19 +795,25 @@ impl<WM> X11Backend<WM>
                 width: width,
                 height: height,
             };
-            trace!("get_window_geometry: {} {}", window, geometry);
-            Ok(geometry)
+            trace!("query_geometry: {} {} border {}", window, geometry, border_width);
+            Ok((geometry, border_width))
         } else {
-            error!("get_window_geometry: unknown window {}", window);
+            error!("query_geometry: unknown window {}", window);
             Err(X11Error::UnknownWindow(window))
         }
 
     }
 
     /// Ask the X server to resize/move the window so it matches the given
-    /// `Geometry`.
-    pub fn set_window_geometry(&mut self, window: Window, new_geometry: Geometry) {
-        trace!("set_window_geometry: {} {}", window, new_geometry);
+    /// `Geometry`, including a border of the given width.
+    pub fn set_window_geometry(&mut self,
+                               window: Window,
+                               new_geometry: Geometry,
+                               border_width: c_uint) {
+        trace!("set_window_geometry: {} {} border {}",
+               window,
+               new_geometry,
+               border_width);
         // Ignore invalid geometries
         if !valid_geometry(&new_geometry) {
             return;
@@ -612,9 +822,9 @@ impl<WM> X11Backend<WM>
         let mut changes = xlib::XWindowChanges {
             x: x,
             y: y,
-            width: (width - 2 * WINDOW_BORDER_WIDTH) as c_int,
-            height: (height - 2 * WINDOW_BORDER_WIDTH) as c_int,
-            border_width: WINDOW_BORDER_WIDTH as c_int,
+            width: max(width.saturating_sub(2 * border_width), 1) as c_int,
+            height: max(height.saturating_sub(2 * border_width), 1) as c_int,
+            border_width: border_width as c_int,
             sibling: 0,
             stack_mode: 0,
         };
@@ -624,3 +834,155 @@ impl<WM> X11Backend<WM>
         }
     }
 }
+
+/// Urgency
+impl<WM> X11Backend<WM>
+    where WM: WindowManager + UrgencySupport
+{
+    /// Show the urgent windows of the window manager.
+    ///
+    /// The windows that became urgent get the urgent border color, the ones
+    /// that are no longer urgent get the unfocused border color, unless they
+    /// are focused. Their `_NET_WM_STATE_DEMANDS_ATTENTION` state is updated
+    /// accordingly.
+    pub fn update_urgency(&mut self, window_layout: &WindowLayout) {
+        let urgent: HashSet<Window> = self.get_wm().get_urgent_windows().into_iter().collect();
+        for &window in self.urgent.symmetric_difference(&urgent) {
+            // Setting the border color of a removed window causes an error.
+            if !self.managed.contains(&window) {
+                continue;
+            }
+            trace!("update_urgency: {} {}", window, urgent.contains(&window));
+            if urgent.contains(&window) {
+                self.set_window_border_color(window, self.urgent_border_color);
+            } else if window_layout.focused_window != Some(window) {
+                self.set_window_border_color(window, self.unfocused_border_color);
+            }
+            self.set_demands_attention(window, urgent.contains(&window));
+        }
+        self.urgent = urgent;
+    }
+}
+
+/// Screens
+impl<WM> X11Backend<WM>
+    where WM: WindowManager
+{
+    /// Update the window manager with the changed screen of the display.
+    ///
+    /// The window manager gets the work area of the screen, without the
+    /// space reserved by docks, see `get_work_area`. A `Screen` has no
+    /// origin, so the window layout is moved by the origin of the work area,
+    /// see `get_root_window_layout`. An extension can take care of this
+    /// instead, e.g. to give the window manager every screen (monitor), see
+    /// [`Extension`]. Finally the work area is published, see
+    /// `update_work_area`.
+    ///
+    /// Does nothing when the screen didn't change, the X server sends
+    /// several events for a single change.
+    ///
+    /// [`Extension`]: trait.Extension.html
+    pub fn update_screens(&mut self) -> X11Result<()> {
+        let mut updated = false;
+        for extension in self.get_extensions().iter() {
+            if try!(extension.update_screens(self)) {
+                updated = true;
+                break;
+            }
+        }
+        if updated {
+            self.origin = (0, 0);
+        } else {
+            let root = self.get_screen();
+            let screen = Geometry {
+                x: 0,
+                y: 0,
+                width: root.width,
+                height: root.height,
+            };
+            let work_area = self.get_work_area(screen);
+            self.monitors = vec![(work_area, screen)];
+            self.origin = (work_area.x, work_area.y);
+            let size = Screen {
+                width: work_area.width,
+                height: work_area.height,
+            };
+            if size != self.get_wm().get_screen() {
+                trace!("update_screens: {:?}", work_area);
+                self.get_wm_mut().resize_screen(size);
+            }
+        }
+        self.update_work_area();
+        Ok(())
+    }
+
+    /// Return the window layout of the window manager in the coordinates of
+    /// the root window, the windows are moved by the origin of the work area
+    /// the window manager got, see `update_screens`.
+    pub fn get_root_window_layout(&self) -> WindowLayout {
+        let mut window_layout = self.get_wm().get_window_layout();
+        let (x, y) = self.origin;
+        for &mut (_, ref mut geometry) in &mut window_layout.windows {
+            geometry.x += x;
+            geometry.y += y;
+        }
+        window_layout
+    }
+
+    /// Convert the given geometry of the given window from the coordinates of
+    /// the root window to the coordinates the window manager uses for it,
+    /// relative to the screen (monitor) that shows the window, see
+    /// [`Extension`]. Without such a screen they are relative to the work
+    /// area the window manager got, see `update_screens`.
+    ///
+    /// [`Extension`]: trait.Extension.html
+    pub fn to_screen_coordinates(&self, window: Window, geometry: Geometry) -> Geometry {
+        let (x, y) = self.get_extensions()
+            .iter()
+            .filter_map(|extension| extension.get_window_screen(self, window))
+            .next()
+            .map_or(self.origin, |screen| (screen.x, screen.y));
+        Geometry {
+            x: geometry.x - x,
+            y: geometry.y - y,
+            ..geometry
+        }
+    }
+
+    /// Return the geometry of the whole screen (monitor) of which the window
+    /// manager got the given work area, see `update_screen_geometries`.
+    /// Return the given geometry when it isn't the work area of a screen.
+    pub fn get_whole_screen(&self, work_area: Geometry) -> Geometry {
+        self.monitors
+            .iter()
+            .find(|&&(area, _)| area == work_area)
+            .map_or(work_area, |&(_, screen)| screen)
+    }
+}
+
+/// Multiple screens
+impl<WM> X11Backend<WM>
+    where WM: WindowManager
+{
+    /// Show the workspaces on the current screens (monitors) of the display,
+    /// see `get_screen_geometries`. The space reserved by docks is taken off
+    /// the screens, see `get_work_area`.
+    ///
+    /// Does nothing when the screens didn't change, the X server sends
+    /// several events for a single change.
+    pub fn update_screen_geometries<W>(&mut self) -> X11Result<()>
+        where WM: MultiScreenSupport<W>,
+              W: WindowManager
+    {
+        self.monitors = self.get_screen_geometries()
+            .into_iter()
+            .map(|screen| (self.get_work_area(screen), screen))
+            .collect();
+        let screens: Vec<Geometry> = self.monitors.iter().map(|&(area, _)| area).collect();
+        if screens != self.get_wm().get_screens() {
+            trace!("update_screen_geometries: {:?}", screens);
+            try!(self.get_wm_mut().set_screens(screens));
+        }
+        Ok(())
+    }
+}
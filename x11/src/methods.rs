@@ -7,18 +7,18 @@ use std::env;
 use std::ffi::{CStr, CString};
 use std::fs;
 use std::mem::{transmute, zeroed};
-use std::os::raw::{c_int, c_long, c_uchar, c_uint, c_ulong};
+use std::os::raw::{c_char, c_int, c_long, c_uchar, c_uint, c_ulong};
 use std::slice;
 use std::sync::Mutex;
 
-use cplwm_api::types::{FloatOrTile, Screen, Window};
+use cplwm_api::types::{FloatOrTile, Geometry, Screen, Window};
 use cplwm_api::wm::WindowManager;
 
 use super::*;
 
 use exec::execvp;
 use libc::wchar_t;
-use x11_dl::xlib;
+use x11_dl::{xlib, xrandr};
 
 lazy_static! {
     /// Private static cache from atom name to atom.
@@ -138,6 +138,28 @@ impl<WM: WindowManager> X11Backend<WM> {
         }
     }
 
+    /// Change the 8-bit items associated with the window's property, e.g. a
+    /// string.
+    ///
+    /// See `change_window_property32`.
+    pub fn change_window_property8(&self,
+                                   window: Window,
+                                   property: xlib::Atom,
+                                   property_type: xlib::Atom,
+                                   mode: c_int,
+                                   props: &[u8]) {
+        unsafe {
+            (self.xlib.XChangeProperty)(self.display,
+                                        window,
+                                        property,
+                                        property_type,
+                                        8,
+                                        mode,
+                                        props.as_ptr(),
+                                        props.len() as c_int);
+        }
+    }
+
     /// Get the [`WM_STATE`] property of the given window.
     ///
     /// Return `None`, when it could not be retrieved.
@@ -264,25 +286,6 @@ impl<WM: WindowManager> X11Backend<WM> {
     /// This method is used to add existing visible windows to the window
     /// manager when it just started.
     pub fn find_visible_windows(&mut self) -> Vec<Window> {
-        let mut root_return = 0;
-        let mut parent_return = 0;
-        let mut children_return_ptr = unsafe { zeroed() };
-        let mut nchildren_return = 0;
-        let status = unsafe {
-            (self.xlib.XQueryTree)(self.display,
-                                   self.root_window,
-                                   &mut root_return,
-                                   &mut parent_return,
-                                   &mut children_return_ptr,
-                                   &mut nchildren_return)
-        };
-        if status == 0 {
-            error!("XQueryTree failed");
-            // Pretend there were no windows
-            return Vec::new();
-        }
-        let windows =
-            unsafe { slice::from_raw_parts(children_return_ptr, nchildren_return as usize) };
         // Function that determines when a window is visible
         let visible = |window: &Window| {
             let mut window_attrs = unsafe { zeroed() };
@@ -303,16 +306,67 @@ impl<WM: WindowManager> X11Backend<WM> {
                 false
             }
         };
+        let windows = self.query_top_level_windows();
         let visible_windows: Vec<Window> = windows.iter().map(|w| *w).filter(visible).collect();
-        unsafe {
-            (self.xlib.XFree)(transmute(children_return_ptr));
-        }
         debug!("find_existing_windows: found {} window(s) of which {} visible",
                windows.len(),
                visible_windows.len());
         visible_windows
     }
 
+    /// Find all existing windows hidden by the window manager.
+    ///
+    /// A window hidden by the window manager, e.g. a window on another
+    /// workspace, is unmapped and its [`WM_STATE`] is `Iconic`. This method
+    /// is used to keep these windows managed after a restart.
+    ///
+    /// [`WM_STATE`]: https://tronche.com/gui/x/icccm/sec-4.html#WM_STATE
+    pub fn find_hidden_windows(&mut self) -> Vec<Window> {
+        let hidden = |window: &Window| {
+            match self.get_wm_state(*window) {
+                Some(WindowState::Iconic) => true,
+                _ => false,
+            }
+        };
+        let windows = self.query_top_level_windows();
+        let hidden_windows: Vec<Window> = windows.iter().map(|w| *w).filter(hidden).collect();
+        debug!("find_hidden_windows: found {} window(s) of which {} hidden",
+               windows.len(),
+               hidden_windows.len());
+        hidden_windows
+    }
+
+    /// Return the children of the root window, using [`XQueryTree`].
+    ///
+    /// [`XQueryTree`]:
+    /// https://tronche.com/gui/x/xlib/window-information/XQueryTree.html
+    fn query_top_level_windows(&self) -> Vec<Window> {
+        let mut root_return = 0;
+        let mut parent_return = 0;
+        let mut children_return_ptr = unsafe { zeroed() };
+        let mut nchildren_return = 0;
+        let status = unsafe {
+            (self.xlib.XQueryTree)(self.display,
+                                   self.root_window,
+                                   &mut root_return,
+                                   &mut parent_return,
+                                   &mut children_return_ptr,
+                                   &mut nchildren_return)
+        };
+        if status == 0 {
+            error!("XQueryTree failed");
+            // Pretend there were no windows
+            return Vec::new();
+        }
+        let windows =
+            unsafe { slice::from_raw_parts(children_return_ptr, nchildren_return as usize) }
+                .to_vec();
+        unsafe {
+            (self.xlib.XFree)(transmute(children_return_ptr));
+        }
+        windows
+    }
+
     /// Restore the window manager state from the state file.
     ///
     /// When the state could not be read or deserialised, nothing is done.
@@ -499,6 +553,102 @@ impl<WM: WindowManager> X11Backend<WM> {
         })
     }
 
+    /// Retrieve the instance name and the class name of the given window,
+    /// i.e. its [`WM_CLASS`] property, using [`XGetClassHint`].
+    ///
+    /// Return `None` when the window has no `WM_CLASS` or when it could not
+    /// be converted to valid `String`s.
+    ///
+    /// [`WM_CLASS`]: https://tronche.com/gui/x/icccm/sec-4.html#WM_CLASS
+    /// [`XGetClassHint`]:
+    /// https://tronche.com/gui/x/xlib/ICC/client-to-window-manager/XGetClassHint.html
+    pub fn get_window_class(&self, window: Window) -> Option<(String, String)> {
+        let mut class_hint: xlib::XClassHint = unsafe { zeroed() };
+        let status = unsafe { (self.xlib.XGetClassHint)(self.display, window, &mut class_hint) };
+        if status == 0 {
+            return None;
+        }
+        let to_string = |ptr: *mut c_char| {
+            if ptr.is_null() {
+                None
+            } else {
+                let cstr = unsafe { CStr::from_ptr(ptr) };
+                let maybe_string = cstr.to_str().ok().map(|s| s.to_owned());
+                unsafe {
+                    (self.xlib.XFree)(transmute(ptr));
+                }
+                maybe_string
+            }
+        };
+        let instance = to_string(class_hint.res_name);
+        let class = to_string(class_hint.res_class);
+        trace!("get_window_class: {} {:?} {:?}", window, instance, class);
+        match (instance, class) {
+            (Some(instance), Some(class)) => Some((instance, class)),
+            _ => None,
+        }
+    }
+
+    /// Retrieve the [`WM_WINDOW_ROLE`] of the given window.
+    ///
+    /// Return `None` when the window has no role or when it could not be
+    /// converted to a valid `String`.
+    ///
+    /// [`WM_WINDOW_ROLE`]: https://tronche.com/gui/x/icccm/sec-5.html#s-5.1
+    pub fn get_window_role(&self, window: Window) -> Option<String> {
+        let wm_window_role_atom = self.get_atom("WM_WINDOW_ROLE");
+        let mut text_prop_return: xlib::XTextProperty = unsafe { zeroed() };
+        let status = unsafe {
+            (self.xlib.XGetTextProperty)(self.display,
+                                         window,
+                                         &mut text_prop_return,
+                                         wm_window_role_atom)
+        };
+        if status == 0 || text_prop_return.value.is_null() {
+            return None;
+        }
+        let cstr = unsafe { CStr::from_ptr(text_prop_return.value as *const c_char) };
+        let maybe_string = cstr.to_str().ok().map(|s| s.to_owned());
+        unsafe {
+            (self.xlib.XFree)(transmute(text_prop_return.value));
+        }
+        maybe_string
+    }
+
+    /// Return the rules of which the given window satisfies all conditions,
+    /// in the same order, see [`Rule`].
+    ///
+    /// The properties of the window are only retrieved once.
+    ///
+    /// [`Rule`]: struct.Rule.html
+    pub fn matching_rules<'a>(&self, window: Window, rules: &'a [Rule]) -> Vec<&'a Rule> {
+        let (instance, class) = match self.get_window_class(window) {
+            Some((instance, class)) => (Some(instance), Some(class)),
+            None => (None, None),
+        };
+        let title = self.get_window_title(window);
+        let role = self.get_window_role(window);
+        let net_wm_window_type = self.get_atom("_NET_WM_WINDOW_TYPE");
+        let window_types = self.get_window_property32(window, net_wm_window_type)
+            .unwrap_or_default();
+        // An unset condition always matches, a set one never matches a
+        // missing property.
+        let equals = |condition: Option<&'static str>, value: &Option<String>| {
+            condition.map_or(true, |c| value.as_ref().map_or(false, |v| v == c))
+        };
+        let matching: Vec<&Rule> = rules.iter()
+            .filter(|rule| {
+                equals(rule.instance, &instance) && equals(rule.class, &class) &&
+                equals(rule.role, &role) &&
+                rule.title.map_or(true, |t| title.as_ref().map_or(false, |v| v.contains(t))) &&
+                rule.window_type
+                    .map_or(true, |name| window_types.contains(&(self.get_atom(name) as c_int)))
+            })
+            .collect();
+        trace!("matching_rules: {} matches {} rule(s)", window, matching.len());
+        matching
+    }
+
     /// Close the given window.
     ///
     /// When the window supports the [ICCCM protocol], the protocol is
@@ -625,6 +775,27 @@ impl<WM: WindowManager> X11Backend<WM> {
         window_state_props.contains(&(net_wm_state_fullscreen as c_int))
     }
 
+    /// Check whether the urgency hint is set in the [`WM_HINTS`] of the
+    /// given window.
+    ///
+    /// Uses [`XGetWMHints`].
+    ///
+    /// [`WM_HINTS`]: https://tronche.com/gui/x/icccm/sec-4.html#WM_HINTS
+    /// [`XGetWMHints`]:
+    /// https://tronche.com/gui/x/xlib/ICC/client-to-window-manager/XGetWMHints.html
+    pub fn has_urgency_hint(&self, window: Window) -> bool {
+        let hints_ptr = unsafe { (self.xlib.XGetWMHints)(self.display, window) };
+        if hints_ptr.is_null() {
+            return false;
+        }
+        let urgent = unsafe { (*hints_ptr).flags } & xlib::XUrgencyHint != 0;
+        unsafe {
+            (self.xlib.XFree)(transmute(hints_ptr));
+        }
+        trace!("has_urgency_hint: {} {}", window, urgent);
+        urgent
+    }
+
     /// Set the window border width using `XSetWindowBorderWidth`.
     pub fn set_window_border_width(&self, window: Window, border_width: c_uint) {
         trace!("set_window_border_width: {}, {}", window, border_width);
@@ -655,4 +826,87 @@ impl<WM: WindowManager> X11Backend<WM> {
             height: unsafe { (*xscreen).height } as c_uint,
         }
     }
+
+    /// Return the geometries of the screens (monitors), in the coordinates of
+    /// the root window.
+    ///
+    /// The screens are queried using Xinerama, which also knows about the
+    /// monitors configured with RandR. A monitor that clones another one is
+    /// only returned once. Without Xinerama, the whole `Screen` is the only
+    /// screen.
+    pub fn get_screen_geometries(&self) -> Vec<Geometry> {
+        let mut geometries = Vec::new();
+        if let Some(ref xinerama) = self.xinerama {
+            unsafe {
+                if (xinerama.XineramaIsActive)(self.display) != xlib::False {
+                    let mut count: c_int = 0;
+                    let infos = (xinerama.XineramaQueryScreens)(self.display, &mut count);
+                    if !infos.is_null() {
+                        for info in slice::from_raw_parts(infos, count as usize) {
+                            let geometry = Geometry {
+                                x: info.x_org as c_int,
+                                y: info.y_org as c_int,
+                                width: info.width as c_uint,
+                                height: info.height as c_uint,
+                            };
+                            if !geometries.contains(&geometry) {
+                                geometries.push(geometry);
+                            }
+                        }
+                        (self.xlib.XFree)(transmute(infos));
+                    }
+                }
+            }
+        }
+        if geometries.is_empty() {
+            let screen = self.get_screen();
+            geometries.push(Geometry {
+                x: 0,
+                y: 0,
+                width: screen.width,
+                height: screen.height,
+            });
+        }
+        trace!("get_screen_geometries: {:?}", geometries);
+        geometries
+    }
+
+    /// Ask the X server to notify us when the screens (monitors) change,
+    /// e.g. when a monitor is plugged in, unplugged or rotated.
+    ///
+    /// This uses the RandR extension, the events are recognised by
+    /// `is_screen_change_event`. Does nothing when the X server doesn't
+    /// support RandR.
+    pub fn select_screen_change_input(&mut self) {
+        if let Some(ref xrandr) = self.xrandr {
+            let mut event_base = 0;
+            let mut error_base = 0;
+            let supported = unsafe {
+                (xrandr.XRRQueryExtension)(self.display, &mut event_base, &mut error_base)
+            };
+            if supported == xlib::False {
+                warn!("select_screen_change_input: RandR is not supported");
+                return;
+            }
+            let mask = xrandr::RRScreenChangeNotifyMask | xrandr::RRCrtcChangeNotifyMask |
+                       xrandr::RROutputChangeNotifyMask;
+            unsafe {
+                (xrandr.XRRSelectInput)(self.display, self.root_window, mask);
+            }
+            self.randr_event_base = Some(event_base);
+        }
+    }
+
+    /// Return `true` when the event is a RandR event saying that the screens
+    /// (monitors) changed, see `select_screen_change_input`.
+    pub fn is_screen_change_event(&self, event: &xlib::XEvent) -> bool {
+        match self.randr_event_base {
+            Some(base) => {
+                let event_type = event.get_type();
+                event_type == base + xrandr::RRScreenChangeNotify ||
+                event_type == base + xrandr::RRNotify
+            }
+            None => false,
+        }
+    }
 }
@@ -103,6 +103,7 @@ impl<WM> X11Backend<WM>
                     width: orig_geometry.width,
                     height: orig_geometry.height,
                 };
+                let new_geometry = backend.to_screen_coordinates(window, new_geometry);
                 try!(backend.get_wm_mut().set_window_geometry(window, new_geometry));
                 Ok(())
             };
@@ -138,6 +139,7 @@ impl<WM> X11Backend<WM>
                     width: (orig_width + (moved_x - start_x)) as c_uint,
                     height: (orig_height + (moved_y - start_y)) as c_uint,
                 };
+                let new_geometry = backend.to_screen_coordinates(window, new_geometry);
                 try!(backend.get_wm_mut().set_window_geometry(window, new_geometry));
                 Ok(())
             };
@@ -0,0 +1,208 @@
+//! Tab strip methods.
+//!
+//! When the window manager uses a layout with tabs, e.g. the monocle layout,
+//! only one of the tiles is visible. A tab strip is a thin window drawn by
+//! the backend right above the visible tile, listing the titles of all tabs
+//! and highlighting the visible one. With multiple screens, every screen can
+//! show its own tab strip.
+
+use std::ffi::CString;
+use std::mem::zeroed;
+use std::os::raw::{c_int, c_uint};
+use std::ptr::null_mut;
+
+use cplwm_api::types::{Geometry, TAB_STRIP_HEIGHT, Window, WindowLayout};
+use cplwm_api::wm::{FloatSupport, FullscreenSupport, MinimiseSupport, TilingSupport,
+                    WindowManager};
+
+use super::*;
+
+use x11_dl::xlib;
+
+/// Horizontal space between the left side of a tab and its title.
+const TAB_TITLE_PADDING: c_int = 4;
+
+/// Tab strip methods.
+impl<WM> X11Backend<WM>
+    where WM: WindowManager + FloatSupport + FullscreenSupport + MinimiseSupport + TilingSupport
+{
+    /// Return the tabs of the window manager, one `Vec` for every group of
+    /// tabs that is shown.
+    ///
+    /// An extension can know more of them, e.g. the tabs of the workspace on
+    /// every screen (monitor), see [`Extension`]. Otherwise it is the tabs
+    /// returned by `get_tabs`.
+    ///
+    /// [`Extension`]: trait.Extension.html
+    pub fn get_tab_groups(&self) -> Vec<Vec<Window>> {
+        for extension in self.get_extensions().iter() {
+            if let Some(groups) = extension.get_tabs(self) {
+                return groups;
+            }
+        }
+        let tabs = self.get_wm().get_tabs();
+        if tabs.is_empty() { Vec::new() } else { vec![tabs] }
+    }
+
+    /// Show a tab strip above the visible tab of every group of tabs in the
+    /// given window layout, see `get_tab_groups`.
+    ///
+    /// The tab strips that are not needed, e.g. because the window manager
+    /// has no tabs or none of them is visible, are hidden.
+    pub fn update_tab_strips(&mut self, window_layout: &WindowLayout) {
+        let mut shown_groups = Vec::new();
+        for tabs in self.get_tab_groups() {
+            let shown = window_layout.windows
+                .iter()
+                .find(|&&(w, _)| tabs.contains(&w))
+                .map(|&(w, geometry)| (w, geometry));
+            if let Some((shown, geometry)) = shown {
+                let geometry = Geometry {
+                    x: geometry.x,
+                    y: geometry.y - TAB_STRIP_HEIGHT as c_int,
+                    width: geometry.width,
+                    height: TAB_STRIP_HEIGHT,
+                };
+                if valid_geometry(&geometry) {
+                    shown_groups.push((tabs, shown, geometry));
+                }
+            }
+        }
+        let used = shown_groups.len();
+        for (i, (tabs, shown, geometry)) in shown_groups.into_iter().enumerate() {
+            trace!("update_tab_strips: {} tab(s) at {}", tabs.len(), geometry);
+            let strip = self.get_tab_strip(i);
+            self.tab_strips[i] = (strip, tabs, Some(shown));
+            unsafe {
+                (self.xlib.XMoveResizeWindow)(self.display,
+                                              strip,
+                                              geometry.x,
+                                              geometry.y,
+                                              geometry.width,
+                                              geometry.height);
+                (self.xlib.XMapRaised)(self.display, strip);
+            }
+            self.draw_tab_strip(strip);
+        }
+        for &mut (strip, ref mut tabs, ref mut shown) in self.tab_strips.iter_mut().skip(used) {
+            if shown.take().is_some() {
+                trace!("update_tab_strips: hide {}", strip);
+                tabs.clear();
+                unsafe {
+                    (self.xlib.XUnmapWindow)(self.display, strip);
+                }
+            }
+        }
+    }
+
+    /// Draw the titles of the tabs in the given tab strip.
+    ///
+    /// The width of the strip is divided equally among the tabs. The shown
+    /// tab, i.e. the focused tile of its workspace, gets the focused border
+    /// color, the others the unfocused border color.
+    ///
+    /// Does nothing when the window isn't a tab strip or when it is hidden.
+    pub fn draw_tab_strip(&self, strip: Window) {
+        let (tabs, shown) = match self.tab_strips.iter().find(|&&(s, _, _)| s == strip) {
+            Some(&(_, ref tabs, Some(shown))) => (tabs, shown),
+            _ => return,
+        };
+        let strip_geometry = match self.get_window_geometry(strip) {
+            Ok(geometry) => geometry,
+            Err(_) => return,
+        };
+        let ntabs = tabs.len() as c_uint;
+        let tab_width = strip_geometry.width / ntabs;
+        // The baseline of the titles, measured from the top of the strip
+        let baseline = (TAB_STRIP_HEIGHT * 3 / 4) as c_int;
+        unsafe {
+            let screen_number = (self.xlib.XDefaultScreen)(self.display);
+            let text_pixel = (self.xlib.XWhitePixel)(self.display, screen_number);
+            let gc = (self.xlib.XCreateGC)(self.display, strip, 0, null_mut());
+            for (i, tab) in tabs.iter().enumerate() {
+                let x = i as c_int * tab_width as c_int;
+                // The last tab gets the leftover pixels
+                let width = if i as c_uint == ntabs - 1 {
+                    strip_geometry.width - i as c_uint * tab_width
+                } else {
+                    tab_width
+                };
+                let color = if *tab == shown {
+                    self.focused_border_color
+                } else {
+                    self.unfocused_border_color
+                };
+                (self.xlib.XSetForeground)(self.display, gc, color.pixel);
+                (self.xlib.XFillRectangle)(self.display,
+                                           strip,
+                                           gc,
+                                           x,
+                                           0,
+                                           width,
+                                           TAB_STRIP_HEIGHT);
+                let title = self.get_window_title(*tab).unwrap_or(tab.to_string());
+                if let Ok(title) = CString::new(title) {
+                    (self.xlib.XSetForeground)(self.display, gc, text_pixel);
+                    (self.xlib.XDrawString)(self.display,
+                                            strip,
+                                            gc,
+                                            x + TAB_TITLE_PADDING,
+                                            baseline,
+                                            title.as_ptr(),
+                                            title.as_bytes().len() as c_int);
+                }
+            }
+            (self.xlib.XFreeGC)(self.display, gc);
+        }
+    }
+
+    /// Return the tab strip listing the given tab, if any.
+    pub fn get_tab_strip_of(&self, tab: Window) -> Option<Window> {
+        self.tab_strips
+            .iter()
+            .find(|&&(_, ref tabs, _)| tabs.contains(&tab))
+            .map(|&(strip, _, _)| strip)
+    }
+
+    /// Return the tab strip window with the given index in `tab_strips`,
+    /// create it, and the ones before it, when it doesn't exist yet.
+    ///
+    /// A tab strip is an override-redirect window, so it isn't managed like
+    /// the other windows. It only listens for `Expose` events, so it can be
+    /// redrawn. Mouse clicks on it are not propagated to the root window,
+    /// where they would be mistaken for a click to focus the strip.
+    fn get_tab_strip(&mut self, index: usize) -> Window {
+        while self.tab_strips.len() <= index {
+            let strip = self.create_tab_strip();
+            self.tab_strips.push((strip, Vec::new(), None));
+        }
+        self.tab_strips[index].0
+    }
+
+    /// Create a hidden tab strip window, see `get_tab_strip`.
+    fn create_tab_strip(&self) -> Window {
+        let mut attrs: xlib::XSetWindowAttributes = unsafe { zeroed() };
+        attrs.override_redirect = xlib::True;
+        attrs.background_pixel = self.unfocused_border_color.pixel;
+        attrs.event_mask = xlib::ExposureMask;
+        attrs.do_not_propagate_mask = xlib::ButtonPressMask | xlib::ButtonReleaseMask;
+        let strip = unsafe {
+            (self.xlib.XCreateWindow)(self.display,
+                                      self.root_window,
+                                      0,
+                                      0,
+                                      1,
+                                      1,
+                                      0,
+                                      xlib::CopyFromParent,
+                                      xlib::InputOutput as c_uint,
+                                      null_mut(),
+                                      xlib::CWOverrideRedirect | xlib::CWBackPixel |
+                                      xlib::CWEventMask |
+                                      xlib::CWDontPropagate,
+                                      &mut attrs)
+        };
+        trace!("create_tab_strip: created {}", strip);
+        strip
+    }
+}
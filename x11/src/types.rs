@@ -9,7 +9,7 @@ use std::os::raw::{c_int, c_long, c_uint};
 
 use super::*;
 
-use cplwm_api::types::Window;
+use cplwm_api::types::{BorderWidth, FloatOrTile, Geometry, Window};
 
 use x11_dl::xlib;
 
@@ -128,12 +128,26 @@ pub struct X11Config<WM> {
     pub focused_border_color: ColorName,
     /// The color used for the border of the unfocused windows.
     pub unfocused_border_color: ColorName,
+    /// The color used for the border of the urgent windows.
+    pub urgent_border_color: ColorName,
+    /// The border width of windows, unless the window layout overrides it
+    /// with its `border_widths`.
+    pub border_width: BorderWidth,
+    /// The rules deciding how new windows are placed, see [`Rule`].
+    ///
+    /// [`Rule`]: struct.Rule.html
+    pub rules: Vec<Rule>,
+    /// The extensions providing the functionality that needs more of the
+    /// window manager, see [`Extension`].
+    ///
+    /// [`Extension`]: trait.Extension.html
+    pub extensions: Vec<Box<Extension<WM>>>,
 }
 
 impl<WM> Default for X11Config<WM> {
     /// A default `X11Config`.
     ///
-    /// No bindings are defined, and some colors are chosen for the background
+    /// No bindings or extensions are defined, and some colors are chosen for the background
     /// and the borders.
     fn default() -> X11Config<WM> {
         X11Config {
@@ -142,10 +156,60 @@ impl<WM> Default for X11Config<WM> {
             background_color: "#f4f4f4",
             focused_border_color: "#0f56c6",
             unfocused_border_color: "#c0d6f9",
+            urgent_border_color: "#d64937",
+            border_width: 1,
+            rules: Vec::new(),
+            extensions: Vec::new(),
         }
     }
 }
 
+/// A rule deciding how a new window is placed when it is mapped.
+///
+/// A rule matches a window when all of its conditions that are set match
+/// the window, a rule without conditions matches every window. The actions
+/// that are set override the choices the backend would make otherwise. When
+/// multiple matching rules set the same action, the last one wins.
+///
+/// Set only the fields you need, for example:
+///
+/// ```ignore
+/// Rule {
+///     class: Some("Pidgin"),
+///     workspace: Some("chat"),
+///     skip_focus: Some(true),
+///     ..Default::default()
+/// }
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct Rule {
+    /// Condition: the instance name, the first part of `WM_CLASS`, equals
+    /// this.
+    pub instance: Option<&'static str>,
+    /// Condition: the class name, the second part of `WM_CLASS`, equals this.
+    pub class: Option<&'static str>,
+    /// Condition: the title of the window contains this.
+    pub title: Option<&'static str>,
+    /// Condition: `_NET_WM_WINDOW_TYPE` contains the atom with this name,
+    /// e.g. `"_NET_WM_WINDOW_TYPE_DIALOG"`.
+    pub window_type: Option<&'static str>,
+    /// Condition: `WM_WINDOW_ROLE` equals this.
+    pub role: Option<&'static str>,
+    /// Action: float or tile the window.
+    pub float_or_tile: Option<FloatOrTile>,
+    /// Action: add the window to the workspace with this name instead of the
+    /// current one. The workspace is created when it doesn't exist yet.
+    pub workspace: Option<&'static str>,
+    /// Action: the initial geometry of the window when it floats, relative to
+    /// the screen it appears on.
+    pub geometry: Option<Geometry>,
+    /// Action: start the window fullscreen, or not.
+    pub fullscreen: Option<bool>,
+    /// Action: when `true`, the window doesn't get the focus, the focused
+    /// window keeps it.
+    pub skip_focus: Option<bool>,
+}
+
 /// The type of function that can be executed while dragging the mouse.
 ///
 /// The two `c_int` arguments are the current x- and y-coordinates of the
@@ -12,7 +12,7 @@ use std::os::raw::{c_int, c_uint, c_ulong};
 use std::path::{Path, PathBuf};
 use std::slice;
 
-use super::{ColorName, X11Error, X11Result};
+use super::{ColorName, Rule, X11Error, X11Result};
 
 use cplwm_api::types::{Geometry, Screen};
 
@@ -321,3 +321,101 @@ pub fn respect_hints(geometry: &mut Geometry, hints: &xlib::XSizeHints) {
 
     trace!("GEOMETRY AFTER HINTS: {}", geometry);
 }
+
+/// Return the action chosen by the given matching rules.
+///
+/// The `action` function returns the action set by a rule, e.g. `|rule|
+/// rule.workspace`. When multiple rules set it, the last one wins. Return
+/// `None` when none of the rules set it.
+pub fn rule_action<T, F>(rules: &[&Rule], action: F) -> Option<T>
+    where F: Fn(&Rule) -> Option<T>
+{
+    rules.iter().filter_map(|rule| action(rule)).last()
+}
+
+/// Return the given screen geometry without the space the given strut
+/// reserves.
+///
+/// The strut consists of the 12 values of [`_NET_WM_STRUT_PARTIAL`]: the
+/// sizes of the space reserved along the left, right, top and bottom edges of
+/// the root window (of the given size), followed by the start and end of each
+/// of them. Only the reserved space that overlaps with the screen is taken
+/// off. When nothing of the screen would be left, it is returned unchanged.
+///
+/// [`_NET_WM_STRUT_PARTIAL`]: https://developer.gnome.org/wm-spec/
+pub fn reserve_strut(screen: Geometry, strut: &[c_int], root: Screen) -> Geometry {
+    let mut left = screen.x;
+    let mut top = screen.y;
+    let mut right = screen.x + screen.width as c_int;
+    let mut bottom = screen.y + screen.height as c_int;
+    // Whether the reserved space from `start` to `end` (inclusive) overlaps
+    // with the screen from `low` to `high` (exclusive)
+    let overlaps = |start: c_int, end: c_int, low: c_int, high: c_int| start < high && end >= low;
+    if strut[0] > 0 && overlaps(strut[4], strut[5], top, bottom) {
+        left = max(left, strut[0]);
+    }
+    if strut[1] > 0 && overlaps(strut[6], strut[7], top, bottom) {
+        right = min(right, root.width as c_int - strut[1]);
+    }
+    if strut[2] > 0 && overlaps(strut[8], strut[9], left, right) {
+        top = max(top, strut[2]);
+    }
+    if strut[3] > 0 && overlaps(strut[10], strut[11], left, right) {
+        bottom = min(bottom, root.height as c_int - strut[3]);
+    }
+    if left >= right || top >= bottom {
+        return screen;
+    }
+    Geometry {
+        x: left,
+        y: top,
+        width: (right - left) as c_uint,
+        height: (bottom - top) as c_uint,
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn test_reserve_strut() {
+    let root = Screen {
+        width: 1600,
+        height: 600,
+    };
+    let left_screen = Geometry {
+        x: 0,
+        y: 0,
+        width: 800,
+        height: 600,
+    };
+    let right_screen = Geometry {
+        x: 800,
+        y: 0,
+        width: 800,
+        height: 600,
+    };
+    // A panel along the whole top edge
+    let top_panel = [0, 0, 20, 0, 0, 0, 0, 0, 0, 1599, 0, 0];
+    assert_eq!(Geometry {
+                   x: 0,
+                   y: 20,
+                   width: 800,
+                   height: 580,
+               },
+               reserve_strut(left_screen, &top_panel, root));
+    // A panel at the bottom of the right screen only
+    let bottom_panel = [0, 0, 0, 30, 0, 0, 0, 0, 0, 0, 800, 1599];
+    assert_eq!(left_screen, reserve_strut(left_screen, &bottom_panel, root));
+    assert_eq!(Geometry {
+                   x: 800,
+                   y: 0,
+                   width: 800,
+                   height: 570,
+               },
+               reserve_strut(right_screen, &bottom_panel, root));
+    // A panel at the left edge of the root window is not on the right screen
+    let left_panel = [40, 0, 0, 0, 0, 599, 0, 0, 0, 0, 0, 0];
+    assert_eq!(right_screen, reserve_strut(right_screen, &left_panel, root));
+    // Nothing would be left of the screen
+    let huge_panel = [0, 0, 600, 0, 0, 0, 0, 0, 0, 1599, 0, 0];
+    assert_eq!(left_screen, reserve_strut(left_screen, &huge_panel, root));
+}